@@ -24,6 +24,7 @@ echo "Writing output to ${BASE_SRC_PATH}/q${DAY}.rs ..."
 cat >"${BASE_SRC_PATH}/q${DAY}.rs" <<EOF
 #![allow(dead_code, unused_variables)]
 
+use crate::solution::solution;
 use crate::utils::parser::{parse, FileLines};
 
 struct Input {
@@ -38,16 +39,18 @@ impl TryFrom<FileLines> for Input {
     }
 }
 
-fn part_1(input_file: &str) -> std::io::Result<usize> {
+pub fn part_1(input_file: &str) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
     Ok(0)
 }
 
-fn part_2(input_file: &str) -> std::io::Result<usize> {
+pub fn part_2(input_file: &str) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
     Ok(0)
 }
 
+solution!($((10#${DAY})));
+
 #[cfg(test)]
 mod tests {
     use super::{part_1, part_2};
@@ -82,6 +85,13 @@ mod tests {
 EOF
 
 echo "Updating ${BASE_SRC_PATH}/mod.rs ..."
-echo "mod q${DAY};" >> "${BASE_SRC_PATH}/mod.rs"
+# Only the DAYS list gets the new day; other arrays like EXPORTS are left alone
+awk -v day="${DAY}" '
+    !added && /^$/ { print "mod q" day ";"; added = 1 }
+    /^pub const DAYS/ { in_days = 1 }
+    in_days && /^\];$/ { print "    Day::of::<q" day "::Solver>(),"; in_days = 0 }
+    { print }
+' "${BASE_SRC_PATH}/mod.rs" > "${BASE_SRC_PATH}/mod.rs.tmp"
+mv "${BASE_SRC_PATH}/mod.rs.tmp" "${BASE_SRC_PATH}/mod.rs"
 
 echo "Done!"
@@ -0,0 +1,533 @@
+use crate::compare::panic_message;
+use crate::registry::{days, Day, AUTHORS};
+use crate::solution::Answer;
+use crate::utils::parser::error;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::panic;
+use std::path::Path;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+pub const DEFAULT_RUNS: usize = 10;
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+pub const DEFAULT_OUTPUT: &str = "bench.json";
+
+/// The system allocator, counting allocations and tracking the peak number of
+/// bytes in use so a benchmark can report how much memory a part needed.
+///
+/// The counters are process wide: a part that timed out keeps running in the
+/// background and will inflate the allocations of the parts measured after it.
+/// It is only the global allocator outside of tests, so under `cargo test`
+/// `measure` reports no allocations.
+pub struct CountingAlloc;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = System.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+            record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+fn record_alloc(size: usize) {
+    let allocated = ALLOCATED.fetch_add(size, Ordering::Relaxed) + size;
+    PEAK.fetch_max(allocated, Ordering::Relaxed);
+    ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Sample {
+    pub time: Duration,
+    /// Most bytes in use at once during the run, above what was in use before.
+    pub peak_bytes: usize,
+    pub allocations: usize,
+}
+
+/// Runs `f` once, timing it and counting its allocations.
+pub fn measure<T>(f: impl FnOnce() -> T) -> (T, Sample) {
+    let baseline = ALLOCATED.load(Ordering::Relaxed);
+    PEAK.store(baseline, Ordering::Relaxed);
+    let allocations = ALLOCATIONS.load(Ordering::Relaxed);
+    let start = Instant::now();
+    let result = f();
+    let time = start.elapsed();
+    let sample = Sample {
+        time,
+        peak_bytes: PEAK.load(Ordering::Relaxed).saturating_sub(baseline),
+        allocations: ALLOCATIONS.load(Ordering::Relaxed) - allocations,
+    };
+    (result, sample)
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Stats {
+    pub runs: usize,
+    pub min: Duration,
+    pub median: Duration,
+    pub max: Duration,
+    pub peak_bytes: usize,
+    pub allocations: usize,
+}
+
+impl Stats {
+    /// Summarises a non-empty set of samples. Allocations are per run.
+    pub fn of(samples: &[Sample]) -> Option<Self> {
+        let mut times: Vec<Duration> = samples.iter().map(|s| s.time).collect();
+        times.sort();
+        let median = match times.len() {
+            0 => return None,
+            n if n % 2 == 1 => times[n / 2],
+            n => (times[n / 2 - 1] + times[n / 2]) / 2,
+        };
+        Some(Stats {
+            runs: times.len(),
+            min: times[0],
+            median,
+            max: times[times.len() - 1],
+            peak_bytes: samples.iter().map(|s| s.peak_bytes).max()?,
+            allocations: samples.iter().map(|s| s.allocations).max()?,
+        })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Outcome {
+    Measured(Answer, Stats),
+    Failed(String),
+    Panicked(String),
+    /// The first run did not finish within the timeout.
+    TimedOut,
+    NoInput,
+}
+
+#[derive(Debug)]
+pub struct Benchmark {
+    pub author: &'static str,
+    pub day: u8,
+    pub part: u8,
+    pub input_file: Option<String>,
+    pub outcome: Outcome,
+}
+
+pub struct Options {
+    pub authors: Vec<&'static str>,
+    pub day: Option<u8>,
+    pub runs: usize,
+    pub timeout: Duration,
+    pub output: String,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Options {
+            authors: AUTHORS.to_vec(),
+            day: None,
+            runs: DEFAULT_RUNS,
+            timeout: DEFAULT_TIMEOUT,
+            output: String::from(DEFAULT_OUTPUT),
+        }
+    }
+}
+
+impl Options {
+    /// Parses `[ gee | roar ] [ <day> ] [ --runs <n> ] [ --timeout <secs> ] [ --out <file> ]`.
+    pub fn parse(args: &[String]) -> io::Result<Self> {
+        let mut options = Options::default();
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            match arg.as_str() {
+                "--runs" | "--timeout" | "--out" => {
+                    let Some(value) = args.next() else {
+                        return error(&format!("Missing value for {}", arg));
+                    };
+                    match arg.as_str() {
+                        "--runs" => options.runs = parse_number(arg, value)?,
+                        "--timeout" => {
+                            options.timeout = Duration::from_secs(parse_number(arg, value)?)
+                        }
+                        _ => options.output = value.clone(),
+                    }
+                }
+                author if AUTHORS.contains(&author) => {
+                    options.authors = AUTHORS.iter().copied().filter(|a| *a == author).collect()
+                }
+                day => options.day = Some(parse_number("day", day)?),
+            }
+        }
+        if options.runs == 0 {
+            return error("--runs must be at least 1");
+        }
+        Ok(options)
+    }
+}
+
+fn parse_number<T: std::str::FromStr>(name: &str, value: &str) -> io::Result<T> {
+    match value.parse() {
+        Ok(n) => Ok(n),
+        Err(_) => error(&format!("Bad {}: {}", name.trim_start_matches('-'), value)),
+    }
+}
+
+/// Each author is benchmarked against their own puzzle input. Most days
+/// share one file between both parts, but roar's day 1 has one per part.
+pub fn input_file(author: &str, day: u8, part: u8) -> Option<String> {
+    [
+        format!("input/{}/q{:02}_input.txt", author, day),
+        format!("input/{}/q{}-p{}-input.txt", author, day, part),
+    ]
+    .into_iter()
+    .find(|f| Path::new(f).exists())
+}
+
+pub fn bench_all(options: &Options) -> Vec<Benchmark> {
+    let mut benchmarks = Vec::new();
+    for author in &options.authors {
+        let days = days(author).unwrap_or_default();
+        for day in days
+            .iter()
+            .filter(|d| options.day.is_none_or(|n| n == d.day))
+        {
+            for part in [1, 2] {
+                let input_file = input_file(author, day.day, part);
+                let outcome = match &input_file {
+                    Some(input_file) => {
+                        bench_part(day, part, input_file, options.runs, options.timeout)
+                    }
+                    None => Outcome::NoInput,
+                };
+                benchmarks.push(Benchmark {
+                    author,
+                    day: day.day,
+                    part,
+                    input_file,
+                    outcome,
+                });
+            }
+        }
+    }
+    benchmarks
+}
+
+/// Runs a part up to `runs` times on its own thread. Each run gets `timeout`
+/// to finish; if a later run is too slow the runs so far are still reported.
+pub fn bench_part(
+    day: &Day,
+    part: u8,
+    input_file: &str,
+    runs: usize,
+    timeout: Duration,
+) -> Outcome {
+    let solve = if part == 1 { day.part_1 } else { day.part_2 };
+    let (sender, receiver) = mpsc::channel();
+    let input_file = String::from(input_file);
+    thread::spawn(move || {
+        for _ in 0..runs {
+            let result = panic::catch_unwind(|| measure(|| solve(&input_file)));
+            let run = match result {
+                Ok((Ok(answer), sample)) => Ok((answer, sample)),
+                Ok((Err(e), _)) => Err(Outcome::Failed(e.to_string())),
+                Err(payload) => Err(Outcome::Panicked(panic_message(payload))),
+            };
+            let stop = run.is_err();
+            if sender.send(run).is_err() || stop {
+                break;
+            }
+        }
+    });
+
+    let mut answer = None;
+    let mut samples = Vec::new();
+    while samples.len() < runs {
+        match receiver.recv_timeout(timeout) {
+            Ok(Ok((a, sample))) => {
+                answer = Some(a);
+                samples.push(sample);
+            }
+            Ok(Err(outcome)) => return outcome,
+            Err(_) => break,
+        }
+    }
+    match (answer, Stats::of(&samples)) {
+        (Some(answer), Some(stats)) => Outcome::Measured(answer, stats),
+        _ => Outcome::TimedOut,
+    }
+}
+
+fn status(outcome: &Outcome) -> &'static str {
+    match outcome {
+        Outcome::Measured(..) => "ok",
+        Outcome::Failed(_) => "error",
+        Outcome::Panicked(_) => "panic",
+        Outcome::TimedOut => "timeout",
+        Outcome::NoInput => "no input",
+    }
+}
+
+pub fn summary(benchmarks: &[Benchmark]) -> String {
+    let mut table = format!(
+        "{:<6} | {:>3} | {:>4} | {:>4} | {:>12} | {:>12} | {:>12} | {:>12} | {:>10} | Status\n",
+        "Author", "Day", "Part", "Runs", "Min", "Median", "Max", "Peak bytes", "Allocs"
+    );
+    table.push_str(&format!("{}\n", "-".repeat(108)));
+    for b in benchmarks {
+        let _ = match &b.outcome {
+            Outcome::Measured(_, s) => writeln!(
+                table,
+                "{:<6} | {:>3} | {:>4} | {:>4} | {:>12} | {:>12} | {:>12} | {:>12} | {:>10} | ok",
+                b.author,
+                b.day,
+                b.part,
+                s.runs,
+                format!("{:.3?}", s.min),
+                format!("{:.3?}", s.median),
+                format!("{:.3?}", s.max),
+                s.peak_bytes,
+                s.allocations
+            ),
+            outcome => writeln!(
+                table,
+                "{:<6} | {:>3} | {:>4} | {:>4} | {:>12} | {:>12} | {:>12} | {:>12} | {:>10} | {}",
+                b.author,
+                b.day,
+                b.part,
+                "",
+                "",
+                "",
+                "",
+                "",
+                "",
+                status(outcome)
+            ),
+        };
+    }
+    table
+}
+
+/// Renders the benchmarks as a JSON array with one result per line, in a fixed
+/// order, so two runs can be compared with a plain `diff`. Times are in
+/// nanoseconds.
+pub fn to_json(benchmarks: &[Benchmark]) -> String {
+    let mut json = String::from("[\n");
+    for (i, b) in benchmarks.iter().enumerate() {
+        let _ = write!(
+            json,
+            "  {{\"author\": {}, \"day\": {}, \"part\": {}, \"input\": {}, \"status\": {}",
+            quote(b.author),
+            b.day,
+            b.part,
+            b.input_file.as_deref().map_or(String::from("null"), quote),
+            quote(status(&b.outcome))
+        );
+        match &b.outcome {
+            Outcome::Measured(answer, s) => {
+                let _ = write!(
+                    json,
+                    ", \"answer\": {}, \"runs\": {}, \"min_ns\": {}, \"median_ns\": {}, \"max_ns\": {}, \"peak_bytes\": {}, \"allocations\": {}",
+                    answer,
+                    s.runs,
+                    s.min.as_nanos(),
+                    s.median.as_nanos(),
+                    s.max.as_nanos(),
+                    s.peak_bytes,
+                    s.allocations
+                );
+            }
+            Outcome::Failed(message) | Outcome::Panicked(message) => {
+                let _ = write!(json, ", \"message\": {}", quote(message));
+            }
+            Outcome::TimedOut | Outcome::NoInput => {}
+        }
+        json.push('}');
+        if i + 1 < benchmarks.len() {
+            json.push(',');
+        }
+        json.push('\n');
+    }
+    json.push_str("]\n");
+    json
+}
+
+fn quote(s: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in s.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+pub fn save(benchmarks: &[Benchmark], output: &str) -> io::Result<()> {
+    fs::write(output, to_json(benchmarks))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        bench_part, input_file, measure, quote, to_json, Benchmark, CountingAlloc, Options,
+        Outcome, Sample, Stats,
+    };
+    use crate::registry::find;
+    use crate::solution::Answer;
+    use std::alloc::{GlobalAlloc, Layout};
+    use std::time::Duration;
+
+    fn sample(millis: u64) -> Sample {
+        Sample {
+            time: Duration::from_millis(millis),
+            peak_bytes: millis as usize,
+            allocations: 1,
+        }
+    }
+
+    #[test]
+    fn bench_stats() {
+        let stats = Stats::of(&[sample(5), sample(1), sample(3)]).unwrap();
+        assert_eq!(stats.min, Duration::from_millis(1));
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(stats.max, Duration::from_millis(5));
+        assert_eq!(stats.peak_bytes, 5);
+        let stats = Stats::of(&[sample(4), sample(2)]).unwrap();
+        assert_eq!(stats.median, Duration::from_millis(3));
+        assert_eq!(Stats::of(&[]), None);
+    }
+
+    #[test]
+    fn bench_measure_counts_allocations() {
+        // Calling the allocator directly, since it isn't the global one in
+        // tests, so nothing else touches the counters
+        let layout = Layout::from_size_align(1 << 20, 8).unwrap();
+        let ((), sample) = measure(|| unsafe {
+            let small = CountingAlloc.alloc(Layout::new::<u64>());
+            let big = CountingAlloc.alloc(layout);
+            CountingAlloc.dealloc(big, layout);
+            CountingAlloc.dealloc(small, Layout::new::<u64>());
+        });
+        assert_eq!(sample.peak_bytes, (1 << 20) + 8);
+        assert_eq!(sample.allocations, 2);
+    }
+
+    #[test]
+    fn bench_part_sample() {
+        let day = find("gee", 1).unwrap();
+        let outcome = bench_part(
+            day,
+            1,
+            "input/gee/q01_sample.txt",
+            3,
+            Duration::from_secs(60),
+        );
+        let Outcome::Measured(answer, stats) = outcome else {
+            panic!("Not measured: {:?}", outcome);
+        };
+        assert_eq!(answer, Answer(142));
+        assert_eq!(stats.runs, 3);
+        assert!(stats.min <= stats.median && stats.median <= stats.max);
+        let outcome = bench_part(
+            day,
+            1,
+            "input/nobody/q01_input.txt",
+            3,
+            Duration::from_secs(60),
+        );
+        assert!(matches!(outcome, Outcome::Failed(_)));
+    }
+
+    #[test]
+    fn bench_input_file() {
+        assert_eq!(
+            input_file("gee", 1, 2).as_deref(),
+            Some("input/gee/q01_input.txt")
+        );
+        assert_eq!(
+            input_file("roar", 1, 2).as_deref(),
+            Some("input/roar/q1-p2-input.txt")
+        );
+        assert_eq!(input_file("nobody", 1, 1), None);
+    }
+
+    #[test]
+    fn bench_json() {
+        let stats = Stats::of(&[sample(2)]).unwrap();
+        let benchmarks = [
+            Benchmark {
+                author: "gee",
+                day: 1,
+                part: 1,
+                input_file: Some(String::from("input/gee/q01_input.txt")),
+                outcome: Outcome::Measured(Answer(142), stats),
+            },
+            Benchmark {
+                author: "roar",
+                day: 2,
+                part: 2,
+                input_file: None,
+                outcome: Outcome::Panicked(String::from("bad \"input\"\n")),
+            },
+        ];
+        assert_eq!(
+            to_json(&benchmarks),
+            "[\n  {\"author\": \"gee\", \"day\": 1, \"part\": 1, \"input\": \"input/gee/q01_input.txt\", \"status\": \"ok\", \"answer\": 142, \"runs\": 1, \"min_ns\": 2000000, \"median_ns\": 2000000, \"max_ns\": 2000000, \"peak_bytes\": 2, \"allocations\": 1},\n  {\"author\": \"roar\", \"day\": 2, \"part\": 2, \"input\": null, \"status\": \"panic\", \"message\": \"bad \\\"input\\\"\\n\"}\n]\n"
+        );
+        assert_eq!(quote("\u{1}"), "\"\\u0001\"");
+    }
+
+    #[test]
+    fn bench_options() {
+        let args: Vec<String> = ["roar", "21", "--runs", "3", "--out", "b.json"]
+            .iter()
+            .map(|s| s.to_string())
+            .collect();
+        let options = Options::parse(&args).unwrap();
+        assert_eq!(options.authors, vec!["roar"]);
+        assert_eq!(options.day, Some(21));
+        assert_eq!(options.runs, 3);
+        assert_eq!(options.output, "b.json");
+        assert!(Options::parse(&[String::from("--runs")]).is_err());
+        assert!(Options::parse(&[String::from("--runs"), String::from("0")]).is_err());
+    }
+}
@@ -0,0 +1,293 @@
+use crate::registry::{find, Part, AUTHORS};
+use crate::solution::Answer;
+use std::any::Any;
+use std::fmt;
+use std::panic;
+use std::path::Path;
+use std::sync::mpsc;
+use std::thread;
+use std::time::Duration;
+
+pub const DAYS: u8 = 25;
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Run {
+    Answer(Answer),
+    /// Returned `Ok(0)`, which is what the generated skeleton does.
+    Stub,
+    Failed(String),
+    Panicked(String),
+    TimedOut,
+    /// The author has no solution for this day.
+    Missing,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Status {
+    Match,
+    Mismatch,
+    Stub,
+    Panic,
+    Error,
+    Timeout,
+    Missing,
+    NoInput,
+}
+
+#[derive(Debug)]
+pub struct PartComparison {
+    pub part: u8,
+    /// One run per author, in `AUTHORS` order.
+    pub runs: Vec<Run>,
+}
+
+#[derive(Debug)]
+pub struct Comparison {
+    pub day: u8,
+    pub input_file: Option<String>,
+    pub parts: Vec<PartComparison>,
+}
+
+impl PartComparison {
+    pub fn status(&self) -> Status {
+        let has = |f: fn(&Run) -> bool| self.runs.iter().any(f);
+        if has(|r| matches!(r, Run::Panicked(_))) {
+            Status::Panic
+        } else if has(|r| matches!(r, Run::Failed(_))) {
+            Status::Error
+        } else if has(|r| matches!(r, Run::TimedOut)) {
+            Status::Timeout
+        } else if has(|r| matches!(r, Run::Stub)) {
+            Status::Stub
+        } else if has(|r| matches!(r, Run::Missing)) {
+            Status::Missing
+        } else if self.runs.windows(2).all(|w| w[0] == w[1]) {
+            Status::Match
+        } else {
+            Status::Mismatch
+        }
+    }
+}
+
+impl Comparison {
+    pub fn status(&self, part: u8) -> Status {
+        self.parts
+            .iter()
+            .find(|p| p.part == part)
+            .map_or(Status::NoInput, |p| p.status())
+    }
+}
+
+impl fmt::Display for Run {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Run::Answer(answer) => write!(f, "{}", answer),
+            Run::Stub => write!(f, "0 (stub)"),
+            Run::Failed(_) => write!(f, "error"),
+            Run::Panicked(_) => write!(f, "panic"),
+            Run::TimedOut => write!(f, "timeout"),
+            Run::Missing => write!(f, "-"),
+        }
+    }
+}
+
+impl fmt::Display for Status {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            Status::Match => "match",
+            Status::Mismatch => "MISMATCH",
+            Status::Stub => "stub",
+            Status::Panic => "panic",
+            Status::Error => "error",
+            Status::Timeout => "timeout",
+            Status::Missing => "missing",
+            Status::NoInput => "no input",
+        };
+        f.pad(s)
+    }
+}
+
+/// The input used when comparing a day without an explicit file: gee's puzzle
+/// input if there is one, otherwise roar's.
+pub fn default_input(day: u8) -> Option<String> {
+    AUTHORS
+        .iter()
+        .map(|author| format!("input/{}/q{:02}_input.txt", author, day))
+        .find(|f| Path::new(f).exists())
+}
+
+/// Runs every author's solution for both parts of `day` against `input_file`.
+pub fn compare_day(day: u8, input_file: &str, timeout: Duration) -> Comparison {
+    let parts = [1, 2]
+        .into_iter()
+        .map(|part| PartComparison {
+            part,
+            runs: AUTHORS
+                .iter()
+                .map(|author| match find(author, day) {
+                    Some(d) if part == 1 => run_part(d.part_1, input_file, timeout),
+                    Some(d) => run_part(d.part_2, input_file, timeout),
+                    None => Run::Missing,
+                })
+                .collect(),
+        })
+        .collect();
+    Comparison {
+        day,
+        input_file: Some(String::from(input_file)),
+        parts,
+    }
+}
+
+pub fn compare_all(timeout: Duration) -> Vec<Comparison> {
+    (1..=DAYS)
+        .map(|day| match default_input(day) {
+            Some(input_file) => compare_day(day, &input_file, timeout),
+            None => Comparison {
+                day,
+                input_file: None,
+                parts: Vec::new(),
+            },
+        })
+        .collect()
+}
+
+/// Runs a part on its own thread so panics can be reported and runaway
+/// solutions abandoned once `timeout` has passed.
+fn run_part(part: Part, input_file: &str, timeout: Duration) -> Run {
+    let (sender, receiver) = mpsc::channel();
+    let input_file = String::from(input_file);
+    thread::spawn(move || {
+        let result = panic::catch_unwind(|| part(&input_file));
+        let run = match result {
+            Ok(Ok(Answer(0))) => Run::Stub,
+            Ok(Ok(answer)) => Run::Answer(answer),
+            Ok(Err(e)) => Run::Failed(e.to_string()),
+            Err(payload) => Run::Panicked(panic_message(payload)),
+        };
+        let _ = sender.send(run);
+    });
+    receiver.recv_timeout(timeout).unwrap_or(Run::TimedOut)
+}
+
+pub fn panic_message(payload: Box<dyn Any + Send>) -> String {
+    if let Some(s) = payload.downcast_ref::<&str>() {
+        String::from(*s)
+    } else if let Some(s) = payload.downcast_ref::<String>() {
+        s.clone()
+    } else {
+        String::from("unknown panic")
+    }
+}
+
+pub fn summary(comparisons: &[Comparison]) -> String {
+    let mut table = format!(
+        "{:>3} | {:>4} | {:>20} | {:>20} | {:<8} | Input\n",
+        "Day", "Part", AUTHORS[0], AUTHORS[1], "Status"
+    );
+    table.push_str(&format!("{}\n", "-".repeat(96)));
+    for comparison in comparisons {
+        for part in [1, 2] {
+            let runs = comparison
+                .parts
+                .iter()
+                .find(|p| p.part == part)
+                .map(|p| p.runs.iter().map(|r| r.to_string()).collect::<Vec<_>>())
+                .unwrap_or_else(|| vec![String::new(); AUTHORS.len()]);
+            table.push_str(&format!(
+                "{:>3} | {:>4} | {:>20} | {:>20} | {:<8} | {}\n",
+                comparison.day,
+                part,
+                runs[0],
+                runs[1],
+                comparison.status(part),
+                comparison.input_file.as_deref().unwrap_or("-")
+            ));
+        }
+    }
+    for comparison in comparisons {
+        for part in &comparison.parts {
+            for (author, run) in AUTHORS.iter().zip(&part.runs) {
+                if let Run::Failed(message) | Run::Panicked(message) = run {
+                    table.push_str(&format!(
+                        "Day {} part {} ({}): {}\n",
+                        comparison.day, part.part, author, message
+                    ));
+                }
+            }
+        }
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compare_day, run_part, summary, PartComparison, Run, Status};
+    use crate::solution::Answer;
+    use std::time::Duration;
+
+    const TIMEOUT: Duration = Duration::from_secs(60);
+
+    #[test]
+    fn compare_matching_day() {
+        let comparison = compare_day(1, "input/gee/q01_sample.txt", TIMEOUT);
+        assert_eq!(comparison.status(1), Status::Match);
+        assert_eq!(
+            comparison.parts[0].runs,
+            vec![Run::Answer(Answer(142)), Run::Answer(Answer(142))]
+        );
+    }
+
+    #[test]
+    fn compare_stub_and_missing() {
+        let comparison = compare_day(25, "input/roar/q25_sample.txt", TIMEOUT);
+        assert_eq!(comparison.parts[1].runs, vec![Run::Missing, Run::Stub]);
+        assert_eq!(comparison.status(2), Status::Stub);
+    }
+
+    #[test]
+    fn compare_panic_and_error() {
+        // Neither q01 finds a digit in a map of trails
+        let comparison = compare_day(1, "input/roar/q23_sample.txt", TIMEOUT);
+        assert_eq!(comparison.status(1), Status::Error);
+        let explode = |_: &str| -> std::io::Result<Answer> { panic!("boom") };
+        assert_eq!(
+            run_part(explode, "input/roar/q23_sample.txt", TIMEOUT),
+            Run::Panicked(String::from("boom"))
+        );
+        let part = PartComparison {
+            part: 1,
+            runs: vec![
+                Run::Panicked(String::from("boom")),
+                Run::Failed(String::from("oops")),
+            ],
+        };
+        assert_eq!(part.status(), Status::Panic);
+        let comparison = compare_day(1, "input/nobody/q01_input.txt", TIMEOUT);
+        assert!(matches!(comparison.parts[0].runs[0], Run::Failed(_)));
+        let part = PartComparison {
+            part: 1,
+            runs: vec![Run::Failed(String::from("oops")), Run::Answer(Answer(1))],
+        };
+        assert_eq!(part.status(), Status::Error);
+    }
+
+    #[test]
+    fn compare_mismatch() {
+        let part = PartComparison {
+            part: 1,
+            runs: vec![Run::Answer(Answer(1)), Run::Answer(Answer(2))],
+        };
+        assert_eq!(part.status(), Status::Mismatch);
+    }
+
+    #[test]
+    fn compare_summary_lists_every_part() {
+        let comparison = compare_day(1, "input/gee/q01_sample.txt", TIMEOUT);
+        let table = summary(&[comparison]);
+        assert!(table.contains("  1 |    1 |"));
+        assert!(table.contains("  1 |    2 |"));
+    }
+}
@@ -14,7 +14,34 @@ mod q13;
 mod q14;
 mod q15;
 mod q16;
-mod q17;
+pub(crate) mod q17;
 mod q18;
 mod q19;
 mod q20;
+
+use crate::registry::{Day, Export};
+
+pub const DAYS: &[Day] = &[
+    Day::of::<q01::Solver>(),
+    Day::of::<q02::Solver>(),
+    Day::of::<q03::Solver>(),
+    Day::of::<q04::Solver>(),
+    Day::of::<q05::Solver>(),
+    Day::of::<q06::Solver>(),
+    Day::of::<q07::Solver>(),
+    Day::of::<q08::Solver>(),
+    Day::of::<q09::Solver>(),
+    Day::of::<q10::Solver>(),
+    Day::of::<q11::Solver>(),
+    Day::of::<q12::Solver>(),
+    Day::of::<q13::Solver>(),
+    Day::of::<q14::Solver>(),
+    Day::of::<q15::Solver>(),
+    Day::of::<q16::Solver>(),
+    Day::of::<q17::Solver>(),
+    Day::of::<q18::Solver>(),
+    Day::of::<q19::Solver>(),
+    Day::of::<q20::Solver>(),
+];
+
+pub const EXPORTS: &[(u8, Export)] = &[(8, q08::to_dot), (20, q20::to_dot)];
@@ -1,23 +1,31 @@
+use crate::solution::solution;
 use crate::utils::parser::FileLines;
 
-fn _part_1(input: &str) -> std::io::Result<u32> {
-    let lines = FileLines::new(input)?;
+pub fn part_1(input: &str) -> std::io::Result<u32> {
+    let mut lines = FileLines::new(input)?;
     let mut result: u32 = 0;
-    for line in lines {
-        let i1 = line.find(|c: char| c.is_ascii_digit()).unwrap();
-        let i2 = line.rfind(|c: char| c.is_ascii_digit()).unwrap();
+    while let Some(line) = lines.next() {
+        let (Some(i1), Some(i2)) = (
+            line.find(|c: char| c.is_ascii_digit()),
+            line.rfind(|c: char| c.is_ascii_digit()),
+        ) else {
+            return Err(lines.error_at(&line, &line, "a digit"));
+        };
         result += (u32::from(line.as_bytes()[i1]) - 48) * 10;
         result += u32::from(line.as_bytes()[i2]) - 48;
     }
     Ok(result)
 }
 
-fn _part_2(input: &str) -> std::io::Result<u32> {
-    let lines = FileLines::new(input)?;
+pub fn part_2(input: &str) -> std::io::Result<u32> {
+    let mut lines = FileLines::new(input)?;
     let mut result: u32 = 0;
-    for line in lines {
+    while let Some(line) = lines.next() {
         let items = _match_indexes(line.as_str());
-        result += items.first().unwrap().1 * 10 + items.last().unwrap().1;
+        let (Some(first), Some(last)) = (items.first(), items.last()) else {
+            return Err(lines.error_at(&line, &line, "a digit or digit name"));
+        };
+        result += first.1 * 10 + last.1;
     }
     Ok(result)
 }
@@ -52,9 +60,11 @@ fn _match_indexes(s: &str) -> Vec<(usize, u32)> {
     v
 }
 
+solution!(1);
+
 #[cfg(test)]
 mod tests {
-    use super::{_part_1, _part_2};
+    use super::{part_1, part_2};
 
     const INPUT: &str = "input/gee/q01_input.txt";
     const INPUT_SAMPLE: &str = "input/gee/q01_sample.txt";
@@ -62,25 +72,25 @@ mod tests {
 
     #[test]
     fn gee_q01_p1_sample() {
-        let result = _part_1(INPUT_SAMPLE);
+        let result = part_1(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 142);
     }
 
     #[test]
     fn gee_q01_p1_main() {
-        let result = _part_1(INPUT);
+        let result = part_1(INPUT);
         assert_eq!(result.unwrap(), 57346);
     }
 
     #[test]
     fn gee_q01_p2_sample() {
-        let result = _part_2(INPUT_SAMPLE2);
+        let result = part_2(INPUT_SAMPLE2);
         assert_eq!(result.unwrap(), 281);
     }
 
     #[test]
     fn gee_q01_p2_main() {
-        let result = _part_2(INPUT);
+        let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 57345);
     }
 }
@@ -1,3 +1,4 @@
+use crate::solution::solution;
 use crate::utils::parser::FileLines;
 
 struct Input {
@@ -18,35 +19,36 @@ struct CubeSet {
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(_lines: FileLines) -> Result<Self, Self::Error> {
+    fn try_from(mut _lines: FileLines) -> Result<Self, Self::Error> {
         let mut games: Vec<Game> = Vec::new();
-        for line in _lines {
-            if let Some((id, view_line)) = line.split_once(": ") {
-                let mut _views: Vec<CubeSet> = Vec::new();
-                for view in view_line.split("; ") {
-                    let mut _red = 0;
-                    let mut _green = 0;
-                    let mut _blue = 0;
-                    for color in view.split(", ") {
-                        if color.ends_with(" red") {
-                            _red = color.strip_suffix(" red").unwrap().parse().unwrap();
-                        } else if color.ends_with(" green") {
-                            _green = color.strip_suffix(" green").unwrap().parse().unwrap();
-                        } else if color.ends_with(" blue") {
-                            _blue = color.strip_suffix(" blue").unwrap().parse().unwrap();
-                        }
+        while let Some(line) = _lines.next() {
+            let (id, view_line) = _lines.split_once(&line, &line, ": ")?;
+            let mut _views: Vec<CubeSet> = Vec::new();
+            for view in view_line.split("; ") {
+                let mut _red = 0;
+                let mut _green = 0;
+                let mut _blue = 0;
+                for color in view.split(", ") {
+                    let (count, name) = _lines.split_once(&line, color, " ")?;
+                    let count = _lines.parse_at(&line, count, "a cube count")?;
+                    match name {
+                        "red" => _red = count,
+                        "green" => _green = count,
+                        "blue" => _blue = count,
+                        _ => return Err(_lines.error_at(&line, name, "red, green or blue")),
                     }
-                    _views.push(CubeSet {
-                        _red,
-                        _green,
-                        _blue,
-                    });
                 }
-                games.push(Game {
-                    _id: id.strip_prefix("Game ").unwrap().parse().unwrap(),
-                    _views,
-                })
+                _views.push(CubeSet {
+                    _red,
+                    _green,
+                    _blue,
+                });
             }
+            let id = _lines.strip_prefix(&line, id, "Game ")?;
+            games.push(Game {
+                _id: _lines.parse_at(&line, id, "a game id")?,
+                _views,
+            })
         }
         Ok(Input { _games: games })
     }
@@ -78,7 +80,7 @@ fn _power(cube_set: &CubeSet) -> u32 {
     cube_set._red * cube_set._green * cube_set._blue
 }
 
-fn _part_1(input: &str) -> std::io::Result<u32> {
+pub fn part_1(input: &str) -> std::io::Result<u32> {
     let input = Input::try_from(FileLines::new(input)?)?;
     const LIMITS: CubeSet = CubeSet {
         _red: 12,
@@ -93,7 +95,7 @@ fn _part_1(input: &str) -> std::io::Result<u32> {
         .sum())
 }
 
-fn _part_2(input: &str) -> std::io::Result<u32> {
+pub fn part_2(input: &str) -> std::io::Result<u32> {
     let input = Input::try_from(FileLines::new(input)?)?;
     Ok(input
         ._games
@@ -103,34 +105,36 @@ fn _part_2(input: &str) -> std::io::Result<u32> {
         .sum())
 }
 
+solution!(2);
+
 #[cfg(test)]
 mod tests {
-    use super::{_part_1, _part_2};
+    use super::{part_1, part_2};
 
     const INPUT: &str = "input/gee/q02_input.txt";
     const INPUT_SAMPLE: &str = "input/gee/q02_sample.txt";
 
     #[test]
     fn gee_q02_p1_sample() {
-        let result = _part_1(INPUT_SAMPLE);
+        let result = part_1(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 8);
     }
 
     #[test]
     fn gee_q02_p1_main() {
-        let result = _part_1(INPUT);
+        let result = part_1(INPUT);
         assert_eq!(result.unwrap(), 2105);
     }
 
     #[test]
     fn gee_q02_p2_sample() {
-        let result = _part_2(INPUT_SAMPLE);
+        let result = part_2(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 2286);
     }
 
     #[test]
     fn gee_q02_p2_main() {
-        let result = _part_2(INPUT);
+        let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 72422);
     }
 }
@@ -1,3 +1,4 @@
+use crate::solution::solution;
 use crate::utils::parser::FileLines;
 use std::cmp::max;
 use std::collections::HashSet;
@@ -18,10 +19,16 @@ struct Location {
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(_lines: FileLines) -> Result<Self, Self::Error> {
+    fn try_from(mut _lines: FileLines) -> Result<Self, Self::Error> {
         let mut _numbers = Vec::new();
         let mut _symbols = Vec::new();
-        for (i, line) in _lines.enumerate() {
+        while let Some(line) = _lines.next() {
+            let i = _lines.line_number() - 1;
+            let number = |lines: &FileLines, start: usize, digits: &str| {
+                digits
+                    .parse()
+                    .map_err(|_| lines.error_at_column(start + 1, "a number", digits))
+            };
             let mut current_number = String::new();
             let mut number_start = 0;
             for (j, c) in line.chars().enumerate() {
@@ -39,7 +46,8 @@ impl TryFrom<FileLines> for Input {
                                 _start: number_start,
                                 _end: j - 1,
                             };
-                            _numbers.push((current_number.parse().unwrap(), location));
+                            _numbers
+                                .push((number(&_lines, number_start, &current_number)?, location));
                             current_number.clear();
                         }
                         if c != '.' {
@@ -58,7 +66,7 @@ impl TryFrom<FileLines> for Input {
                     _start: number_start,
                     _end: line.len() - 1,
                 };
-                _numbers.push((current_number.parse().unwrap(), location));
+                _numbers.push((number(&_lines, number_start, &current_number)?, location));
             }
         }
         Ok(Input { _numbers, _symbols })
@@ -112,7 +120,7 @@ impl Location {
     }
 }
 
-fn _part_1(input_file: &str) -> std::io::Result<u32> {
+pub fn part_1(input_file: &str) -> std::io::Result<u32> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
     let symbols = input._symbol_reach();
     Ok(input
@@ -123,7 +131,7 @@ fn _part_1(input_file: &str) -> std::io::Result<u32> {
         .sum())
 }
 
-fn _part_2(input_file: &str) -> std::io::Result<u32> {
+pub fn part_2(input_file: &str) -> std::io::Result<u32> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
     Ok(input
         ._symbols
@@ -132,34 +140,36 @@ fn _part_2(input_file: &str) -> std::io::Result<u32> {
         .sum())
 }
 
+solution!(3);
+
 #[cfg(test)]
 mod tests {
-    use super::{_part_1, _part_2};
+    use super::{part_1, part_2};
 
     const INPUT: &str = "input/gee/q03_input.txt";
     const INPUT_SAMPLE: &str = "input/gee/q03_sample.txt";
 
     #[test]
     fn gee_q03_p1_sample() {
-        let result = _part_1(INPUT_SAMPLE);
+        let result = part_1(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 4361);
     }
 
     #[test]
     fn gee_q03_p1_main() {
-        let result = _part_1(INPUT);
+        let result = part_1(INPUT);
         assert_eq!(result.unwrap(), 507214);
     }
 
     #[test]
     fn gee_q03_p2_sample() {
-        let result = _part_2(INPUT_SAMPLE);
+        let result = part_2(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 467835);
     }
 
     #[test]
     fn gee_q03_p2_main() {
-        let result = _part_2(INPUT);
+        let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 72553319);
     }
 }
@@ -1,5 +1,6 @@
 #![allow(dead_code)]
 
+use crate::solution::solution;
 use crate::utils::parser::FileLines;
 use std::collections::{HashMap, HashSet};
 
@@ -17,21 +18,23 @@ struct Card {
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
-        let cards = lines
-            .map(|line| {
-                let (win, have) = line.split_once(": ").unwrap().1.split_once(" | ").unwrap();
-                Card {
-                    winning: HashSet::from_iter(
-                        win.split_whitespace().map(|s| s.parse::<u32>().unwrap()),
-                    ),
-                    numbers: have
-                        .split_whitespace()
-                        .map(|s| s.parse::<u32>().unwrap())
-                        .collect(),
-                }
-            })
-            .collect();
+    fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
+        let mut cards = Vec::new();
+        while let Some(line) = lines.next() {
+            let numbers = lines.split_once(&line, &line, ": ")?.1;
+            let (win, have) = lines.split_once(&line, numbers, " | ")?;
+            let parse = |s| lines.parse_at::<u32>(&line, s, "a number");
+            cards.push(Card {
+                winning: win
+                    .split_whitespace()
+                    .map(parse)
+                    .collect::<Result<_, _>>()?,
+                numbers: have
+                    .split_whitespace()
+                    .map(parse)
+                    .collect::<Result<_, _>>()?,
+            });
+        }
         Ok(Input { cards })
     }
 }
@@ -83,16 +86,18 @@ impl Card {
     }
 }
 
-fn part_1(input_file: &str) -> std::io::Result<u32> {
+pub fn part_1(input_file: &str) -> std::io::Result<u32> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
     Ok(input.cards.iter().map(|c| c.score()).sum())
 }
 
-fn part_2(input_file: &str) -> std::io::Result<usize> {
+pub fn part_2(input_file: &str) -> std::io::Result<usize> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
     Ok(input.total_scratchcards())
 }
 
+solution!(4);
+
 #[cfg(test)]
 mod tests {
     use super::{part_1, part_2};
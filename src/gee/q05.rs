@@ -1,143 +1,188 @@
 #![allow(dead_code)]
 
-use crate::utils::parser::FileLines;
+use crate::solution::solution;
+use crate::utils::parser::{error, FileLines, Headed};
+use crate::utils::ranges::{chain_apply, chain_get, RangeMap, RangeSet};
+use std::str::FromStr;
 
 #[derive(Debug)]
 struct Input {
     seeds: Vec<u64>,
-    seed_to_soil: Vec<(u64, u64, u64)>,
-    soil_to_fertilizer: Vec<(u64, u64, u64)>,
-    fertilizer_to_water: Vec<(u64, u64, u64)>,
-    water_to_light: Vec<(u64, u64, u64)>,
-    light_to_temperature: Vec<(u64, u64, u64)>,
-    temperature_to_humidity: Vec<(u64, u64, u64)>,
-    humidity_to_location: Vec<(u64, u64, u64)>,
+    /// Why the seeds can't be read as ranges, such as a last seed with no
+    /// range length after it. Only part 2 reads them that way.
+    bad_seed_ranges: Option<std::io::Error>,
+    seed_to_soil: RangeMap,
+    soil_to_fertilizer: RangeMap,
+    fertilizer_to_water: RangeMap,
+    water_to_light: RangeMap,
+    light_to_temperature: RangeMap,
+    temperature_to_humidity: RangeMap,
+    humidity_to_location: RangeMap,
 }
 
-impl TryFrom<FileLines> for Input {
+/// The almanac's maps, in the order a seed goes through them.
+const MAPS: [&str; 7] = [
+    "seed-to-soil map",
+    "soil-to-fertilizer map",
+    "fertilizer-to-water map",
+    "water-to-light map",
+    "light-to-temperature map",
+    "temperature-to-humidity map",
+    "humidity-to-location map",
+];
+
+/// A map section's header, as an index into `MAPS`.
+struct MapName(usize);
+
+impl FromStr for MapName {
+    type Err = ();
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        MAPS.iter().position(|&m| m == s).map(MapName).ok_or(())
+    }
+}
+
+struct Seeds(Vec<u64>, Option<std::io::Error>);
+
+impl TryFrom<FileLines> for Seeds {
     type Error = std::io::Error;
 
     fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
-        let seeds = lines
-            .next()
-            .unwrap()
-            .strip_prefix("seeds: ")
-            .unwrap()
+        let line = lines.expect_line("seeds")?;
+        let seeds: Vec<u64> = lines
+            .strip_prefix(&line, &line, "seeds: ")?
             .split(' ')
-            .map(|s| s.parse().unwrap())
-            .collect();
-        let mut seed_to_soil = Vec::new();
-        let mut soil_to_fertilizer = Vec::new();
-        let mut fertilizer_to_water = Vec::new();
-        let mut water_to_light = Vec::new();
-        let mut light_to_temperature = Vec::new();
-        let mut temperature_to_humidity = Vec::new();
-        let mut humidity_to_location = Vec::new();
-        lines.next();
-        let mut map: &mut Vec<(u64, u64, u64)> = &mut seed_to_soil;
-        for line in lines {
-            match line.as_str() {
-                "seed-to-soil map:" => map = &mut seed_to_soil,
-                "soil-to-fertilizer map:" => map = &mut soil_to_fertilizer,
-                "fertilizer-to-water map:" => map = &mut fertilizer_to_water,
-                "water-to-light map:" => map = &mut water_to_light,
-                "light-to-temperature map:" => map = &mut light_to_temperature,
-                "temperature-to-humidity map:" => map = &mut temperature_to_humidity,
-                "humidity-to-location map:" => map = &mut humidity_to_location,
-                "" => (),
-                s => {
-                    let v: Vec<u64> = s.split(' ').map(|s| s.parse().unwrap()).collect();
-                    map.push((v[0], v[1], v[2]));
-                }
+            .map(|s| lines.parse_at(&line, s, "a seed number"))
+            .collect::<Result<_, _>>()?;
+        let bad_ranges = if seeds.len() % 2 == 1 {
+            Some(lines.error_at(&line, &line[line.len()..], "a seed range length"))
+        } else if seeds
+            .chunks_exact(2)
+            .any(|p| p[0].checked_add(p[1]).is_none())
+        {
+            Some(lines.error_at(&line, &line, "seed ranges that fit in 64 bits"))
+        } else {
+            None
+        };
+        Ok(Seeds(seeds, bad_ranges))
+    }
+}
+
+/// A map of (destination, source, length) lines.
+struct Map(RangeMap);
+
+impl TryFrom<FileLines> for Map {
+    type Error = std::io::Error;
+
+    fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
+        let map = lines.parse_each(|lines, line| {
+            let v = line
+                .split(' ')
+                .map(|n| lines.parse_at(line, n, "a number"))
+                .collect::<Result<Vec<u64>, _>>()?;
+            let [destination, source, length] = v[..] else {
+                return Err(lines.error_at(line, line, "three numbers"));
+            };
+            if source.max(destination).checked_add(length).is_none() {
+                return Err(lines.error_at(line, line, "ranges that fit in 64 bits"));
             }
+            Ok((destination, source, length))
+        })?;
+        Ok(Map(RangeMap::new(map)?))
+    }
+}
+
+impl TryFrom<FileLines> for Input {
+    type Error = std::io::Error;
+
+    fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
+        let mut sections = lines.sections();
+        let Seeds(seeds, bad_seed_ranges) = sections.parse_next("seeds")?;
+        let mut maps: [Option<RangeMap>; 7] = Default::default();
+        for section in sections.by_ref() {
+            let Headed {
+                header: MapName(i),
+                body: Map(map),
+            } = Headed::try_from(section)?;
+            maps[i] = Some(map);
+        }
+        if let Some(i) = maps.iter().position(Option::is_none) {
+            // there are no sections left, so this reports the end of the input
+            sections.expect(MAPS[i])?;
         }
-        seed_to_soil.sort_by_key(|x| x.1);
-        soil_to_fertilizer.sort_by_key(|x| x.1);
-        fertilizer_to_water.sort_by_key(|x| x.1);
-        water_to_light.sort_by_key(|x| x.1);
-        light_to_temperature.sort_by_key(|x| x.1);
-        temperature_to_humidity.sort_by_key(|x| x.1);
-        humidity_to_location.sort_by_key(|x| x.1);
+        // Fields are initialised in the order written, which is `MAPS` order.
+        let mut maps = maps.into_iter().flatten();
+        let mut next = || maps.next().unwrap_or_default();
         Ok(Input {
             seeds,
-            seed_to_soil,
-            soil_to_fertilizer,
-            fertilizer_to_water,
-            water_to_light,
-            light_to_temperature,
-            temperature_to_humidity,
-            humidity_to_location,
+            bad_seed_ranges,
+            seed_to_soil: next(),
+            soil_to_fertilizer: next(),
+            fertilizer_to_water: next(),
+            water_to_light: next(),
+            light_to_temperature: next(),
+            temperature_to_humidity: next(),
+            humidity_to_location: next(),
         })
     }
 }
 
 impl Input {
+    fn maps(&self) -> [&RangeMap; 7] {
+        [
+            &self.seed_to_soil,
+            &self.soil_to_fertilizer,
+            &self.fertilizer_to_water,
+            &self.water_to_light,
+            &self.light_to_temperature,
+            &self.temperature_to_humidity,
+            &self.humidity_to_location,
+        ]
+    }
+
     fn location(&self, seed: u64) -> u64 {
-        let mut result = seed;
-        result = self.lookup(result, &self.seed_to_soil);
-        result = self.lookup(result, &self.soil_to_fertilizer);
-        result = self.lookup(result, &self.fertilizer_to_water);
-        result = self.lookup(result, &self.water_to_light);
-        result = self.lookup(result, &self.light_to_temperature);
-        result = self.lookup(result, &self.temperature_to_humidity);
-        result = self.lookup(result, &self.humidity_to_location);
-        result
+        chain_get(self.maps(), seed)
     }
 
-    fn lookup(&self, source: u64, map: &Vec<(u64, u64, u64)>) -> u64 {
-        let (mut a, mut b) = (0, map.len() - 1);
-        while a <= b {
-            let i = (a + b) / 2;
-            let (dest, src, range) = map[i];
-            if src <= source && source < src + range {
-                return dest + source - src;
-            } else if source < src {
-                if i == 0 {
-                    break;
-                }
-                b = i - 1;
-            } else if source > src {
-                a = i + 1;
-            }
+    fn lowest_location(&self) -> std::io::Result<u64> {
+        match self.seeds.iter().map(|&s| self.location(s)).min() {
+            Some(location) => Ok(location),
+            None => error("No seeds"),
         }
-        source
     }
-}
 
-fn part_1(input_file: &str) -> std::io::Result<u64> {
-    let input = Input::try_from(FileLines::new(input_file)?)?;
-    Ok(input
-        .seeds
-        .iter()
-        .map(|&s| input.location(s))
-        .min()
-        .unwrap())
-}
-
-fn part_2(input_file: &str) -> std::io::Result<u64> {
-    let input = Input::try_from(FileLines::new(input_file)?)?;
-    let mut iter = input.seeds.iter();
-    let mut pairs: Vec<(u64, u64)> = Vec::new();
-    while let Some(&s) = iter.next() {
-        if let Some(&r) = iter.next() {
-            pairs.push((s, r));
+    /// The lowest location for any seed when the seeds are (start, length)
+    /// ranges.
+    fn lowest_range_location(&mut self) -> std::io::Result<u64> {
+        if let Some(e) = self.bad_seed_ranges.take() {
+            return Err(e);
         }
-    }
-    let mut min_location = u64::MAX;
-    for (i, range) in &pairs {
-        for j in 0..*range {
-            let location = input.location(i + j);
-            if location < min_location {
-                min_location = location;
-            }
+        let seeds: RangeSet = self
+            .seeds
+            .chunks_exact(2)
+            .map(|p| p[0]..p[0] + p[1])
+            .collect();
+        match chain_apply(self.maps(), seeds).min() {
+            Some(location) => Ok(location),
+            None => error("No seeds"),
         }
     }
-    Ok(min_location)
 }
 
+pub fn part_1(input_file: &str) -> std::io::Result<u64> {
+    Input::try_from(FileLines::new(input_file)?)?.lowest_location()
+}
+
+pub fn part_2(input_file: &str) -> std::io::Result<u64> {
+    Input::try_from(FileLines::new(input_file)?)?.lowest_range_location()
+}
+
+solution!(5);
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{part_1, part_2, Input, MAPS};
+    use crate::utils::parser::{FileLines, ParseError, Source};
 
     const INPUT: &str = "input/gee/q05_input.txt";
     const INPUT_SAMPLE: &str = "input/gee/q05_sample.txt";
@@ -160,10 +205,54 @@ mod tests {
         assert_eq!(result.unwrap(), 46);
     }
 
-    #[ignore]
     #[test]
     fn gee_q05_p2_main() {
         let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 41222968);
     }
+
+    /// An almanac with `seeds` and the given seed-to-soil lines, where every
+    /// other map is a single entry.
+    fn almanac(seeds: &str, seed_to_soil: &str) -> std::io::Result<Input> {
+        let mut text = format!("seeds: {}\n\nseed-to-soil map:\n{}\n", seeds, seed_to_soil);
+        for name in &MAPS[1..] {
+            text.push_str(&format!("\n{}:\n0 0 1\n", name));
+        }
+        Input::try_from(FileLines::open(Source::text(text))?)
+    }
+
+    #[test]
+    fn gee_q05_unpaired_seed() {
+        let mut input = almanac("79 14 55 13", "50 98 2").unwrap();
+        assert!(input.bad_seed_ranges.is_none());
+        assert_eq!(input.lowest_range_location().unwrap(), 55);
+        let mut input = almanac("79 14 55", "50 98 2").unwrap();
+        assert_eq!(input.lowest_location().unwrap(), 14);
+        let e = input.lowest_range_location().err().unwrap();
+        let e = ParseError::of(&e).unwrap();
+        assert_eq!((e.line, e.column), (1, 16));
+        assert_eq!(e.expected, "a seed range length");
+    }
+
+    #[test]
+    fn gee_q05_bad_almanacs() {
+        // A seed range with nothing in it leaves no seeds
+        let mut input = almanac("79 0", "50 98 2").unwrap();
+        assert!(input.lowest_range_location().is_err());
+        assert!(almanac("", "50 98 2").is_err());
+
+        let mut input = almanac("18446744073709551615 1", "50 98 2").unwrap();
+        assert!(input.lowest_location().is_ok());
+        assert!(input.lowest_range_location().is_err());
+        let e = almanac("79 14", "0 18446744073709551615 2").err().unwrap();
+        assert_eq!(ParseError::of(&e).unwrap().line, 4);
+
+        // Every map has to be there
+        let text = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n";
+        let e = Input::try_from(FileLines::open(Source::text(text)).unwrap())
+            .err()
+            .unwrap();
+        let e = ParseError::of(&e).unwrap();
+        assert_eq!((e.line, e.expected.as_str()), (5, "soil-to-fertilizer map"));
+    }
 }
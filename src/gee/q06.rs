@@ -1,4 +1,5 @@
-use crate::utils::parser::FileLines;
+use crate::solution::solution;
+use crate::utils::parser::{error, FileLines};
 
 #[derive(Debug)]
 struct Input {
@@ -15,20 +16,28 @@ impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
     fn try_from(mut _lines: FileLines) -> Result<Self, Self::Error> {
-        let time = _lines.next().unwrap();
-        let distance = _lines.next().unwrap();
-        let _races = time
-            .strip_prefix("Time:")
-            .unwrap()
+        let time = _lines.expect_line("times")?;
+        let time_list = _lines.strip_prefix(&time, &time, "Time:")?;
+        let times = time_list
             .split_whitespace()
-            .map(|s| s.parse::<u64>().unwrap())
-            .zip(
-                distance
-                    .strip_prefix("Distance:")
-                    .unwrap()
-                    .split_whitespace()
-                    .map(|s| s.parse::<u64>().unwrap()),
-            )
+            .map(|s| _lines.parse_at::<u64>(&time, s, "a time"))
+            .collect::<Result<Vec<_>, _>>()?;
+        if times.is_empty() {
+            return Err(_lines.error_at(&time, time_list, "a time"));
+        }
+        let distance = _lines.expect_line("distances")?;
+        let distance_list = _lines.strip_prefix(&distance, &distance, "Distance:")?;
+        let distances = distance_list
+            .split_whitespace()
+            .map(|s| _lines.parse_at::<u64>(&distance, s, "a distance"))
+            .collect::<Result<Vec<_>, _>>()?;
+        if distances.len() != times.len() {
+            let expected = format!("a distance for each of the {} times", times.len());
+            return Err(_lines.error_at(&distance, distance_list, &expected));
+        }
+        let _races = times
+            .into_iter()
+            .zip(distances)
             .map(|(t, d)| Race {
                 _time: t,
                 _distance: d,
@@ -40,7 +49,8 @@ impl TryFrom<FileLines> for Input {
 }
 
 impl Input {
-    fn _combined_race(&self) -> Race {
+    /// The races as one, with the kerning between the numbers ignored.
+    fn _combined_race(&self) -> std::io::Result<Race> {
         let mut time = String::new();
         let mut distance = String::new();
 
@@ -48,10 +58,10 @@ impl Input {
             time.push_str(format!("{}", self._races[i]._time).as_str());
             distance.push_str(format!("{}", self._races[i]._distance).as_str());
         }
-        Race {
-            _time: time.parse().unwrap(),
-            _distance: distance.parse().unwrap(),
-        }
+        let (Ok(_time), Ok(_distance)) = (time.parse(), distance.parse()) else {
+            return error("The combined race is too long to count");
+        };
+        Ok(Race { _time, _distance })
     }
 }
 
@@ -67,44 +77,63 @@ impl Race {
     }
 }
 
-fn _part_1(input_file: &str) -> std::io::Result<u64> {
+pub fn part_1(input_file: &str) -> std::io::Result<u64> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
     Ok(input._races.iter().map(|r| r._ways_to_win()).product())
 }
 
-fn _part_2(input_file: &str) -> std::io::Result<u64> {
+pub fn part_2(input_file: &str) -> std::io::Result<u64> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
-    Ok(input._combined_race()._ways_to_win())
+    Ok(input._combined_race()?._ways_to_win())
 }
 
+solution!(6);
+
 #[cfg(test)]
 mod tests {
-    use super::{_part_1, _part_2};
+    use super::{part_1, part_2, Input};
+    use crate::utils::parser::{parse, ParseError, Source};
 
     const INPUT: &str = "input/gee/q06_input.txt";
     const INPUT_SAMPLE: &str = "input/gee/q06_sample.txt";
 
     #[test]
     fn gee_q06_p1_sample() {
-        let result = _part_1(INPUT_SAMPLE);
+        let result = part_1(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 288);
     }
 
     #[test]
     fn gee_q06_p1_main() {
-        let result = _part_1(INPUT);
+        let result = part_1(INPUT);
         assert_eq!(result.unwrap(), 861300);
     }
 
     #[test]
     fn gee_q06_p2_sample() {
-        let result = _part_2(INPUT_SAMPLE);
+        let result = part_2(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 71503);
     }
 
     #[test]
     fn gee_q06_p2_main() {
-        let result = _part_2(INPUT);
+        let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 28101347);
     }
+
+    #[test]
+    fn gee_q06_bad_races() {
+        let line = |text: &str| {
+            let e = parse::<Input>(Source::text(text)).err().unwrap();
+            ParseError::of(&e).unwrap().line
+        };
+        assert_eq!(line("Time:\nDistance:\n"), 1);
+        assert_eq!(line("Time: 7 15\nDistance: 9\n"), 2);
+        assert_eq!(line("Time: 7\nDistance: 9 40\n"), 2);
+        let input: Input = parse(Source::text(
+            "Time: 99999 99999 99999 99999\nDistance: 1 2 3 4\n",
+        ))
+        .unwrap();
+        assert!(input._combined_race().is_err());
+    }
 }
@@ -1,3 +1,4 @@
+use crate::solution::solution;
 use crate::utils::parser::FileLines;
 use std::cmp::Ordering;
 use std::collections::{HashMap, HashSet};
@@ -16,36 +17,28 @@ impl TryFrom<(FileLines, bool)> for Input {
     type Error = std::io::Error;
 
     fn try_from(_in: (FileLines, bool)) -> Result<Self, Self::Error> {
-        let (lines, use_jokers) = _in;
-        let _hands = lines
-            .map(|line| {
-                let (hand, bid) = line.split_once(' ').unwrap();
-                let _cards = hand
-                    .chars()
-                    .map(|c| match c {
-                        'A' => 14,
-                        'K' => 13,
-                        'Q' => 12,
-                        'J' if use_jokers => 1,
-                        'J' if !use_jokers => 11,
-                        'T' => 10,
-                        '9' => 9,
-                        '8' => 8,
-                        '7' => 7,
-                        '6' => 6,
-                        '5' => 5,
-                        '4' => 4,
-                        '3' => 3,
-                        '2' => 2,
-                        _ => 0,
-                    })
-                    .collect();
-                Hand {
-                    _cards,
-                    _bid: bid.parse().unwrap(),
-                }
-            })
-            .collect();
+        let (mut lines, use_jokers) = _in;
+        let mut _hands = Vec::new();
+        while let Some(line) = lines.next() {
+            let (hand, bid) = lines.split_once(&line, &line, " ")?;
+            let _cards = hand
+                .char_indices()
+                .map(|(i, c)| match c {
+                    'A' => Ok(14),
+                    'K' => Ok(13),
+                    'Q' => Ok(12),
+                    'J' if use_jokers => Ok(1),
+                    'J' => Ok(11),
+                    'T' => Ok(10),
+                    '2'..='9' => Ok(c as u32 - '0' as u32),
+                    _ => Err(lines.error_at(&line, &hand[i..i + c.len_utf8()], "a card")),
+                })
+                .collect::<Result<_, _>>()?;
+            _hands.push(Hand {
+                _cards,
+                _bid: lines.parse_at(&line, bid, "a bid")?,
+            });
+        }
         Ok(Input { _hands })
     }
 }
@@ -94,7 +87,7 @@ fn _compare_hands(a: &Hand, b: &Hand) -> Ordering {
     }
 }
 
-fn _part_1(input_file: &str) -> std::io::Result<u32> {
+pub fn part_1(input_file: &str) -> std::io::Result<u32> {
     let mut input = Input::try_from((FileLines::new(input_file)?, false))?;
     input._hands.sort_by(_compare_hands);
     Ok(input
@@ -105,7 +98,7 @@ fn _part_1(input_file: &str) -> std::io::Result<u32> {
         .sum())
 }
 
-fn _part_2(input_file: &str) -> std::io::Result<u32> {
+pub fn part_2(input_file: &str) -> std::io::Result<u32> {
     let mut input = Input::try_from((FileLines::new(input_file)?, true))?;
     input._hands.sort_by(_compare_hands);
     Ok(input
@@ -116,34 +109,36 @@ fn _part_2(input_file: &str) -> std::io::Result<u32> {
         .sum())
 }
 
+solution!(7);
+
 #[cfg(test)]
 mod tests {
-    use super::{_part_1, _part_2};
+    use super::{part_1, part_2};
 
     const INPUT: &str = "input/gee/q07_input.txt";
     const INPUT_SAMPLE: &str = "input/gee/q07_sample.txt";
 
     #[test]
     fn gee_q07_p1_sample() {
-        let result = _part_1(INPUT_SAMPLE);
+        let result = part_1(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 6440);
     }
 
     #[test]
     fn gee_q07_p1_main() {
-        let result = _part_1(INPUT);
+        let result = part_1(INPUT);
         assert_eq!(result.unwrap(), 253638586);
     }
 
     #[test]
     fn gee_q07_p2_sample() {
-        let result = _part_2(INPUT_SAMPLE);
+        let result = part_2(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 5905);
     }
 
     #[test]
     fn gee_q07_p2_main() {
-        let result = _part_2(INPUT);
+        let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 253253225);
     }
 }
@@ -1,43 +1,58 @@
 #![allow(dead_code)]
 
-use crate::utils::parser::FileLines;
-use std::cmp;
+use crate::solution::solution;
+use crate::utils::cycle::find_cycle;
+use crate::utils::dot::Dot;
+use crate::utils::geom::Turn;
+use crate::utils::math::crt;
+use crate::utils::parser::{error, FileLines};
+use itertools::Itertools;
 use std::collections::HashMap;
 
 #[derive(Debug)]
 struct Input {
-    instructions: Vec<Direction>,
+    instructions: Vec<Turn>,
     nodes: HashMap<String, (String, String)>,
 }
 
-#[derive(Debug)]
-enum Direction {
-    Left,
-    Right,
-}
-
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
     fn try_from(mut _lines: FileLines) -> Result<Self, Self::Error> {
-        let instructions = _lines
-            .next_result()?
-            .chars()
-            .map(|c| match c {
-                'L' => Direction::Left,
-                'R' => Direction::Right,
-                _ => panic!("Bad direction: {}", c),
+        let line = _lines.expect_line("instructions")?;
+        let instructions: Vec<Turn> = line
+            .char_indices()
+            .map(|(i, c)| match c {
+                'L' => Ok(Turn::Left),
+                'R' => Ok(Turn::Right),
+                _ => Err(_lines.error_at(&line, &line[i..i + c.len_utf8()], "L or R")),
             })
-            .collect();
+            .collect::<Result<_, _>>()?;
+        if instructions.is_empty() {
+            return Err(_lines.error_at(&line, &line, "L or R"));
+        }
         _lines.next();
         let mut nodes = HashMap::new();
-        for line in _lines {
-            if let Some((src, dest)) = line.split_once(" = ") {
-                if let Some((l, r)) = dest.split_once(", ") {
-                    nodes.insert(
-                        String::from(src),
-                        (String::from(&l[1..]), String::from(&r[..3])),
-                    );
+        // Turns can lead to nodes further down, so they're checked once every
+        // node is known: (line number, line, where the left and right turns are)
+        let mut turns = Vec::new();
+        while let Some(line) = _lines.next() {
+            let (src, dest) = _lines.split_once(&line, &line, " = ")?;
+            let dest = _lines.strip_prefix(&line, dest, "(")?;
+            let dest = _lines.strip_suffix(&line, dest, ")")?;
+            let (l, r) = _lines.split_once(&line, dest, ", ")?;
+            nodes.insert(String::from(src), (String::from(l), String::from(r)));
+            let span = |token: &str| {
+                let start = token.as_ptr() as usize - line.as_ptr() as usize;
+                start..start + token.len()
+            };
+            let (l, r) = (span(l), span(r));
+            turns.push((_lines.line_number(), line, l, r));
+        }
+        for (number, line, l, r) in turns {
+            for turn in [l, r] {
+                if !nodes.contains_key(&line[turn.clone()]) {
+                    return Err(_lines.error_on_line(number, &line, &line[turn], "a known node"));
                 }
             }
         }
@@ -48,76 +63,109 @@ impl TryFrom<FileLines> for Input {
     }
 }
 
-fn find_cycles<'a>(
-    instructions: &[Direction],
-    nodes: &'a HashMap<String, (String, String)>,
-) -> HashMap<&'a str, usize> {
-    let mut cycles: HashMap<&str, usize> = HashMap::new();
-    let mut steps = 0;
-    let mut i = 0;
-    let mut current_nodes: Vec<(&str, &str, Vec<_>)> = nodes
-        .keys()
-        .filter(|s| s.ends_with('A'))
-        .map(|s| (s.as_str(), s.as_str(), vec![("", 0)]))
-        .collect();
-    current_nodes.sort();
-    loop {
-        let instruction = &instructions[i];
-        current_nodes = current_nodes
-            .into_iter()
-            .map(|(s, n, mut v)| match instruction {
-                Direction::Left => {
-                    let x = nodes.get(n).map(|(l, _)| l).unwrap().as_str();
-                    (s, x, v)
-                }
-                Direction::Right => {
-                    let x = nodes.get(n).map(|(_, r)| r).unwrap().as_str();
-                    if x.ends_with('Z') {
-                        v.push((x, steps + 1));
-                        if !cycles.contains_key(s) {
-                            cycles.insert(s, steps + 1);
-                        }
-                    }
-                    (s, x, v)
-                }
-            })
-            .collect();
-        steps += 1;
-        if cycles.len() == current_nodes.len() {
-            return cycles;
+/// Where `turn` leads from `node`. Parsing checks every turn leads to a
+/// node in the map, so only a missing start can be looked up here.
+fn next_node<'a>(nodes: &'a HashMap<String, (String, String)>, node: &str, turn: Turn) -> &'a str {
+    match turn {
+        Turn::Left => &nodes[node].0,
+        Turn::Right => &nodes[node].1,
+    }
+}
+
+/// When a ghost is on a Z node: on its way into a loop, and then each (first
+/// step, period) once it's going round.
+struct Ghost {
+    before_loop: Vec<usize>,
+    hits: Vec<(usize, usize)>,
+    loop_start: usize,
+}
+
+impl Ghost {
+    fn on_z(&self, steps: usize) -> bool {
+        if steps < self.loop_start {
+            self.before_loop.contains(&steps)
         } else {
-            i = match i {
-                n if n >= instructions.len() - 1 => 0,
-                n => n + 1,
-            };
+            self.hits
+                .iter()
+                .any(|&(hit, period)| (steps - self.loop_start) % period == hit - self.loop_start)
         }
     }
 }
 
-fn lcm(a: usize, b: usize) -> usize {
-    let (small, big) = (cmp::min(a, b), cmp::max(a, b));
-    if big % small == 0 {
-        return big;
+fn find_cycle_of<'a>(
+    instructions: &[Turn],
+    nodes: &'a HashMap<String, (String, String)>,
+    start: &'a str,
+) -> Ghost {
+    // (node, instruction index) is all it takes to know where a ghost goes next
+    let step = |&(node, i): &(&'a str, usize)| {
+        let node = next_node(nodes, node, instructions[i]);
+        (node, (i + 1) % instructions.len())
+    };
+    let cycle = find_cycle((start, 0), step);
+    let mut state = (start, 0);
+    let mut ghost = Ghost {
+        before_loop: Vec::new(),
+        hits: Vec::new(),
+        loop_start: cycle.start,
+    };
+    for steps in 0..cycle.start + cycle.length {
+        if state.0.ends_with('Z') {
+            if steps < cycle.start {
+                ghost.before_loop.push(steps);
+            } else {
+                ghost.hits.push((steps, cycle.length));
+            }
+        }
+        state = step(&state);
     }
-    for i in 1..small {
-        if big * i % small == 0 {
-            return big * i;
+    ghost
+}
+
+fn find_cycles(instructions: &[Turn], nodes: &HashMap<String, (String, String)>) -> Vec<Ghost> {
+    nodes
+        .keys()
+        .filter(|s| s.ends_with('A'))
+        .map(|s| find_cycle_of(instructions, nodes, s))
+        .collect()
+}
+
+/// The node map as a GraphViz digraph, with edges labelled by turn. Ghosts
+/// start on the green A nodes and are done on the red Z nodes.
+pub fn to_dot(input_file: &str) -> std::io::Result<Dot> {
+    let input = Input::try_from(FileLines::new(input_file)?)?;
+    let mut dot = Dot::digraph("nodes");
+    dot.node_defaults(&[("shape", "circle")]);
+    for node in input.nodes.keys().sorted() {
+        let (l, r) = &input.nodes[node];
+        if node.ends_with('A') {
+            dot.node(node, &[("color", "green"), ("style", "bold")]);
+        } else if node.ends_with('Z') {
+            dot.node(node, &[("color", "red"), ("shape", "doublecircle")]);
+        } else {
+            dot.node(node, &[]);
+        }
+        if l == r {
+            dot.edge(node, l, &[("label", "LR")]);
+        } else {
+            dot.edge(node, l, &[("label", "L")]);
+            dot.edge(node, r, &[("label", "R")]);
         }
     }
-    small * big
+    Ok(dot)
 }
 
-fn part_1(input_file: &str) -> std::io::Result<u32> {
+pub fn part_1(input_file: &str) -> std::io::Result<u32> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
     let mut steps = 0;
     let mut i = 0;
     let (instructions, nodes) = (input.instructions, input.nodes);
-    let mut current_node = &String::from("AAA");
+    if !nodes.contains_key("AAA") {
+        return error("There's no AAA node to start from");
+    }
+    let mut current_node = "AAA";
     loop {
-        current_node = match instructions[i] {
-            Direction::Left => nodes.get(current_node).map(|(l, _)| l).unwrap(),
-            Direction::Right => nodes.get(current_node).map(|(_, r)| r).unwrap(),
-        };
+        current_node = next_node(&nodes, current_node, instructions[i]);
         steps += 1;
         if current_node == "ZZZ" {
             break;
@@ -133,23 +181,44 @@ fn part_1(input_file: &str) -> std::io::Result<u32> {
 
 pub fn part_2(input_file: &str) -> std::io::Result<usize> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
-    let cycles = find_cycles(&input.instructions, &input.nodes);
-    let cycle_sizes: Vec<usize> = cycles.values().copied().collect();
-    let mut result = 1;
-    for n in cycle_sizes {
-        result = lcm(result, n);
+    let ghosts = find_cycles(&input.instructions, &input.nodes);
+    // Before every ghost is going round its loop, just look
+    let settled = ghosts.iter().map(|g| g.loop_start).max().unwrap_or(0);
+    if let Some(steps) = (0..settled).find(|&s| ghosts.iter().all(|g| g.on_z(s))) {
+        return Ok(steps);
+    }
+    // A ghost can pass several Z nodes per loop, so try each pick of one
+    // hit per ghost and keep the earliest time they all line up
+    let earliest = ghosts
+        .iter()
+        .map(|g| g.hits.clone())
+        .multi_cartesian_product()
+        .filter_map(|hits| {
+            crt(hits
+                .iter()
+                .map(|&(hit, period)| (hit as u128, period as u128)))
+        })
+        .map(|(steps, _)| steps)
+        .min();
+    match earliest {
+        Some(steps) => Ok(steps as usize),
+        None => error("The ghosts are never all on Z nodes at once"),
     }
-    Ok(result)
 }
 
+solution!(8);
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{part_1, part_2, to_dot, Input};
+    use crate::utils::parser::{parse, ParseError, Source};
 
     const INPUT: &str = "input/gee/q08_input.txt";
     const INPUT_SAMPLE_1: &str = "input/gee/q08_sample.txt";
     const INPUT_SAMPLE_2: &str = "input/gee/q08_sample_2.txt";
     const INPUT_SAMPLE_3: &str = "input/gee/q08_sample_3.txt";
+    const INPUT_SAMPLE_4: &str = "input/gee/q08_sample_4.txt";
+    const INPUT_SAMPLE_5: &str = "input/gee/q08_sample_5.txt";
 
     #[test]
     fn gee_q08_p1_sample_1() {
@@ -175,6 +244,51 @@ mod tests {
         assert_eq!(result.unwrap(), 6);
     }
 
+    #[test]
+    fn gee_q08_p2_late_cycles() {
+        // Z every 2 steps from step 2 and every 3 steps from step 4, which a
+        // plain lcm of the cycle lengths would get wrong
+        let result = part_2(INPUT_SAMPLE_4);
+        assert_eq!(result.unwrap(), 4);
+    }
+
+    #[test]
+    fn gee_q08_p2_before_cycles() {
+        // Both ghosts are only ever on Z at step 1, before they loop forever
+        // on B nodes
+        let result = part_2(INPUT_SAMPLE_5);
+        assert_eq!(result.unwrap(), 1);
+    }
+
+    #[test]
+    fn gee_q08_unknown_nodes() {
+        let e = parse::<Input>(Source::text("L\n\nAAA = (BBB, ZZZ)\nZZZ = (ZZZ, ZZZ)\n"))
+            .err()
+            .unwrap();
+        let e = ParseError::of(&e).unwrap();
+        assert_eq!((e.line, e.column), (3, 8));
+        assert_eq!(e.found, "\"BBB\"");
+        let e = parse::<Input>(Source::text("L\n\nAAA = (AAA, ZZZ)\n")).err();
+        assert_eq!(ParseError::of(&e.unwrap()).unwrap().column, 13);
+        assert!(part_1(INPUT_SAMPLE_5).is_err());
+
+        let text = "\n\nAAA = (AAA, AAA)\n";
+        let e = parse::<Input>(Source::text(text)).err().unwrap();
+        let e = ParseError::of(&e).unwrap();
+        assert_eq!((e.line, e.expected.as_str()), (1, "L or R"));
+    }
+
+    #[test]
+    fn gee_q08_to_dot() {
+        let dot = to_dot(INPUT_SAMPLE_3).unwrap();
+        assert_eq!((dot.node_count(), dot.edge_count()), (8, 12));
+        let dot = dot.to_string();
+        assert!(dot.contains("\"11A\" [color=\"green\", style=\"bold\"];"));
+        assert!(dot.contains("\"22Z\" [color=\"red\", shape=\"doublecircle\"];"));
+        assert!(dot.contains("\"11A\" -> \"11B\" [label=\"L\"];"));
+        assert!(dot.contains("\"XXX\" -> \"XXX\" [label=\"LR\"];"));
+    }
+
     #[test]
     fn gee_q08_p2_main() {
         let result = part_2(INPUT);
@@ -1,5 +1,6 @@
 #![allow(dead_code, unused_variables)]
 
+use crate::solution::solution;
 use crate::utils::parser::{parse, FileLines};
 
 struct Input {
@@ -8,76 +9,99 @@ struct Input {
 
 struct History {
     sequence: Vec<i32>,
+    /// The sequence and its differences, down to a level of all zeros.
+    levels: Vec<Vec<i32>>,
 }
 
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
-        let histories = lines
-            .map(|l| History {
-                sequence: l.split_whitespace().map(|n| n.parse().unwrap()).collect(),
-            })
-            .collect();
+    fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
+        let mut histories = Vec::new();
+        while let Some(l) = lines.next() {
+            let sequence: Vec<i32> = l
+                .split_whitespace()
+                .map(|n| lines.parse_at(&l, n, "a number"))
+                .collect::<Result<_, _>>()?;
+            let Some(levels) = build_levels(&sequence) else {
+                let expected = "a sequence whose differences reach all zeros";
+                return Err(lines.error_at(&l, &l, expected));
+            };
+            histories.push(History { sequence, levels });
+        }
 
         Ok(Input { histories })
     }
 }
 
-impl History {
-    fn next_value(&self) -> i32 {
-        let levels = self.build_levels();
-        let mut n = 0;
-        for i in 1..=levels.len() {
-            let index = levels.len() - i;
-            n += levels[index].last().unwrap();
+/// Differences of differences until they're all zero, or `None` if the
+/// numbers run out first.
+fn build_levels(sequence: &[i32]) -> Option<Vec<Vec<i32>>> {
+    let mut levels: Vec<Vec<i32>> = vec![sequence.to_vec()];
+    loop {
+        let level = levels.last()?;
+        if level.is_empty() {
+            return None;
         }
-        n
+        if level.iter().all(|&n| n == 0) {
+            return Some(levels);
+        }
+        let next_level = level.windows(2).map(|v| v[1] - v[0]).collect();
+        levels.push(next_level);
     }
+}
 
-    fn previous_value(&self) -> i32 {
-        let levels = self.build_levels();
-        let mut n = 0;
-        for i in 1..=levels.len() {
-            let index = levels.len() - i;
-            n = levels[index].first().unwrap() - n;
-        }
-        n
+impl History {
+    fn next_value(&self) -> i32 {
+        self.levels.iter().filter_map(|level| level.last()).sum()
     }
 
-    fn build_levels(&self) -> Vec<Vec<i32>> {
-        let mut levels: Vec<Vec<i32>> = vec![self.sequence.to_vec()];
-        let mut size = levels[0].len();
-        while levels.last().unwrap().iter().any(|&n| n != 0) {
-            let mut next_level: Vec<i32> = Vec::new();
-            for i in 0..(size - 1) {
-                let v = &levels.last().unwrap();
-                next_level.push(v[i + 1] - v[i]);
-            }
-            size = next_level.len();
-            levels.push(next_level);
-        }
-        levels
+    fn previous_value(&self) -> i32 {
+        self.levels
+            .iter()
+            .rev()
+            .filter_map(|level| level.first())
+            .fold(0, |n, first| first - n)
     }
 }
 
-fn part_1(input_file: &str) -> std::io::Result<i32> {
+pub fn part_1(input_file: &str) -> std::io::Result<i32> {
     let input = parse::<Input>(input_file)?;
     Ok(input.histories.iter().map(|h| h.next_value()).sum())
 }
 
-fn part_2(input_file: &str) -> std::io::Result<i32> {
+pub fn part_2(input_file: &str) -> std::io::Result<i32> {
     let input = parse::<Input>(input_file)?;
     Ok(input.histories.iter().map(|h| h.previous_value()).sum())
 }
 
+solution!(9);
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{part_1, part_2, Input};
+    use crate::utils::parser::{parse, ParseError, Source};
 
     const INPUT: &str = "input/gee/q09_input.txt";
     const INPUT_SAMPLE: &str = "input/gee/q09_sample.txt";
 
+    #[test]
+    fn gee_q09_inline_history() {
+        let input: Input = parse(Source::text("10 13 16 21 30 45\n")).unwrap();
+        assert_eq!(input.histories[0].next_value(), 68);
+        assert_eq!(input.histories[0].previous_value(), 5);
+    }
+
+    #[test]
+    fn gee_q09_bad_histories() {
+        for text in ["1 3 6 1\n", "0 1 2\n\n"] {
+            let e = parse::<Input>(Source::text(text)).err().unwrap();
+            let e = ParseError::of(&e).unwrap();
+            assert_eq!(e.expected, "a sequence whose differences reach all zeros");
+            assert_eq!(e.line, text.lines().count());
+        }
+    }
+
     #[test]
     fn gee_q09_p1_sample() {
         let result = part_1(INPUT_SAMPLE);
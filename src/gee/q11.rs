@@ -1,5 +1,6 @@
 #![allow(dead_code, unused_variables)]
 
+use crate::solution::solution;
 use crate::utils::parser::{parse, FileLines};
 use std::cmp::{max, min};
 use std::collections::{HashMap, HashSet};
@@ -81,18 +82,20 @@ fn shortest_path((x1, y1): (usize, usize), (x2, y2): (usize, usize)) -> usize {
     max(x1, x2) - min(x1, x2) + max(y1, y2) - min(y1, y2)
 }
 
-fn part_1(input_file: &str) -> std::io::Result<usize> {
+pub fn part_1(input_file: &str) -> std::io::Result<usize> {
     let mut input: Input = parse(input_file)?;
     input.expand_universe(2);
     Ok(input.shortest_paths().into_iter().sum())
 }
 
-fn part_2(input_file: &str) -> std::io::Result<usize> {
+pub fn part_2(input_file: &str) -> std::io::Result<usize> {
     let mut input: Input = parse(input_file)?;
     input.expand_universe(1_000_000);
     Ok(input.shortest_paths().into_iter().sum())
 }
 
+solution!(11);
+
 #[cfg(test)]
 mod tests {
     use super::{part_1, part_2};
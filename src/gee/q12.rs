@@ -1,5 +1,6 @@
 #![allow(dead_code, unused_variables)]
 
+use crate::solution::solution;
 use crate::utils::parser::{parse, FileLines};
 use std::collections::HashMap;
 
@@ -17,15 +18,17 @@ struct Row {
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
-        let rows = lines
-            .map(|l| {
-                let (a, b) = l.split_once(' ').unwrap();
-                let springs = a.chars().collect();
-                let groups = b.split(',').map(|n| n.parse().unwrap()).collect();
-                Row { springs, groups }
-            })
-            .collect();
+    fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
+        let mut rows = Vec::new();
+        while let Some(l) = lines.next() {
+            let (a, b) = lines.split_once(&l, &l, " ")?;
+            let springs = a.chars().collect();
+            let groups = b
+                .split(',')
+                .map(|n| lines.parse_at(&l, n, "a group size"))
+                .collect::<Result<_, _>>()?;
+            rows.push(Row { springs, groups });
+        }
         Ok(Input { rows })
     }
 }
@@ -99,7 +102,7 @@ impl Row {
     }
 }
 
-fn part_1(input_file: &str) -> std::io::Result<usize> {
+pub fn part_1(input_file: &str) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
     Ok(input.rows.iter().map(|r| r.match_groups()).sum())
 }
@@ -110,6 +113,8 @@ pub fn part_2(input_file: &str) -> std::io::Result<usize> {
     Ok(input.rows.iter().map(|r| r.match_groups()).sum())
 }
 
+solution!(12);
+
 #[cfg(test)]
 mod tests {
     use super::{part_1, part_2};
@@ -1,5 +1,6 @@
 #![allow(dead_code, unused_variables)]
 
+use crate::solution::solution;
 use crate::utils::parser::{parse, FileLines};
 use std::cmp::min;
 
@@ -11,21 +12,20 @@ struct Pattern {
     grid: Vec<Vec<char>>,
 }
 
+impl TryFrom<FileLines> for Pattern {
+    type Error = std::io::Error;
+
+    fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
+        let grid = lines.parse_each(|lines, line| lines.chars_of(line, ".#", "ash or rock"))?;
+        Ok(Pattern { grid })
+    }
+}
+
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
     fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
-        let mut patterns = Vec::new();
-        let mut grid: Vec<Vec<char>> = Vec::new();
-        for line in lines {
-            if line.is_empty() {
-                patterns.push(Pattern { grid: grid.clone() });
-                grid.clear();
-            } else {
-                grid.push(line.chars().collect());
-            }
-        }
-        patterns.push(Pattern { grid: grid.clone() });
+        let patterns = lines.sections().parse_all()?;
         Ok(Input { patterns })
     }
 }
@@ -138,7 +138,7 @@ fn find_reflection_values(columns: &Vec<String>, rows: &Vec<String>) -> Vec<usiz
     results
 }
 
-fn part_1(input_file: &str) -> std::io::Result<usize> {
+pub fn part_1(input_file: &str) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
     Ok(input
         .patterns
@@ -147,7 +147,7 @@ fn part_1(input_file: &str) -> std::io::Result<usize> {
         .sum())
 }
 
-fn part_2(input_file: &str) -> std::io::Result<usize> {
+pub fn part_2(input_file: &str) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
     Ok(input
         .patterns
@@ -156,6 +156,8 @@ fn part_2(input_file: &str) -> std::io::Result<usize> {
         .sum())
 }
 
+solution!(13);
+
 #[cfg(test)]
 mod tests {
     use super::{part_1, part_2};
@@ -1,10 +1,18 @@
 #![allow(dead_code, unused_variables)]
 
+use crate::solution::solution;
 use crate::utils::parser::{parse, FileLines};
 use std::collections::HashMap;
 
+/// e.g. `rn=1` or `cm-`
+enum Step {
+    Remove(String),
+    Set(String, usize),
+}
+
 struct Input {
     sequence: Vec<String>,
+    steps: Vec<Step>,
 }
 
 struct LensBox<'a> {
@@ -16,8 +24,25 @@ impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
     fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
+        let line = lines.expect_line("an initialization sequence")?;
+        let mut steps = Vec::new();
+        for s in line.split(',') {
+            let (label, step) = match s.strip_suffix('-') {
+                Some(label) => (label, Step::Remove(String::from(label))),
+                None => {
+                    let (label, focal_length) = lines.split_once(&line, s, "=")?;
+                    let focal_length = lines.parse_at(&line, focal_length, "a focal length")?;
+                    (label, Step::Set(String::from(label), focal_length))
+                }
+            };
+            if label.is_empty() {
+                return Err(lines.error_at(&line, label, "a label"));
+            }
+            steps.push(step);
+        }
         Ok(Input {
-            sequence: lines.next().unwrap().split(',').map(String::from).collect(),
+            sequence: line.split(',').map(String::from).collect(),
+            steps,
         })
     }
 }
@@ -66,30 +91,25 @@ fn hash(s: &str) -> usize {
     value
 }
 
-fn hash_map<'a>(s: &'a str, boxes: &mut [LensBox<'a>]) {
-    if s.ends_with('-') {
-        let (label, _) = s.split_once('-').unwrap();
-        let b = &mut boxes[hash(label)];
-        b.remove_lens(label);
-    } else {
-        let (label, focal_length) = s.split_once('=').unwrap();
-        let b = &mut boxes[hash(label)];
-        b.set_lens(label, focal_length.parse().unwrap());
+fn hash_map<'a>(step: &'a Step, boxes: &mut [LensBox<'a>]) {
+    match step {
+        Step::Remove(label) => boxes[hash(label)].remove_lens(label),
+        Step::Set(label, focal_length) => boxes[hash(label)].set_lens(label, *focal_length),
     }
 }
 
-fn part_1(input_file: &str) -> std::io::Result<usize> {
+pub fn part_1(input_file: &str) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
     Ok(input.sequence.iter().map(|s| hash(s)).sum())
 }
 
-fn part_2(input_file: &str) -> std::io::Result<usize> {
+pub fn part_2(input_file: &str) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
     let mut boxes = Vec::<LensBox>::new();
     for _ in 0..256 {
         boxes.push(LensBox::new());
     }
-    input.sequence.iter().for_each(|s| hash_map(s, &mut boxes));
+    input.steps.iter().for_each(|s| hash_map(s, &mut boxes));
     Ok(boxes
         .iter()
         .enumerate()
@@ -97,9 +117,12 @@ fn part_2(input_file: &str) -> std::io::Result<usize> {
         .sum())
 }
 
+solution!(15);
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{part_1, part_2, Input};
+    use crate::utils::parser::{parse, ParseError, Source};
 
     const INPUT: &str = "input/gee/q15_input.txt";
     const INPUT_SAMPLE: &str = "input/gee/q15_sample.txt";
@@ -127,4 +150,12 @@ mod tests {
         let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 230462);
     }
+
+    #[test]
+    fn gee_q15_bad_steps() {
+        for (text, column) in [("rn=1,cm=x", 9), ("rn=1,cm", 6), ("=4", 1), ("rn=1,-", 6)] {
+            let error = parse::<Input>(Source::text(text)).err().unwrap();
+            assert_eq!(ParseError::of(&error).unwrap().column, column, "{}", text);
+        }
+    }
 }
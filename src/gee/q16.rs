@@ -1,27 +1,24 @@
 #![allow(dead_code, unused_variables)]
 
+use crate::solution::solution;
+use crate::utils::geom::Direction;
+use crate::utils::grid::Grid;
 use crate::utils::parser::{parse, FileLines};
 use std::collections::HashSet;
 
 type Beam = (usize, usize, Direction);
 
-#[derive(Clone, Debug, Eq, Hash, PartialEq)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
 struct Input {
-    grid: Vec<Vec<char>>,
+    grid: Grid<char>,
 }
 
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
     fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
-        let grid = lines.map(|l| l.chars().collect()).collect();
+        let grid = Grid::parse(lines, "a mirror, splitter or empty space", |c| {
+            r"./\|-".contains(c).then_some(c)
+        })?;
         Ok(Input { grid })
     }
 }
@@ -36,66 +33,21 @@ impl Input {
                 if visited.contains(&beam) {
                     continue;
                 }
-                visited.insert(beam.clone());
+                visited.insert(beam);
                 let (r, c, direction) = (beam.0, beam.1, &beam.2);
-                match (self.grid[r][c], direction) {
-                    ('/', Direction::Up) | ('\\', Direction::Down) => {
-                        if c < self.grid[0].len() - 1 {
-                            new_beams.insert((r, c + 1, Direction::Right));
-                        }
-                    }
-                    ('/', Direction::Down) | ('\\', Direction::Up) => {
-                        if c > 0 {
-                            new_beams.insert((r, c - 1, Direction::Left));
-                        }
-                    }
-                    ('/', Direction::Left) | ('\\', Direction::Right) => {
-                        if r < self.grid.len() - 1 {
-                            new_beams.insert((r + 1, c, Direction::Down));
-                        }
-                    }
-                    ('/', Direction::Right) | ('\\', Direction::Left) => {
-                        if r > 0 {
-                            new_beams.insert((r - 1, c, Direction::Up));
-                        }
-                    }
-                    ('-', Direction::Up) | ('-', Direction::Down) => {
-                        if c > 0 {
-                            new_beams.insert((r, c - 1, Direction::Left));
-                        }
-                        if c < self.grid[0].len() - 1 {
-                            new_beams.insert((r, c + 1, Direction::Right));
-                        }
+                let next = match self.grid[(r, c)] {
+                    '/' if direction.is_vertical() => vec![direction.turn_right()],
+                    '/' => vec![direction.turn_left()],
+                    '\\' if direction.is_vertical() => vec![direction.turn_left()],
+                    '\\' => vec![direction.turn_right()],
+                    '-' if direction.is_vertical() => vec![Direction::Left, Direction::Right],
+                    '|' if direction.is_horizontal() => vec![Direction::Up, Direction::Down],
+                    _ => vec![*direction],
+                };
+                for d in next {
+                    if let Some((r, c)) = self.grid.step((r, c), d.offset()) {
+                        new_beams.insert((r, c, d));
                     }
-                    ('|', Direction::Left) | ('|', Direction::Right) => {
-                        if r > 0 {
-                            new_beams.insert((r - 1, c, Direction::Up));
-                        }
-                        if r < self.grid.len() - 1 {
-                            new_beams.insert((r + 1, c, Direction::Down));
-                        }
-                    }
-                    ('.', Direction::Up) | ('|', Direction::Up) => {
-                        if r > 0 {
-                            new_beams.insert((r - 1, c, Direction::Up));
-                        }
-                    }
-                    ('.', Direction::Down) | ('|', Direction::Down) => {
-                        if r < self.grid.len() - 1 {
-                            new_beams.insert((r + 1, c, Direction::Down));
-                        }
-                    }
-                    ('.', Direction::Left) | ('-', Direction::Left) => {
-                        if c > 0 {
-                            new_beams.insert((r, c - 1, Direction::Left));
-                        }
-                    }
-                    ('.', Direction::Right) | ('-', Direction::Right) => {
-                        if c < self.grid[0].len() - 1 {
-                            new_beams.insert((r, c + 1, Direction::Right));
-                        }
-                    }
-                    (x, _) => panic!("Invalid grid item: {}", x),
                 }
             }
             if new_beams.is_empty() {
@@ -112,16 +64,16 @@ impl Input {
     }
 }
 
-fn part_1(input_file: &str) -> std::io::Result<usize> {
+pub fn part_1(input_file: &str) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
     Ok(input.find_activations((0, 0, Direction::Right)))
 }
 
-fn part_2(input_file: &str) -> std::io::Result<usize> {
+pub fn part_2(input_file: &str) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
     let mut result = 0;
-    for i in 0..input.grid.len() {
-        let l = input.find_activations((i, input.grid[0].len() - 1, Direction::Left));
+    for i in 0..input.grid.height() {
+        let l = input.find_activations((i, input.grid.width() - 1, Direction::Left));
         if l > result {
             result = l;
         }
@@ -130,8 +82,8 @@ fn part_2(input_file: &str) -> std::io::Result<usize> {
             result = r;
         }
     }
-    for i in 0..input.grid[0].len() {
-        let u = input.find_activations((input.grid.len() - 1, i, Direction::Up));
+    for i in 0..input.grid.width() {
+        let u = input.find_activations((input.grid.height() - 1, i, Direction::Up));
         if u > result {
             result = u;
         }
@@ -143,6 +95,8 @@ fn part_2(input_file: &str) -> std::io::Result<usize> {
     Ok(result)
 }
 
+solution!(16);
+
 #[cfg(test)]
 mod tests {
     use super::{part_1, part_2};
@@ -1,184 +1,95 @@
 #![allow(dead_code, unused_variables)]
 
-use crate::utils::parser::{parse, FileLines};
-use std::collections::{BinaryHeap, HashMap, HashSet};
-
-#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
+use crate::solution::solution;
+use crate::utils::crucible::CrucibleRules;
+use crate::utils::geom::Direction;
+use crate::utils::grid::Grid;
+use crate::utils::parser::{error, parse, FileLines};
+use crate::utils::search::astar;
 
 #[derive(Debug)]
 struct Input {
-    grid: Vec<Vec<usize>>,
+    grid: Grid<usize>,
 }
 
 type Node = (usize, usize);
 type Cost = usize;
-type Value = usize;
 type Streak = usize;
-type Path = (Node, Cost, Direction, Streak);
-type OpenSetItem = (Node, Direction, Streak);
-type QueueItem = (Value, Path);
+type State = (Node, Option<Direction>, Streak);
 
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
     fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
-        let grid = lines
-            .map(|l| {
-                l.chars()
-                    .map(|c| c.to_digit(10).unwrap() as usize)
-                    .collect()
-            })
-            .collect();
+        let grid = Grid::parse(lines, "a heat loss digit", |c| {
+            c.to_digit(10).map(|d| d as usize)
+        })?;
         Ok(Input { grid })
     }
 }
 
-fn path_value(grid: &[Vec<usize>], path: Path, target: Node) -> usize {
-    let ((r, c), g, _, _) = path;
-    let h = target.0 - r + target.1 - c;
-    usize::MAX - (g + h)
+fn find_path(grid: &Grid<usize>, rules: &CrucibleRules) -> Option<usize> {
+    let target = rules.target_in(grid.height(), grid.width());
+    astar(
+        [(rules.start, None, 0)],
+        |&state| valid_next_steps(grid, state, target, rules),
+        |&((r, c), _, _)| r.abs_diff(target.0) + c.abs_diff(target.1),
+        |&(node, _, streak)| node == target && rules.can_stop(streak),
+    )
+    .map(|found| found.cost)
 }
 
-fn find_path(grid: &[Vec<usize>], start: Node, target: Node, is_ultra: bool) -> usize {
-    let mut queue = BinaryHeap::<QueueItem>::new();
-    let mut open_set = HashSet::<OpenSetItem>::new();
-    let mut g_scores = HashMap::new();
-    let start_right = (start, 0, Direction::Right, 0);
-    let start_down = (start, 0, Direction::Down, 0);
-    queue.push((path_value(grid, start_right, target), start_right));
-    queue.push((path_value(grid, start_down, target), start_down));
-    while let Some((value, path)) = queue.pop() {
-        let (node, cost, direction, streak) = path;
-        if node == target && (!is_ultra || streak >= 4) {
-            return cost;
-        }
-        open_set.remove(&(node, direction, streak));
-        for next_step in valid_next_steps(grid, path, target, is_ultra) {
-            let (next_node, next_cost, next_direction, next_streak) = next_step;
-            let open_set_item = (next_node, next_direction, next_streak);
-            let current_best = *g_scores.get(&open_set_item).unwrap_or(&usize::MAX);
-            if next_cost < current_best {
-                g_scores.insert(open_set_item, next_cost);
-                if !open_set.contains(&open_set_item) {
-                    let value = path_value(grid, next_step, target);
-                    open_set.insert(open_set_item);
-                    queue.push((value, next_step));
-                }
-            }
-        }
+fn valid_next_steps(
+    grid: &Grid<usize>,
+    state: State,
+    target: Node,
+    rules: &CrucibleRules,
+) -> Vec<(State, Cost)> {
+    let (node, direction, streak) = state;
+    if node == target && rules.can_stop(streak) {
+        return vec![];
     }
-    usize::MAX
+    rules
+        .moves(direction, streak)
+        .into_iter()
+        .filter_map(|(direction, streak)| {
+            let next_node = grid.step(node, direction.offset())?;
+            Some(((next_node, Some(direction), streak), grid[next_node]))
+        })
+        .collect()
 }
 
-fn try_to_take_step(
-    grid: &[Vec<usize>],
-    path: Path,
-    direction: Direction,
-    is_ultra: bool,
-) -> Option<Path> {
-    let ((r, c), last_cost, last_direction, last_streak) = path;
-    if !is_ultra && direction == last_direction && last_streak >= 3 {
-        return None;
-    }
-    if is_ultra && direction == last_direction && last_streak >= 10 {
-        return None;
+/// The least heat loss from the start to the target for any crucible.
+pub fn least_heat_loss(input_file: &str, rules: &CrucibleRules) -> std::io::Result<usize> {
+    let input: Input = parse(input_file)?;
+    if !input.grid.contains(rules.start)
+        || !input
+            .grid
+            .contains(rules.target_in(input.grid.height(), input.grid.width()))
+    {
+        return error("The crucible's start or target is outside the map");
     }
-    if is_ultra && direction != last_direction && last_streak < 4 {
-        return None;
+    match find_path(&input.grid, rules) {
+        Some(heat_loss) => Ok(heat_loss),
+        None => error("The crucible cannot reach its target"),
     }
-    let next_node = match direction {
-        Direction::Up => {
-            if r == 0 || last_direction == Direction::Down {
-                return None;
-            } else {
-                (r - 1, c)
-            }
-        }
-        Direction::Down => {
-            if r >= grid.len() - 1 || last_direction == Direction::Up {
-                return None;
-            } else {
-                (r + 1, c)
-            }
-        }
-        Direction::Left => {
-            if c == 0 || last_direction == Direction::Right {
-                return None;
-            } else {
-                (r, c - 1)
-            }
-        }
-        Direction::Right => {
-            if c >= grid[0].len() - 1 || last_direction == Direction::Left {
-                return None;
-            } else {
-                (r, c + 1)
-            }
-        }
-    };
-    let streak = if direction == last_direction {
-        last_streak + 1
-    } else {
-        1
-    };
-    Some((
-        next_node,
-        last_cost + grid[next_node.0][next_node.1],
-        direction,
-        streak,
-    ))
 }
 
-fn valid_next_steps(grid: &[Vec<usize>], path: Path, target: Node, is_ultra: bool) -> Vec<Path> {
-    if path.0 == target {
-        vec![]
-    } else {
-        let mut candidates = Vec::new();
-        for direction in [
-            Direction::Up,
-            Direction::Down,
-            Direction::Left,
-            Direction::Right,
-        ] {
-            if let Some(p) = try_to_take_step(grid, path, direction, is_ultra) {
-                candidates.push(p);
-            }
-        }
-        candidates
-    }
+pub fn part_1(input_file: &str) -> std::io::Result<usize> {
+    least_heat_loss(input_file, &CrucibleRules::crucible())
 }
 
-fn part_1(input_file: &str) -> std::io::Result<usize> {
-    let input: Input = parse(input_file)?;
-    let grid = &input.grid;
-    Ok(find_path(
-        grid,
-        (0, 0),
-        (grid.len() - 1, grid[0].len() - 1),
-        false,
-    ))
+pub fn part_2(input_file: &str) -> std::io::Result<usize> {
+    least_heat_loss(input_file, &CrucibleRules::ultra_crucible())
 }
 
-fn part_2(input_file: &str) -> std::io::Result<usize> {
-    let input: Input = parse(input_file)?;
-    let grid = &input.grid;
-    Ok(find_path(
-        grid,
-        (0, 0),
-        (grid.len() - 1, grid[0].len() - 1),
-        true,
-    ))
-}
+solution!(17);
 
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{least_heat_loss, part_1, part_2};
+    use crate::utils::crucible::CrucibleRules;
+    use crate::utils::geom::Direction;
 
     const INPUT: &str = "input/gee/q17_input.txt";
     const INPUT_SAMPLE: &str = "input/gee/q17_sample.txt";
@@ -189,6 +100,34 @@ mod tests {
         assert_eq!(result.unwrap(), 102);
     }
 
+    #[test]
+    fn gee_q17_custom_rules() {
+        // Free to wander, it takes the cheapest route outright
+        let rover = CrucibleRules {
+            min_straight: 1,
+            max_straight: usize::MAX,
+            allow_reverse: true,
+            start_directions: Direction::ALL.to_vec(),
+            ..CrucibleRules::crucible()
+        };
+        let free = least_heat_loss(INPUT_SAMPLE, &rover).unwrap();
+        assert_eq!(free, 78);
+        let back = CrucibleRules {
+            start: (12, 12),
+            target: Some((0, 0)),
+            start_directions: vec![Direction::Left, Direction::Up],
+            ..rover.clone()
+        };
+        // Heat is lost entering a block, so the way back swaps the end's 3
+        // for the start's 2
+        assert_eq!(least_heat_loss(INPUT_SAMPLE, &back).unwrap(), 77);
+        let outside = CrucibleRules {
+            target: Some((13, 0)),
+            ..CrucibleRules::crucible()
+        };
+        assert!(least_heat_loss(INPUT_SAMPLE, &outside).is_err());
+    }
+
     #[test]
     fn gee_q17_p1_main() {
         let result = part_1(INPUT);
@@ -201,7 +140,6 @@ mod tests {
         assert_eq!(result.unwrap(), 94);
     }
 
-    #[ignore = "takes too long"]
     #[test]
     fn gee_q17_p2_main() {
         let result = part_2(INPUT);
@@ -1,15 +1,9 @@
 #![allow(dead_code, unused_variables)]
 
-use crate::utils::parser::{parse, FileLines};
-use std::cmp::{max, min};
-use std::collections::{HashMap, VecDeque};
-
-type Constraint = (
-    (usize, usize),
-    (usize, usize),
-    (usize, usize),
-    (usize, usize),
-);
+use crate::solution::solution;
+use crate::utils::parser::{error, parse, FileLines};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::fmt;
 
 #[derive(Debug)]
 struct Input {
@@ -18,62 +12,134 @@ struct Input {
     parts: Vec<Part>,
 }
 
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+}
+
+/// Longest first, so `<=` isn't read as `<` followed by `=`.
+const OPS: [(&str, Op); 5] = [
+    ("<=", Op::Le),
+    (">=", Op::Ge),
+    ("==", Op::Eq),
+    ("<", Op::Lt),
+    (">", Op::Gt),
+];
+
+impl Op {
+    fn compare(self, rating: usize, value: usize) -> bool {
+        match self {
+            Op::Lt => rating < value,
+            Op::Le => rating <= value,
+            Op::Gt => rating > value,
+            Op::Ge => rating >= value,
+            Op::Eq => rating == value,
+        }
+    }
+
+    /// Splits the ratings `lo..=hi` into those that pass the comparison and
+    /// those that don't, either of which may be empty.
+    fn split(self, (lo, hi): Bounds, value: usize) -> (Vec<Bounds>, Vec<Bounds>) {
+        let below = value.checked_sub(1).map(|v| (lo, hi.min(v)));
+        let up_to = Some((lo, hi.min(value)));
+        let from = Some((lo.max(value), hi));
+        let above = value.checked_add(1).map(|v| (lo.max(v), hi));
+        let (pass, fail) = match self {
+            Op::Lt => (vec![below], vec![from]),
+            Op::Le => (vec![up_to], vec![above]),
+            Op::Gt => (vec![above], vec![up_to]),
+            Op::Ge => (vec![from], vec![below]),
+            Op::Eq => (
+                vec![Some((lo.max(value), hi.min(value)))],
+                vec![below, above],
+            ),
+        };
+        let non_empty = |bounds: Vec<Option<Bounds>>| {
+            bounds
+                .into_iter()
+                .flatten()
+                .filter(|(lo, hi)| lo <= hi)
+                .collect()
+        };
+        (non_empty(pass), non_empty(fail))
+    }
+}
+
+#[derive(Debug)]
+struct Condition {
+    var: String,
+    op: Op,
+    value: usize,
+}
+
 #[derive(Debug)]
 enum Rule {
-    Condition(char, char, usize, String),
+    Condition(Condition, String),
     Fallback(String),
 }
 
+/// A part's ratings by variable name.
 #[derive(Debug)]
-struct Part {
-    x: usize,
-    m: usize,
-    a: usize,
-    s: usize,
-}
+struct Part(BTreeMap<String, usize>);
 
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
+    fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
         let mut workflows = Vec::new();
         let mut workflow_map = HashMap::new();
         let mut parts = Vec::new();
-        for line in lines.by_ref() {
-            if line.is_empty() {
-                break;
-            }
-            let (name, r) = line.strip_suffix('}').unwrap().split_once('{').unwrap();
+        let mut sections = lines.sections();
+        let mut lines = sections.expect("workflows")?;
+        while let Some(line) = lines.next() {
+            let workflow = lines.strip_suffix(&line, &line, "}")?;
+            let (name, r) = lines.split_once(&line, workflow, "{")?;
             let rules = r
                 .split(',')
                 .map(|s| {
-                    if s.contains(':') {
-                        let var = s.chars().next().unwrap();
-                        let op = s.chars().nth(1).unwrap();
-                        let (val, dest) = s[2..].split_once(':').unwrap();
-                        Rule::Condition(var, op, val.parse().unwrap(), String::from(dest))
-                    } else {
-                        Rule::Fallback(String::from(s))
+                    let Some((condition, dest)) = s.split_once(':') else {
+                        return Ok(Rule::Fallback(String::from(s)));
+                    };
+                    let split = condition
+                        .find(|c: char| !c.is_ascii_alphabetic())
+                        .unwrap_or(condition.len());
+                    let (var, rest) = condition.split_at(split);
+                    if var.is_empty() {
+                        return Err(lines.error_at(&line, condition, "a variable name"));
                     }
+                    let Some((value, op)) = OPS
+                        .iter()
+                        .find_map(|(s, op)| Some((rest.strip_prefix(s)?, *op)))
+                    else {
+                        return Err(lines.error_at(&line, rest, "<, >, <=, >= or =="));
+                    };
+                    let value = lines.parse_at(&line, value, "a rating")?;
+                    let condition = Condition {
+                        var: String::from(var),
+                        op,
+                        value,
+                    };
+                    Ok(Rule::Condition(condition, String::from(dest)))
                 })
-                .collect();
+                .collect::<Result<_, _>>()?;
             workflows.push(String::from(name));
             workflow_map.insert(String::from(name), rules);
         }
-        for line in lines {
-            let vars = line
-                .strip_prefix('{')
-                .unwrap()
-                .strip_suffix('}')
-                .unwrap()
-                .split(',')
-                .collect::<Vec<_>>();
-            parts.push(Part {
-                x: vars[0].split_once('=').unwrap().1.parse().unwrap(),
-                m: vars[1].split_once('=').unwrap().1.parse().unwrap(),
-                a: vars[2].split_once('=').unwrap().1.parse().unwrap(),
-                s: vars[3].split_once('=').unwrap().1.parse().unwrap(),
-            });
+        let mut lines = sections.expect("part ratings")?;
+        while let Some(line) = lines.next() {
+            let ratings = lines.strip_prefix(&line, &line, "{")?;
+            let ratings = lines.strip_suffix(&line, ratings, "}")?;
+            let mut values = BTreeMap::new();
+            for rating in ratings.split(',') {
+                let (var, value) = lines.split_once(&line, rating, "=")?;
+                let value = lines.parse_at(&line, value, "a rating")?;
+                values.insert(String::from(var), value);
+            }
+            parts.push(Part(values));
         }
         Ok(Input {
             workflows,
@@ -84,157 +150,203 @@ impl TryFrom<FileLines> for Input {
 }
 
 impl Input {
-    fn total_rating(&self) -> usize {
-        self.parts
-            .iter()
-            .map(|p| {
-                if self.is_accepted(p) {
-                    p.x + p.m + p.a + p.s
-                } else {
-                    0
-                }
-            })
-            .sum()
+    fn total_rating(&self) -> std::io::Result<usize> {
+        let mut total = 0;
+        for part in &self.parts {
+            if self.is_accepted(part)? {
+                total += part.0.values().sum::<usize>();
+            }
+        }
+        Ok(total)
+    }
+
+    fn rules(&self, workflow: &str) -> std::io::Result<&[Rule]> {
+        match self.workflow_map.get(workflow) {
+            Some(rules) => Ok(rules),
+            None => error(&format!("No workflow named {}", workflow)),
+        }
+    }
+
+    fn is_accepted(&self, part: &Part) -> std::io::Result<bool> {
+        let mut workflow = "in";
+        // Every workflow at most once, or the part would go round forever
+        for _ in 0..=self.workflows.len() {
+            let dest = self.next_workflow(part, self.rules(workflow)?)?;
+            match dest {
+                "A" => return Ok(true),
+                "R" => return Ok(false),
+                _ => workflow = dest,
+            }
+        }
+        error("Workflows send a part round in a loop")
     }
 
-    fn is_accepted(&self, part: &Part) -> bool {
-        let mut rules = self.workflow_map.get("in").unwrap().iter();
-        while let Some(rule) = rules.next() {
+    fn next_workflow<'a>(&self, part: &Part, rules: &'a [Rule]) -> std::io::Result<&'a str> {
+        for rule in rules {
             match rule {
-                Rule::Condition(var, op, val, dest) if dest.as_str() == "A" => {
-                    if self.passes_rule(part, *var, *op, *val) {
-                        return true;
-                    }
-                }
-                Rule::Condition(var, op, val, dest) if dest.as_str() == "R" => {
-                    if self.passes_rule(part, *var, *op, *val) {
-                        return false;
-                    }
-                }
-                Rule::Condition(var, op, val, dest) => {
-                    if self.passes_rule(part, *var, *op, *val) {
-                        rules = self.workflow_map.get(dest).unwrap().iter();
+                Rule::Condition(condition, dest) => {
+                    let Some(&rating) = part.0.get(&condition.var) else {
+                        return error(&format!("Part has no {} rating", condition.var));
+                    };
+                    if condition.op.compare(rating, condition.value) {
+                        return Ok(dest);
                     }
                 }
-                Rule::Fallback(dest) if dest.as_str() == "A" => return true,
-                Rule::Fallback(dest) if dest.as_str() == "R" => return false,
-                Rule::Fallback(dest) => {
-                    rules = self.workflow_map.get(dest).unwrap().iter();
-                }
+                Rule::Fallback(dest) => return Ok(dest),
             }
         }
-        false
+        error("Workflow has no fallback rule")
     }
+}
 
-    fn passes_rule(&self, part: &Part, var: char, op: char, val: usize) -> bool {
-        let v = match var {
-            'x' => part.x,
-            'm' => part.m,
-            'a' => part.a,
-            's' => part.s,
-            _ => panic!("Bad variable name"),
-        };
-        (op == '>' && v > val) || (op == '<' && v < val)
+/// Inclusive lower and upper bounds on a rating.
+type Bounds = (usize, usize);
+
+/// A box of parts: the inclusive range of each named rating.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Constraint(BTreeMap<String, Bounds>);
+
+impl Constraint {
+    pub fn new<'a>(bounds: impl IntoIterator<Item = (&'a str, Bounds)>) -> Self {
+        Constraint(
+            bounds
+                .into_iter()
+                .map(|(var, b)| (String::from(var), b))
+                .collect(),
+        )
     }
-}
 
-fn apply_constraint(constraint: Constraint, var: char, op: char, val: usize) -> Constraint {
-    let mut c = constraint;
-    let v = match var {
-        'x' => &mut c.0,
-        'm' => &mut c.1,
-        'a' => &mut c.2,
-        's' => &mut c.3,
-        _ => panic!("Bad constraint var"),
-    };
-    match op {
-        '>' => v.0 = val + 1,
-        '<' => v.1 = val - 1,
-        _ => panic!("Bad constraint op"),
+    /// The puzzle's ratings: x, m, a and s, each from 1 to 4000.
+    pub fn xmas() -> Self {
+        Constraint::new(["x", "m", "a", "s"].map(|v| (v, (1, 4000))))
     }
-    c
-}
 
-fn negative_constraint(constraint: Constraint, var: char, op: char, val: usize) -> Constraint {
-    match op {
-        '>' => apply_constraint(constraint, var, '<', val + 1),
-        '<' => apply_constraint(constraint, var, '>', val - 1),
-        _ => panic!("Bad constraint op"),
+    pub fn bounds(&self, var: &str) -> Option<Bounds> {
+        self.0.get(var).copied()
     }
-}
 
-fn combine_constraints(c1: Constraint, c2: Constraint) -> Constraint {
-    let ((x1l, x1u), (m1l, m1u), (a1l, a1u), (s1l, s1u)) = c1;
-    let ((x2l, x2u), (m2l, m2u), (a2l, a2u), (s2l, s2u)) = c2;
-    let x = (max(x1l, x2l), min(x1u, x2u));
-    let m = (max(m1l, m2l), min(m1u, m2u));
-    let a = (max(a1l, a2l), min(a1u, a2u));
-    let s = (max(s1l, s2l), min(s1u, s2u));
-    (x, m, a, s)
-}
+    /// The parts in both boxes, if there are any.
+    pub fn intersect(&self, other: &Constraint) -> Option<Constraint> {
+        let mut c = self.clone();
+        for (var, &(lo, hi)) in &other.0 {
+            let bounds = c.0.entry(var.clone()).or_insert((lo, hi));
+            *bounds = (bounds.0.max(lo), bounds.1.min(hi));
+            if bounds.0 > bounds.1 {
+                return None;
+            }
+        }
+        Some(c)
+    }
 
-fn constraint_combinations(c: Constraint) -> usize {
-    let ((xl, xu), (ml, mu), (al, au), (sl, su)) = c;
-    (xu + 1 - xl) * (mu + 1 - ml) * (au + 1 - al) * (su + 1 - sl)
-}
+    pub fn combinations(&self) -> usize {
+        self.0.values().map(|(lo, hi)| hi + 1 - lo).product()
+    }
 
-fn starting_constraint(min: usize, max: usize) -> Constraint {
-    ((min, max), (min, max), (min, max), (min, max))
+    fn with(&self, var: &str, bounds: Bounds) -> Constraint {
+        let mut c = self.clone();
+        c.0.insert(String::from(var), bounds);
+        c
+    }
+
+    /// The boxes that pass and fail `condition`, which together make up
+    /// this one.
+    fn split(&self, condition: &Condition) -> std::io::Result<(Vec<Self>, Vec<Self>)> {
+        let Some(bounds) = self.bounds(&condition.var) else {
+            return error(&format!("No rating range for {}", condition.var));
+        };
+        let (pass, fail) = condition.op.split(bounds, condition.value);
+        let boxes = |b: Vec<Bounds>| {
+            b.into_iter()
+                .map(|b| self.with(&condition.var, b))
+                .collect()
+        };
+        Ok((boxes(pass), boxes(fail)))
+    }
 }
 
-fn part_1(input_file: &str) -> std::io::Result<usize> {
-    let input: Input = parse(input_file)?;
-    Ok(input.total_rating())
+impl fmt::Display for Constraint {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let bounds: Vec<_> = self
+            .0
+            .iter()
+            .map(|(var, (lo, hi))| format!("{}={}..={}", var, lo, hi))
+            .collect();
+        write!(f, "{{{}}}", bounds.join(","))
+    }
 }
 
-fn part_2(input_file: &str) -> std::io::Result<usize> {
+/// Every box of parts within `ratings` that the workflows accept. The boxes
+/// don't overlap, so their combinations can just be added up.
+pub fn accepted_boxes(input_file: &str, ratings: &Constraint) -> std::io::Result<Vec<Constraint>> {
     let input: Input = parse(input_file)?;
-    let mut queue = VecDeque::<(Constraint, String)>::new();
+    let mut queue = VecDeque::<(Constraint, &str, usize)>::new();
     let mut accepted = Vec::<Constraint>::new();
-    queue.push_back((starting_constraint(1, 4000), String::from("in")));
-    while let Some((mut constraint, workflow)) = queue.pop_front() {
-        let rules = input.workflow_map.get(&workflow).unwrap();
-        for rule in rules {
-            match rule {
-                Rule::Condition(var, op, val, dest) if dest.as_str() == "A" => {
-                    accepted.push(apply_constraint(constraint, *var, *op, *val));
-                    constraint = negative_constraint(constraint, *var, *op, *val);
-                }
-                Rule::Condition(var, op, val, dest) if dest.as_str() == "R" => {
-                    constraint = negative_constraint(constraint, *var, *op, *val);
-                }
-                Rule::Condition(var, op, val, dest) => {
-                    queue.push_back((apply_constraint(constraint, *var, *op, *val), dest.clone()));
-                    constraint = negative_constraint(constraint, *var, *op, *val);
-                }
-                Rule::Fallback(dest) if dest.as_str() == "A" => {
-                    accepted.push(constraint);
-                }
-                Rule::Fallback(dest) if dest.as_str() == "R" => (),
-                Rule::Fallback(dest) => {
-                    queue.push_back((constraint, dest.clone()));
+    queue.push_back((ratings.clone(), "in", 0));
+    while let Some((constraint, workflow, depth)) = queue.pop_front() {
+        if depth > input.workflows.len() {
+            return error("Workflows send parts round in a loop");
+        }
+        let mut remaining = vec![constraint];
+        for rule in input.rules(workflow)? {
+            let (passed, dest) = match rule {
+                Rule::Condition(condition, dest) => {
+                    let mut passed = Vec::new();
+                    let mut failed = Vec::new();
+                    for constraint in &remaining {
+                        let (pass, fail) = constraint.split(condition)?;
+                        passed.extend(pass);
+                        failed.extend(fail);
+                    }
+                    remaining = failed;
+                    (passed, dest)
                 }
+                Rule::Fallback(dest) => (std::mem::take(&mut remaining), dest),
+            };
+            match dest.as_str() {
+                "A" => accepted.extend(passed),
+                "R" => (),
+                _ => queue.extend(passed.into_iter().map(|c| (c, dest.as_str(), depth + 1))),
             }
         }
     }
-    let total = accepted.iter().map(|c| constraint_combinations(*c)).sum();
-    Ok(total)
+    Ok(accepted)
 }
 
+pub fn accepted_combinations(input_file: &str, ratings: &Constraint) -> std::io::Result<usize> {
+    let boxes = accepted_boxes(input_file, ratings)?;
+    Ok(boxes.iter().map(Constraint::combinations).sum())
+}
+
+pub fn part_1(input_file: &str) -> std::io::Result<usize> {
+    let input: Input = parse(input_file)?;
+    input.total_rating()
+}
+
+pub fn part_2(input_file: &str) -> std::io::Result<usize> {
+    accepted_combinations(input_file, &Constraint::xmas())
+}
+
+solution!(19);
+
 #[cfg(test)]
 mod tests {
-    use super::{apply_constraint, combine_constraints, part_1, part_2};
+    use super::{accepted_boxes, accepted_combinations, part_1, part_2, Constraint};
 
     const INPUT: &str = "input/gee/q19_input.txt";
     const INPUT_SAMPLE: &str = "input/gee/q19_sample.txt";
+    const INPUT_SAMPLE_2: &str = "input/gee/q19_sample_2.txt";
 
     #[test]
     fn gee_q19_testio() {
-        let c1 = ((1, 4000), (1, 4000), (1, 4000), (1, 4000));
-        let c2 = apply_constraint(c1, 'm', '<', 35);
-        let c3 = apply_constraint(c1, 'x', '>', 3032);
-        let c4 = combine_constraints(c2, c3);
-        assert_eq!(c4, ((3033, 4000), (1, 34), (1, 4000), (1, 4000)));
+        let c1 = Constraint::xmas();
+        let c2 = Constraint::new([("m", (1, 34))]);
+        let c3 = Constraint::new([("x", (3033, 4000))]);
+        let c4 = c1.intersect(&c2).unwrap().intersect(&c3).unwrap();
+        assert_eq!(c4.bounds("x"), Some((3033, 4000)));
+        assert_eq!(c4.bounds("m"), Some((1, 34)));
+        assert_eq!(c4.combinations(), 968 * 34 * 4000 * 4000);
+        assert_eq!(c2.intersect(&Constraint::new([("m", (35, 40))])), None);
+        assert_eq!(c3.to_string(), "{x=3033..=4000}");
     }
 
     #[test]
@@ -260,4 +372,20 @@ mod tests {
         let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 116738260946855);
     }
+
+    #[test]
+    fn gee_q19_custom_ratings() {
+        assert_eq!(part_1(INPUT_SAMPLE_2).unwrap(), 118);
+        let ratings = Constraint::new([("cool", (1, 20)), ("speed", (1, 10))]);
+        let boxes = accepted_boxes(INPUT_SAMPLE_2, &ratings).unwrap();
+        assert_eq!(boxes.len(), 4);
+        assert!(boxes.contains(&Constraint::new([("cool", (3, 3)), ("speed", (1, 10))])));
+        for (i, a) in boxes.iter().enumerate() {
+            assert!(boxes[i + 1..].iter().all(|b| a.intersect(b).is_none()));
+        }
+        let result = accepted_combinations(INPUT_SAMPLE_2, &ratings);
+        assert_eq!(result.unwrap(), 10 + 11 * 9);
+        // The puzzle's own ratings don't cover these variables
+        assert!(part_2(INPUT_SAMPLE_2).is_err());
+    }
 }
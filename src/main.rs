@@ -1,11 +1,97 @@
+mod bench;
+mod compare;
 mod gee;
+mod registry;
 mod roar;
+mod solution;
 mod utils;
 
 use ferris_says::say;
+use std::env;
 use std::io::{stdout, BufWriter};
+use utils::parser::error;
+
+// Not in tests, where the harness runs tests on parallel threads whose
+// allocations would all land in the same counters
+#[cfg(not(test))]
+#[global_allocator]
+static ALLOC: bench::CountingAlloc = bench::CountingAlloc;
+
+const USAGE: &str = "Usage: aoc-2023 [ gee | roar ] <day> <part> <input file | ->
+       aoc-2023 list
+       aoc-2023 compare [ <day> [ <input file> ] ]
+       aoc-2023 dot [ gee | roar ] <day> <input file | ->
+       aoc-2023 bench [ gee | roar ] [ <day> ] [ --runs <n> ] [ --timeout <secs> ] [ --out <file> ]";
 
 fn main() -> std::io::Result<()> {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match args.as_slice() {
+        [] => {
+            banner()?;
+            eprintln!("{}", USAGE);
+            Ok(())
+        }
+        [command] if command == "list" => {
+            for author in registry::AUTHORS {
+                let days = registry::days(author).unwrap();
+                let numbers: Vec<String> = days.iter().map(|d| d.day.to_string()).collect();
+                println!("{}: {}", author, numbers.join(" "));
+            }
+            Ok(())
+        }
+        [command] if command == "compare" => {
+            print!(
+                "{}",
+                compare::summary(&compare::compare_all(compare::DEFAULT_TIMEOUT))
+            );
+            Ok(())
+        }
+        [command, day, rest @ ..] if command == "compare" && rest.len() <= 1 => {
+            let day = parse_day(day)?;
+            let Some(input_file) = rest
+                .first()
+                .cloned()
+                .or_else(|| compare::default_input(day))
+            else {
+                return error(&format!("No input found for day {}", day));
+            };
+            let comparison = compare::compare_day(day, &input_file, compare::DEFAULT_TIMEOUT);
+            print!("{}", compare::summary(&[comparison]));
+            Ok(())
+        }
+        [command, author, day, input_file] if command == "dot" => {
+            let day = parse_day(day)?;
+            print!("{}", registry::dot(author, day, input_file)?);
+            Ok(())
+        }
+        [command, rest @ ..] if command == "bench" => {
+            let options = bench::Options::parse(rest)?;
+            let benchmarks = bench::bench_all(&options);
+            print!("{}", bench::summary(&benchmarks));
+            bench::save(&benchmarks, &options.output)?;
+            println!("Saved to {}", options.output);
+            Ok(())
+        }
+        [author, day, part, input_file] => {
+            let day = parse_day(day)?;
+            let Ok(part) = part.parse() else {
+                return error(&format!("Bad part: {}", part));
+            };
+            println!("{}", registry::run(author, day, part, input_file)?);
+            Ok(())
+        }
+        _ => error(USAGE),
+    }
+}
+
+fn parse_day(day: &str) -> std::io::Result<u8> {
+    match day.parse() {
+        Ok(day) => Ok(day),
+        Err(_) => error(&format!("Bad day: {}", day)),
+    }
+}
+
+fn banner() -> std::io::Result<()> {
     let stdout: std::io::Stdout = stdout();
     let message = "AOC 2023!";
     let width: usize = message.chars().count();
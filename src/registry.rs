@@ -0,0 +1,120 @@
+use crate::solution::{Answer, Solution};
+use crate::utils::dot::Dot;
+use crate::utils::parser::error;
+use crate::{gee, roar};
+use std::io;
+
+pub type Part = fn(&str) -> io::Result<Answer>;
+
+/// Renders a day's input as a GraphViz graph.
+pub type Export = fn(&str) -> io::Result<Dot>;
+
+pub struct Day {
+    pub day: u8,
+    pub part_1: Part,
+    pub part_2: Part,
+}
+
+impl Day {
+    /// Registers a solution, running each part with its default parameters.
+    pub const fn of<S: Solution>() -> Self {
+        Day {
+            day: S::DAY,
+            part_1: part_1::<S>,
+            part_2: part_2::<S>,
+        }
+    }
+}
+
+fn part_1<S: Solution>(input_file: &str) -> io::Result<Answer> {
+    S::part_1(input_file, &S::Params::default())
+}
+
+fn part_2<S: Solution>(input_file: &str) -> io::Result<Answer> {
+    S::part_2(input_file, &S::Params::default())
+}
+
+pub const AUTHORS: [&str; 2] = ["gee", "roar"];
+
+pub fn days(author: &str) -> Option<&'static [Day]> {
+    match author {
+        "gee" => Some(gee::DAYS),
+        "roar" => Some(roar::DAYS),
+        _ => None,
+    }
+}
+
+pub fn find(author: &str, day: u8) -> Option<&'static Day> {
+    days(author)?.iter().find(|d| d.day == day)
+}
+
+pub fn run(author: &str, day: u8, part: u8, input_file: &str) -> io::Result<Answer> {
+    if days(author).is_none() {
+        return error(&format!("Unknown author: {}", author));
+    }
+    let Some(solution) = find(author, day) else {
+        return error(&format!("No solution from {} for day {}", author, day));
+    };
+    match part {
+        1 => (solution.part_1)(input_file),
+        2 => (solution.part_2)(input_file),
+        _ => error(&format!("Bad part: {}", part)),
+    }
+}
+
+fn exports(author: &str) -> Option<&'static [(u8, Export)]> {
+    match author {
+        "gee" => Some(gee::EXPORTS),
+        "roar" => Some(roar::EXPORTS),
+        _ => None,
+    }
+}
+
+pub fn dot(author: &str, day: u8, input_file: &str) -> io::Result<Dot> {
+    let Some(exports) = exports(author) else {
+        return error(&format!("Unknown author: {}", author));
+    };
+    match exports.iter().find(|(d, _)| *d == day) {
+        Some((_, export)) => export(input_file),
+        None => error(&format!("No graph from {} for day {}", author, day)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{days, dot, find, run, AUTHORS};
+    use crate::solution::Answer;
+
+    #[test]
+    fn registry_days_are_unique_and_ordered() {
+        for author in AUTHORS {
+            let numbers: Vec<u8> = days(author).unwrap().iter().map(|d| d.day).collect();
+            let expected: Vec<u8> = (1..=numbers.len() as u8).collect();
+            assert_eq!(numbers, expected);
+        }
+    }
+
+    #[test]
+    fn registry_run() {
+        let result = run("gee", 1, 1, "input/gee/q01_sample.txt");
+        assert_eq!(result.unwrap(), Answer(142));
+        let result = run("roar", 21, 1, "input/roar/q21_input.txt");
+        assert_eq!(result.unwrap(), Answer(3658));
+    }
+
+    #[test]
+    fn registry_unknown() {
+        assert!(find("gee", 25).is_none());
+        assert!(run("nobody", 1, 1, "input/gee/q01_sample.txt").is_err());
+        assert!(run("gee", 1, 3, "input/gee/q01_sample.txt").is_err());
+    }
+
+    #[test]
+    fn registry_dot() {
+        let graph = dot("roar", 25, "input/roar/q25_sample.txt").unwrap();
+        assert!(graph.to_string().starts_with("graph \"wiring\" {"));
+        assert!(dot("gee", 20, "input/gee/q20_sample.txt").is_ok());
+        assert!(dot("gee", 1, "input/gee/q01_sample.txt").is_err());
+        assert!(dot("nobody", 8, "input/gee/q08_sample.txt").is_err());
+    }
+}
@@ -23,3 +23,40 @@ mod q22;
 mod q23;
 mod q24;
 mod q25;
+
+use crate::registry::{Day, Export};
+
+pub const DAYS: &[Day] = &[
+    Day::of::<q01::Solver>(),
+    Day::of::<q02::Solver>(),
+    Day::of::<q03::Solver>(),
+    Day::of::<q04::Solver>(),
+    Day::of::<q05::Solver>(),
+    Day::of::<q06::Solver>(),
+    Day::of::<q07::Solver>(),
+    Day::of::<q08::Solver>(),
+    Day::of::<q09::Solver>(),
+    Day::of::<q10::Solver>(),
+    Day::of::<q11::Solver>(),
+    Day::of::<q12::Solver>(),
+    Day::of::<q13::Solver>(),
+    Day::of::<q14::Solver>(),
+    Day::of::<q15::Solver>(),
+    Day::of::<q16::Solver>(),
+    Day::of::<q17::Solver>(),
+    Day::of::<q18::Solver>(),
+    Day::of::<q19::Solver>(),
+    Day::of::<q20::Solver>(),
+    Day::of::<q21::Solver>(),
+    Day::of::<q22::Solver>(),
+    Day::of::<q23::Solver>(),
+    Day::of::<q24::Solver>(),
+    Day::of::<q25::Solver>(),
+];
+
+pub const EXPORTS: &[(u8, Export)] = &[
+    (10, q10::to_dot),
+    (20, q20::to_dot),
+    (23, q23::to_dot),
+    (25, q25::to_dot),
+];
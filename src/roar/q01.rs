@@ -1,21 +1,5 @@
-use std::{
-    fs::File,
-    io::{self, BufRead},
-};
-
-fn _read_lines(file_path: &str) -> std::io::Result<Vec<String>> {
-    let file: File = File::open(file_path).expect("Unable to open file");
-
-    let reader: io::BufReader<File> = io::BufReader::new(file);
-    let mut lines: Vec<String> = Vec::new();
-
-    for line in reader.lines() {
-        let line: String = line.expect("Unable to read line");
-        lines.push(line);
-    }
-
-    Ok(lines)
-}
+use crate::solution::solution;
+use crate::utils::parser::FileLines;
 
 fn _get_number_as_word_if_exists(word_to_check: &str, words: &[&str]) -> Option<usize> {
     words.iter().enumerate().find_map(|(index, &word)| {
@@ -27,17 +11,19 @@ fn _get_number_as_word_if_exists(word_to_check: &str, words: &[&str]) -> Option<
     })
 }
 
-fn _part_1(file_path: &str) -> std::io::Result<u32> {
-    let lines: Vec<String> = _read_lines(file_path).unwrap();
+pub fn part_1(file_path: &str) -> std::io::Result<u32> {
+    let mut lines = FileLines::open(file_path)?;
     let mut numbers: Vec<u32> = Vec::new();
 
-    for line in lines.iter() {
-        let first_number = line.chars().find(|&c| c.is_ascii_digit()).unwrap();
-
-        let second_number = line.chars().rev().find(|&c| c.is_ascii_digit()).unwrap();
+    while let Some(line) = lines.next() {
+        let (Some(first_number), Some(second_number)) = (
+            line.chars().find_map(|c| c.to_digit(10)),
+            line.chars().rev().find_map(|c| c.to_digit(10)),
+        ) else {
+            return Err(lines.error_at(&line, &line, "a digit"));
+        };
 
-        let combined_num = format!("{}{}", first_number, second_number);
-        let number = combined_num.parse::<u32>().unwrap();
+        let number = first_number * 10 + second_number;
         numbers.push(number);
     }
 
@@ -47,19 +33,18 @@ fn _part_1(file_path: &str) -> std::io::Result<u32> {
     Ok(sum)
 }
 
-fn _part_2(file_path: &str) -> std::io::Result<u32> {
-    let lines: Vec<String> = _read_lines(file_path).unwrap();
+pub fn part_2(file_path: &str) -> std::io::Result<u32> {
+    let mut lines = FileLines::open(file_path)?;
     let mut numbers: Vec<u32> = Vec::new();
     let number_lookup = [
         "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
     ];
-    for line in lines.iter() {
+    while let Some(line) = lines.next() {
         let mut word_to_check = String::new();
         let mut first_number = 0;
         let mut second_number = 0;
         for c in line.chars() {
-            if c.is_ascii_digit() {
-                let number = c.to_digit(10).unwrap();
+            if let Some(number) = c.to_digit(10) {
                 first_number = number;
                 break;
             }
@@ -76,8 +61,7 @@ fn _part_2(file_path: &str) -> std::io::Result<u32> {
         word_to_check.clear();
 
         for c in line.chars().rev() {
-            if c.is_ascii_digit() {
-                let number = c.to_digit(10).unwrap();
+            if let Some(number) = c.to_digit(10) {
                 second_number = number;
                 break;
             }
@@ -91,8 +75,11 @@ fn _part_2(file_path: &str) -> std::io::Result<u32> {
             }
         }
 
-        let combined_num = format!("{}{}", first_number, second_number);
-        let number = combined_num.parse::<u32>().unwrap();
+        if first_number == 0 {
+            return Err(lines.error_at(&line, &line, "a digit or digit name"));
+        }
+
+        let number = first_number * 10 + second_number;
         numbers.push(number);
     }
 
@@ -101,27 +88,39 @@ fn _part_2(file_path: &str) -> std::io::Result<u32> {
     Ok(sum)
 }
 
+solution!(1);
+
 #[cfg(test)]
 mod tests {
-    use super::{_part_1, _part_2};
+    use super::{part_1, part_2};
+    use crate::utils::parser::ParseError;
 
     #[test]
     fn roar_q01_p1_sample() {
-        assert_eq!(_part_1("input/roar/q1-p1-sample.txt").unwrap(), 142);
+        assert_eq!(part_1("input/roar/q1-p1-sample.txt").unwrap(), 142);
     }
 
     #[test]
     fn roar_q01_p1_main() {
-        assert_eq!(_part_1("input/roar/q1-p1-input.txt").unwrap(), 54968);
+        assert_eq!(part_1("input/roar/q1-p1-input.txt").unwrap(), 54968);
     }
 
     #[test]
     fn roar_q01_p2_sample() {
-        assert_eq!(_part_2("input/roar/q1-p2-sample.txt").unwrap(), 281);
+        assert_eq!(part_2("input/roar/q1-p2-sample.txt").unwrap(), 281);
     }
 
     #[test]
     fn roar_q01_p2_main() {
-        assert_eq!(_part_2("input/roar/q1-p2-input.txt").unwrap(), 54094);
+        assert_eq!(part_2("input/roar/q1-p2-input.txt").unwrap(), 54094);
+    }
+
+    #[test]
+    fn roar_q01_no_digits() {
+        let error = part_1("input/roar/q23_sample.txt").unwrap_err();
+        let error = ParseError::of(&error).unwrap();
+        assert_eq!((error.line, error.expected.as_str()), (1, "a digit"));
+        assert!(part_2("input/roar/q23_sample.txt").is_err());
+        assert!(part_1("input/nobody/q01_input.txt").is_err());
     }
 }
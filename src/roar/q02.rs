@@ -1,3 +1,4 @@
+use crate::solution::solution;
 use crate::utils::parser::FileLines;
 
 struct Input {
@@ -12,18 +13,17 @@ impl TryFrom<FileLines> for Input {
     }
 }
 
-fn _part_1(input: &str) -> std::io::Result<u32> {
+pub fn part_1(input: &str) -> std::io::Result<u32> {
     let max_red_cubes: u32 = 12;
     let max_green_cubes: u32 = 13;
     let max_blue_cubes: u32 = 14;
-    let lines = FileLines::new(input)?;
+    let mut lines = FileLines::new(input)?;
     let mut result: u32 = 0;
-    for line in lines {
-        let game_input: Vec<&str> = line.split(':').collect();
-        let game_name = game_input[0].trim();
-        let game_name_parts: Vec<&str> = game_name.split_whitespace().collect();
-        let game_id: u32 = game_name_parts[1].parse().unwrap();
-        let game_cubes = game_input[1].trim();
+    while let Some(line) = lines.next() {
+        let (game_name, game_cubes) = lines.split_once(&line, &line, ":")?;
+        let game_id = lines.strip_prefix(&line, game_name.trim(), "Game ")?;
+        let game_id: u32 = lines.parse_at(&line, game_id, "a game id")?;
+        let game_cubes = game_cubes.trim();
         let game_cubes_sets: Vec<&str> = game_cubes.split(';').collect();
 
         let mut game_is_possible = true;
@@ -31,9 +31,10 @@ fn _part_1(input: &str) -> std::io::Result<u32> {
         for game_set in game_cubes_sets {
             let set_parts: Vec<&str> = game_set.split(',').collect();
             for set_part in set_parts {
-                let game_cube_parts: Vec<&str> = set_part.split_whitespace().collect();
-                let game_cube_count: u32 = game_cube_parts[0].parse().unwrap();
-                let game_cube_color = game_cube_parts[1];
+                let (game_cube_count, game_cube_color) =
+                    lines.split_once(&line, set_part.trim(), " ")?;
+                let game_cube_count: u32 =
+                    lines.parse_at(&line, game_cube_count, "a cube count")?;
                 match game_cube_color {
                     "red" => {
                         if game_cube_count > max_red_cubes {
@@ -50,7 +51,7 @@ fn _part_1(input: &str) -> std::io::Result<u32> {
                             game_is_possible = false;
                         }
                     }
-                    _ => {}
+                    _ => return Err(lines.error_at(&line, game_cube_color, "red, green or blue")),
                 }
             }
         }
@@ -62,12 +63,12 @@ fn _part_1(input: &str) -> std::io::Result<u32> {
     Ok(result)
 }
 
-fn _part_2(input: &str) -> std::io::Result<u32> {
-    let lines = FileLines::new(input)?;
+pub fn part_2(input: &str) -> std::io::Result<u32> {
+    let mut lines = FileLines::new(input)?;
     let mut result: u32 = 0;
-    for line in lines {
-        let game_input: Vec<&str> = line.split(':').collect();
-        let game_cubes = game_input[1].trim();
+    while let Some(line) = lines.next() {
+        let (_, game_cubes) = lines.split_once(&line, &line, ":")?;
+        let game_cubes = game_cubes.trim();
         let game_cubes_sets: Vec<&str> = game_cubes.split(';').collect();
 
         let mut min_blue = 0;
@@ -77,9 +78,10 @@ fn _part_2(input: &str) -> std::io::Result<u32> {
         for game_set in game_cubes_sets {
             let set_parts: Vec<&str> = game_set.split(',').collect();
             for set_part in set_parts {
-                let game_cube_parts: Vec<&str> = set_part.split_whitespace().collect();
-                let game_cube_count: u32 = game_cube_parts[0].parse().unwrap();
-                let game_cube_color = game_cube_parts[1];
+                let (game_cube_count, game_cube_color) =
+                    lines.split_once(&line, set_part.trim(), " ")?;
+                let game_cube_count: u32 =
+                    lines.parse_at(&line, game_cube_count, "a cube count")?;
                 match game_cube_color {
                     "red" => {
                         if game_cube_count > min_red {
@@ -96,7 +98,7 @@ fn _part_2(input: &str) -> std::io::Result<u32> {
                             min_blue = game_cube_count;
                         }
                     }
-                    _ => {}
+                    _ => return Err(lines.error_at(&line, game_cube_color, "red, green or blue")),
                 }
             }
         }
@@ -105,9 +107,11 @@ fn _part_2(input: &str) -> std::io::Result<u32> {
     Ok(result)
 }
 
+solution!(2);
+
 #[cfg(test)]
 mod tests {
-    use super::{_part_1, _part_2};
+    use super::{part_1, part_2};
 
     const INPUT: &str = "input/roar/q02_input.txt";
     const FIRST_INPUT_SAMPLE: &str = "input/roar/q02_p1_sample.txt";
@@ -115,25 +119,25 @@ mod tests {
 
     #[test]
     fn roar_q02_p1_sample() {
-        let result = _part_1(FIRST_INPUT_SAMPLE);
+        let result = part_1(FIRST_INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 8);
     }
 
     #[test]
     fn roar_q02_p1_main() {
-        let result = _part_1(INPUT);
+        let result = part_1(INPUT);
         assert_eq!(result.unwrap(), 2164);
     }
 
     #[test]
     fn roar_q02_p2_sample() {
-        let result = _part_2(SECOND_INPUT_SAMPLE);
+        let result = part_2(SECOND_INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 2286);
     }
 
     #[test]
     fn roar_q02_p2_main() {
-        let result = _part_2(INPUT);
+        let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 69929);
     }
 }
@@ -1,3 +1,4 @@
+use crate::solution::solution;
 use crate::utils::parser::FileLines;
 
 struct Input {
@@ -85,8 +86,8 @@ fn _parse_number_at_index(row: &Vec<char>, col: usize) -> u32 {
     number_str.parse::<u32>().unwrap()
 }
 
-fn _part_1(input_file: &str) -> std::io::Result<u32> {
-    let schematic = _build_schematic(input_file).unwrap();
+pub fn part_1(input_file: &str) -> std::io::Result<u32> {
+    let schematic = _build_schematic(input_file)?;
     let mut result: u32 = 0;
     let mut number_str: String = String::new();
     let mut number_is_valid: bool = false;
@@ -112,8 +113,8 @@ fn _part_1(input_file: &str) -> std::io::Result<u32> {
     Ok(result)
 }
 
-fn _part_2(input_file: &str) -> std::io::Result<u32> {
-    let schematic = _build_schematic(input_file).unwrap();
+pub fn part_2(input_file: &str) -> std::io::Result<u32> {
+    let schematic = _build_schematic(input_file)?;
     let mut result: u32 = 0;
 
     for (row_index, row) in schematic.iter().enumerate() {
@@ -185,34 +186,36 @@ fn _part_2(input_file: &str) -> std::io::Result<u32> {
     Ok(result)
 }
 
+solution!(3);
+
 #[cfg(test)]
 mod tests {
-    use super::{_part_1, _part_2};
+    use super::{part_1, part_2};
 
     const INPUT: &str = "input/roar/q03_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q03_sample.txt";
 
     #[test]
     fn roar_q03_p1_sample() {
-        let result = _part_1(INPUT_SAMPLE);
+        let result = part_1(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 4361);
     }
 
     #[test]
     fn roar_q03_p1_main() {
-        let result = _part_1(INPUT);
+        let result = part_1(INPUT);
         assert_eq!(result.unwrap(), 532331);
     }
 
     #[test]
     fn roar_q03_p2_sample() {
-        let result = _part_2(INPUT_SAMPLE);
+        let result = part_2(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 467835);
     }
 
     #[test]
     fn roar_q03_p2_main() {
-        let result = _part_2(INPUT);
+        let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 82301120);
     }
 }
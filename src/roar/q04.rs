@@ -1,3 +1,4 @@
+use crate::solution::solution;
 use crate::utils::parser::FileLines;
 use std::collections::HashMap;
 use std::collections::HashSet;
@@ -14,21 +15,23 @@ impl TryFrom<FileLines> for Input {
     }
 }
 
-fn _part_1(input_file: &str) -> std::io::Result<u32> {
-    let lines = FileLines::new(input_file)?;
+pub fn part_1(input_file: &str) -> std::io::Result<u32> {
+    let mut lines = FileLines::new(input_file)?;
     let mut result: u32 = 0;
-    for line in lines {
-        let parts: Vec<&str> = line.split(':').collect();
-        let numbers_parts: Vec<&str> = parts[1].split('|').collect();
+    while let Some(line) = lines.next() {
+        let (_, numbers) = lines.split_once(&line, &line, ":")?;
+        let numbers_parts = lines.split_once(&line, numbers, "|")?;
 
-        let winning_numbers: Vec<i32> = numbers_parts[0]
+        let winning_numbers: Vec<i32> = numbers_parts
+            .0
             .split_whitespace()
-            .map(|s| s.parse().unwrap())
-            .collect();
-        let our_numbers: Vec<i32> = numbers_parts[1]
+            .map(|s| lines.parse_at(&line, s, "a number"))
+            .collect::<Result<_, _>>()?;
+        let our_numbers: Vec<i32> = numbers_parts
+            .1
             .split_whitespace()
-            .map(|s| s.parse().unwrap())
-            .collect();
+            .map(|s| lines.parse_at(&line, s, "a number"))
+            .collect::<Result<_, _>>()?;
 
         let mut winning_numbers_set: HashSet<i32> = HashSet::new();
         for num in &winning_numbers {
@@ -49,23 +52,26 @@ fn _part_1(input_file: &str) -> std::io::Result<u32> {
     Ok(result)
 }
 
-fn _part_2(input_file: &str) -> std::io::Result<u32> {
-    let lines = FileLines::new(input_file)?;
+pub fn part_2(input_file: &str) -> std::io::Result<u32> {
+    let mut lines = FileLines::new(input_file)?;
     let mut card_counts: HashMap<i32, i32> = HashMap::new();
-    for line in lines {
-        let parts: Vec<&str> = line.split(':').collect();
-        let card_num: i32 = parts[0].split_whitespace().last().unwrap().parse().unwrap();
+    while let Some(line) = lines.next() {
+        let (card, numbers) = lines.split_once(&line, &line, ":")?;
+        let card_num = lines.strip_prefix(&line, card, "Card")?.trim();
+        let card_num: i32 = lines.parse_at(&line, card_num, "a card number")?;
         *card_counts.entry(card_num).or_insert(0) += 1;
-        let numbers_parts: Vec<&str> = parts[1].split('|').collect();
+        let numbers_parts = lines.split_once(&line, numbers, "|")?;
 
-        let winning_numbers: Vec<i32> = numbers_parts[0]
+        let winning_numbers: Vec<i32> = numbers_parts
+            .0
             .split_whitespace()
-            .map(|s| s.parse().unwrap())
-            .collect();
-        let our_numbers: Vec<i32> = numbers_parts[1]
+            .map(|s| lines.parse_at(&line, s, "a number"))
+            .collect::<Result<_, _>>()?;
+        let our_numbers: Vec<i32> = numbers_parts
+            .1
             .split_whitespace()
-            .map(|s| s.parse().unwrap())
-            .collect();
+            .map(|s| lines.parse_at(&line, s, "a number"))
+            .collect::<Result<_, _>>()?;
 
         let mut winning_numbers_set: HashSet<i32> = HashSet::new();
         for num in &winning_numbers {
@@ -88,35 +94,37 @@ fn _part_2(input_file: &str) -> std::io::Result<u32> {
     Ok(total_count_u32)
 }
 
+solution!(4);
+
 #[cfg(test)]
 mod tests {
-    use super::{_part_1, _part_2};
+    use super::{part_1, part_2};
 
     const INPUT: &str = "input/roar/q04_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q04_sample.txt";
 
     #[test]
     fn roar_q04_p1_sample() {
-        let result = _part_1(INPUT_SAMPLE);
+        let result = part_1(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 13);
     }
 
     #[test]
     fn roar_q04_p1_main() {
-        let result = _part_1(INPUT);
+        let result = part_1(INPUT);
         assert_eq!(result.unwrap(), 32001);
     }
 
     #[test]
     fn roar_q04_p2_sample() {
-        let result = _part_2(INPUT_SAMPLE);
+        let result = part_2(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 30);
     }
 
     #[ignore = "Takes too long"]
     #[test]
     fn roar_q04_p2_main() {
-        let result = _part_2(INPUT);
+        let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 5037841);
     }
 }
@@ -1,9 +1,8 @@
-use std::{
-    cmp::{max, min},
-    collections::HashMap,
-};
+use std::collections::HashMap;
 
-use crate::utils::parser::FileLines;
+use crate::solution::solution;
+use crate::utils::parser::{error, FileLines, Headed};
+use crate::utils::ranges::{chain_apply, chain_get, RangeMap, RangeSet};
 
 #[derive(Debug, Clone)]
 struct ConversionMap {
@@ -15,6 +14,8 @@ struct ConversionMap {
 #[derive(Debug)]
 struct SeedAlmanac {
     _seeds: Vec<u64>,
+    // Why the seeds can't be read as ranges, which only part 2 does
+    _bad_seed_ranges: Option<std::io::Error>,
     _mappings: HashMap<String, Vec<ConversionMap>>,
 }
 
@@ -33,179 +34,192 @@ const _MAP_STEPS: [&str; 7] = [
     "humidity-to-location",
 ];
 
+struct ConversionMaps(Vec<ConversionMap>);
+
+impl TryFrom<FileLines> for ConversionMaps {
+    type Error = std::io::Error;
+
+    fn try_from(mut _lines: FileLines) -> Result<Self, Self::Error> {
+        let conversion_maps = _lines.parse_each(|lines, line| {
+            let nums: Vec<u64> = line
+                .split_whitespace()
+                .map(|s| lines.parse_at::<u64>(line, s, "a number"))
+                .collect::<Result<_, _>>()?;
+            let [destination, source, length] = nums[..] else {
+                return Err(lines.error_at(line, line, "three numbers"));
+            };
+            if source.max(destination).checked_add(length).is_none() {
+                return Err(lines.error_at(line, line, "ranges that fit in 64 bits"));
+            }
+            Ok(ConversionMap {
+                _destination_range_start: destination,
+                _source_range_start: source,
+                _range_length: length,
+            })
+        })?;
+        Ok(ConversionMaps(conversion_maps))
+    }
+}
+
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
     fn try_from(_lines: FileLines) -> Result<Self, Self::Error> {
+        let mut sections = _lines.sections();
+        let mut seeds = sections.expect("seeds")?;
+        let line = seeds.expect_line("seeds")?;
+        let seed_list = seeds.strip_prefix(&line, &line, "seeds: ")?;
+        let seed_numbers: Vec<u64> = seed_list
+            .split_whitespace()
+            .map(|s| seeds.parse_at::<u64>(&line, s, "a seed number"))
+            .collect::<Result<_, _>>()?;
+        if seed_numbers.is_empty() {
+            return Err(seeds.error_at(&line, seed_list, "a seed number"));
+        }
+        let bad_seed_ranges = if seed_numbers.len() % 2 == 1 {
+            Some(seeds.error_at(&line, &line[line.len()..], "a seed range length"))
+        } else if seed_numbers
+            .chunks_exact(2)
+            .any(|seed_range| seed_range[0].checked_add(seed_range[1]).is_none())
+        {
+            Some(seeds.error_at(&line, &line, "seed ranges that fit in 64 bits"))
+        } else {
+            None
+        };
         let mut almanac = SeedAlmanac {
-            _seeds: Vec::new(),
+            _bad_seed_ranges: bad_seed_ranges,
+            _seeds: seed_numbers,
             _mappings: HashMap::new(),
         };
-        let mut current_mapping_type = String::new();
-
-        for line in _lines {
-            if line.starts_with("seeds") {
-                almanac._seeds = line
-                    .split_once(": ")
-                    .unwrap()
-                    .1
-                    .split_whitespace()
-                    .map(|s| s.parse::<u64>().unwrap())
-                    .collect();
-            } else if line.trim().ends_with("map:") {
-                current_mapping_type = line.split_whitespace().next().unwrap().to_string();
-                almanac
-                    ._mappings
-                    .insert(current_mapping_type.clone(), Vec::new());
-            } else if !line.is_empty() && line.chars().next().unwrap().is_numeric() {
-                // starts with a number
-                let nums: Vec<u64> = line
-                    .split_whitespace()
-                    .map(|s| s.parse::<u64>().unwrap())
-                    .collect();
-
-                let conversion_map = ConversionMap {
-                    _destination_range_start: nums[0],
-                    _source_range_start: nums[1],
-                    _range_length: nums[2],
-                };
-                almanac
-                    ._mappings
-                    .get_mut(&current_mapping_type)
-                    .unwrap()
-                    .push(conversion_map);
-            }
+
+        for section in sections {
+            // e.g. "seed-to-soil map:" followed by its conversion maps
+            let mapping: Headed<String, ConversionMaps> = Headed::try_from(section)?;
+            let mapping_type = mapping.header.trim_end_matches(" map").to_string();
+            almanac._mappings.insert(mapping_type, mapping.body.0);
         }
 
         Ok(Input { _almanac: almanac })
     }
 }
 
-fn _calculate_destination(seed: u64, start: u64, destination: u64, range: u64) -> u64 {
-    if seed >= start && seed < start + range {
-        return destination + (seed - start);
-    }
-    seed
+fn _build_maps(almanac: &SeedAlmanac) -> std::io::Result<Vec<RangeMap>> {
+    _MAP_STEPS
+        .iter()
+        .map(|&step| {
+            let Some(conversion_maps) = almanac._mappings.get(step) else {
+                return error(&format!("Missing {} map", step));
+            };
+            RangeMap::new(conversion_maps.iter().map(|cm| {
+                (
+                    cm._destination_range_start,
+                    cm._source_range_start,
+                    cm._range_length,
+                )
+            }))
+        })
+        .collect()
 }
 
-fn _part_1(input_file: &str) -> std::io::Result<u64> {
+pub fn part_1(input_file: &str) -> std::io::Result<u64> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
     let almanac = input._almanac;
-    let mut locations = Vec::new();
-    for seed in almanac._seeds {
-        let mut current = seed;
-        for step in _MAP_STEPS {
-            let conversion_map = almanac._mappings.get(step).unwrap();
-            for conversion_map in conversion_map {
-                let current_location = _calculate_destination(
-                    current,
-                    conversion_map._source_range_start,
-                    conversion_map._destination_range_start,
-                    conversion_map._range_length,
-                );
-                if current_location != current {
-                    current = current_location;
-                    break;
-                }
-            }
-
-            if step == "humidity-to-location" {
-                locations.push(current);
-            }
-        }
+    let maps = _build_maps(&almanac)?;
+    let locations = almanac._seeds.iter().map(|&seed| chain_get(&maps, seed));
+    match locations.min() {
+        Some(location) => Ok(location),
+        None => error("No seeds"),
     }
-    Ok(*locations.iter().min().unwrap())
 }
 
-fn _build_maps(almanac: &SeedAlmanac) -> Vec<&Vec<ConversionMap>> {
-    // Try to reduce nesting later, I definitely made poor choices on structure here
-    let mut maps_list = Vec::new();
-    for step in _MAP_STEPS {
-        let conversion_maps = almanac._mappings.get(step).unwrap();
-        maps_list.push(conversion_maps);
-    }
-    maps_list
-}
-
-fn _part_2(input_file: &str) -> std::io::Result<u64> {
+pub fn part_2(input_file: &str) -> std::io::Result<u64> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
-    let almanac = input._almanac;
-    let mut locations: Vec<u64> = Vec::new();
-
-    for seed_range in almanac._seeds.chunks(2) {
-        println!("Seed range {:?}", seed_range);
-        let mut ranges = vec![(seed_range[0], seed_range[0] + seed_range[1] - 1)];
-
-        for conversion_maps in _build_maps(&almanac) {
-            let mut transformed_ranges = vec![];
-            for cm in conversion_maps {
-                let source_start = cm._source_range_start;
-                let destination = cm._destination_range_start;
-                let source_len = cm._range_length;
-                let source_end = source_start + source_len;
-
-                // Keep track of this madness
-                let mut new_ranges = vec![];
-
-                for (start, end) in ranges {
-                    // Attempt a sort of sliding interval thing
-                    let left_side = (start, min(end, source_start));
-                    let right_side = (max(source_end, start), end);
-                    let interval = (max(start, source_start), min(source_end, end));
-
-                    if left_side.1 > left_side.0 {
-                        new_ranges.push(left_side);
-                    }
-                    if right_side.1 > right_side.0 {
-                        new_ranges.push(right_side);
-                    }
-                    if interval.1 > interval.0 {
-                        // Basically do _calculate_destination on each side of interval
-                        transformed_ranges.push((
-                            destination + (interval.0 - source_start),
-                            destination + (interval.1 - source_start),
-                        ));
-                    }
-                }
-                // Update ranges on each line of the conversion map, e.g. for soil-to-fertilizer and so on
-                ranges = new_ranges;
-            }
-            transformed_ranges.extend(ranges);
-            ranges = transformed_ranges;
-        }
-        locations.push(ranges.iter().min().unwrap().0);
+    let mut almanac = input._almanac;
+    if let Some(e) = almanac._bad_seed_ranges.take() {
+        return Err(e);
+    }
+    let maps = _build_maps(&almanac)?;
+    // Push every seed range through at once, splitting wherever a map does
+    let seeds: RangeSet = almanac
+        ._seeds
+        .chunks_exact(2)
+        .map(|seed_range| seed_range[0]..seed_range[0] + seed_range[1])
+        .collect();
+    // Ranges with nothing in them can leave no seeds at all
+    match chain_apply(&maps, seeds).min() {
+        Some(location) => Ok(location),
+        None => error("No seeds"),
     }
-    Ok(*locations.iter().min().unwrap())
 }
 
+solution!(5);
+
 #[cfg(test)]
 mod tests {
-    use super::{_part_1, _part_2};
+    use super::{part_1, part_2, Input};
+    use crate::utils::parser::{FileLines, ParseError, Source};
 
     const INPUT: &str = "input/roar/q05_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q05_sample.txt";
+    const INPUT_EMPTY_RANGE: &str = "input/roar/q05_empty_range.txt";
 
     #[test]
     fn roar_q05_p1_sample() {
-        let result = _part_1(INPUT_SAMPLE);
+        let result = part_1(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 35);
     }
 
     #[test]
     fn roar_q05_p1_main() {
-        let result = _part_1(INPUT);
+        let result = part_1(INPUT);
         assert_eq!(result.unwrap(), 174137457);
     }
 
     #[test]
     fn roar_q05_p2_sample() {
-        let result = _part_2(INPUT_SAMPLE);
+        let result = part_2(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 46);
     }
 
     #[test]
     fn roar_q05_p2_main() {
-        let result = _part_2(INPUT);
+        let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 1493866);
     }
+
+    #[test]
+    fn roar_q05_unpaired_seed() {
+        let almanac = |seeds: &str| {
+            let text = format!("seeds: {}\n\nseed-to-soil map:\n50 98 2\n", seeds);
+            Input::try_from(FileLines::open(Source::text(text)).unwrap())
+        };
+        assert!(almanac("79 14 55 13")
+            .unwrap()
+            ._almanac
+            ._bad_seed_ranges
+            .is_none());
+        let input = almanac("79 14 55").unwrap();
+        let e = input._almanac._bad_seed_ranges.unwrap();
+        let e = ParseError::of(&e).unwrap();
+        assert_eq!((e.line, e.column), (1, 16));
+        assert_eq!(e.expected, "a seed range length");
+
+        let e = almanac("").err().unwrap();
+        assert_eq!(ParseError::of(&e).unwrap().column, 8);
+        let input = almanac("18446744073709551615 1").unwrap();
+        assert!(input._almanac._bad_seed_ranges.is_some());
+    }
+
+    #[test]
+    fn roar_q05_bad_almanacs() {
+        // The only seed range has nothing in it
+        assert_eq!(part_1(INPUT_EMPTY_RANGE).unwrap(), 0);
+        assert!(part_2(INPUT_EMPTY_RANGE).is_err());
+
+        let text = "seeds: 79 14\n\nseed-to-soil map:\n0 18446744073709551615 2\n";
+        let e = Input::try_from(FileLines::open(Source::text(text)).unwrap())
+            .err()
+            .unwrap();
+        assert_eq!(ParseError::of(&e).unwrap().line, 4);
+    }
 }
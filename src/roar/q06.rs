@@ -1,3 +1,4 @@
+use crate::solution::solution;
 use crate::utils::parser::FileLines;
 
 struct Input {
@@ -8,48 +9,42 @@ struct Input {
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(_lines: FileLines) -> Result<Self, Self::Error> {
+    fn try_from(mut _lines: FileLines) -> Result<Self, Self::Error> {
         let mut races = Vec::new();
         let mut times = Vec::new();
         let mut distances = Vec::new();
         let mut real_race = (0, 0);
 
-        for (i, line) in _lines.enumerate() {
+        while let Some(line) = _lines.next() {
+            let i = _lines.line_number() - 1;
             if i == 0 {
-                times = line
-                    .split_once(": ")
-                    .unwrap()
-                    .1
+                let values = _lines.split_once(&line, &line, ": ")?.1;
+                times = values
                     .split_whitespace()
-                    .map(|s| s.parse::<u64>().unwrap())
-                    .collect();
-                let full_time: String = line
-                    .split_once(": ")
-                    .unwrap()
-                    .1
-                    .split_whitespace()
-                    .collect();
-                real_race.0 = full_time.parse::<u128>().unwrap();
+                    .map(|s| _lines.parse_at::<u64>(&line, s, "a time"))
+                    .collect::<Result<_, _>>()?;
+                let full_time: String = values.split_whitespace().collect();
+                real_race.0 = _lines.parse_at::<u128>(&line, &full_time, "a time")?;
             }
 
             if i == 1 {
-                distances = line
-                    .split_once(": ")
-                    .unwrap()
-                    .1
-                    .split_whitespace()
-                    .map(|s| s.parse::<u64>().unwrap())
-                    .collect();
-                let full_distance: String = line
-                    .split_once(": ")
-                    .unwrap()
-                    .1
+                let values = _lines.split_once(&line, &line, ": ")?.1;
+                distances = values
                     .split_whitespace()
-                    .collect();
-                real_race.1 = full_distance.parse::<u128>().unwrap();
+                    .map(|s| _lines.parse_at::<u64>(&line, s, "a distance"))
+                    .collect::<Result<_, _>>()?;
+                let full_distance: String = values.split_whitespace().collect();
+                real_race.1 = _lines.parse_at::<u128>(&line, &full_distance, "a distance")?;
             }
         }
 
+        if distances.len() != times.len() {
+            return Err(_lines.error_at_column(
+                1,
+                &format!("{} distances", times.len()),
+                &distances.len().to_string(),
+            ));
+        }
         for (i, time) in times.iter().enumerate() {
             races.push((*time, distances[i]));
         }
@@ -61,7 +56,7 @@ impl TryFrom<FileLines> for Input {
     }
 }
 
-fn _part_1(input_file: &str) -> std::io::Result<u32> {
+pub fn part_1(input_file: &str) -> std::io::Result<u32> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
     println!("{:?}", input._races);
     let mut total = 1;
@@ -86,7 +81,7 @@ fn _part_1(input_file: &str) -> std::io::Result<u32> {
     Ok(total)
 }
 
-fn _part_2(input_file: &str) -> std::io::Result<u32> {
+pub fn part_2(input_file: &str) -> std::io::Result<u32> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
 
     println!("{}, {}", input._real_race.0, input._real_race.1);
@@ -111,34 +106,36 @@ fn _part_2(input_file: &str) -> std::io::Result<u32> {
     Ok(total)
 }
 
+solution!(6);
+
 #[cfg(test)]
 mod tests {
-    use super::{_part_1, _part_2};
+    use super::{part_1, part_2};
 
     const INPUT: &str = "input/roar/q06_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q06_sample.txt";
 
     #[test]
     fn roar_q06_p1_sample() {
-        let result = _part_1(INPUT_SAMPLE);
+        let result = part_1(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 288);
     }
 
     #[test]
     fn roar_q06_p1_main() {
-        let result = _part_1(INPUT);
+        let result = part_1(INPUT);
         assert_eq!(result.unwrap(), 293046);
     }
 
     #[test]
     fn roar_q06_p2_sample() {
-        let result = _part_2(INPUT_SAMPLE);
+        let result = part_2(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 71503);
     }
 
     #[test]
     fn roar_q06_p2_main() {
-        let result = _part_2(INPUT);
+        let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 35150181);
     }
 }
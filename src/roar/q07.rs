@@ -1,3 +1,4 @@
+use crate::solution::solution;
 use crate::utils::parser::FileLines;
 use std::cmp::Ordering;
 use std::collections::{BinaryHeap, HashMap};
@@ -119,12 +120,15 @@ struct Input {
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(_lines: FileLines) -> Result<Self, Self::Error> {
+    fn try_from(mut _lines: FileLines) -> Result<Self, Self::Error> {
         let mut hands_and_bids = Vec::new();
-        for line in _lines {
-            let hand_and_bid = line.split_once(' ').unwrap();
+        while let Some(line) = _lines.next() {
+            let hand_and_bid = _lines.split_once(&line, &line, " ")?;
             let hand = hand_and_bid.0;
-            let bid = hand_and_bid.1.parse::<u32>().unwrap();
+            if let Some(i) = hand.find(|c: char| !"AKQJT98765432".contains(c)) {
+                return Err(_lines.error_at(&line, &hand[i..], "a card"));
+            }
+            let bid = _lines.parse_at::<u32>(&line, hand_and_bid.1, "a bid")?;
             let hand_and_bid = HandAndBid {
                 jokers_wild: false,
                 data: (hand.to_string(), bid),
@@ -153,7 +157,7 @@ fn _get_total_winnings(ordered_hands_and_bids: BinaryHeap<HandAndBid>) -> u32 {
     total
 }
 
-fn _part_1(input_file: &str) -> std::io::Result<u32> {
+pub fn part_1(input_file: &str) -> std::io::Result<u32> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
     let mut ordered_hands_and_bids = BinaryHeap::new();
     for hand_and_bid in input._hands_and_bids.iter() {
@@ -162,7 +166,7 @@ fn _part_1(input_file: &str) -> std::io::Result<u32> {
     Ok(_get_total_winnings(ordered_hands_and_bids))
 }
 
-fn _part_2(input_file: &str) -> std::io::Result<u32> {
+pub fn part_2(input_file: &str) -> std::io::Result<u32> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
     let mut ordered_hands_and_bids = BinaryHeap::new();
     for hand_and_bid in input._hands_and_bids.iter() {
@@ -175,34 +179,36 @@ fn _part_2(input_file: &str) -> std::io::Result<u32> {
     Ok(_get_total_winnings(ordered_hands_and_bids))
 }
 
+solution!(7);
+
 #[cfg(test)]
 mod tests {
-    use super::{_part_1, _part_2};
+    use super::{part_1, part_2};
 
     const INPUT: &str = "input/roar/q07_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q07_sample.txt";
 
     #[test]
     fn roar_q07_p1_sample() {
-        let result = _part_1(INPUT_SAMPLE);
+        let result = part_1(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 6440);
     }
 
     #[test]
     fn roar_q07_p1_main() {
-        let result = _part_1(INPUT);
+        let result = part_1(INPUT);
         assert_eq!(result.unwrap(), 248453531);
     }
 
     #[test]
     fn roar_q07_p2_sample() {
-        let result = _part_2(INPUT_SAMPLE);
+        let result = part_2(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 5905);
     }
 
     #[test]
     fn roar_q07_p2_main() {
-        let result = _part_2(INPUT);
+        let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 248781813);
     }
 }
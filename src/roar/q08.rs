@@ -1,7 +1,8 @@
 use std::collections::HashMap;
 
-use crate::utils::parser::FileLines;
-use num::integer::lcm;
+use crate::solution::solution;
+use crate::utils::math::lcm_all;
+use crate::utils::parser::{error, FileLines};
 
 struct Input {
     _directions: Vec<char>,
@@ -13,16 +14,20 @@ struct Input {
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(_lines: FileLines) -> Result<Self, Self::Error> {
+    fn try_from(mut _lines: FileLines) -> Result<Self, Self::Error> {
         let mut directions = Vec::new();
         let mut network = HashMap::new();
         let mut ends_with_a = Vec::new();
         let mut ends_with_z = Vec::new();
-        for (i, line) in _lines.enumerate() {
+        while let Some(line) = _lines.next() {
+            let i = _lines.line_number() - 1;
             if i == 0 {
-                directions = line.chars().collect();
+                directions = _lines.chars_of(&line, "LR", "L or R")?;
+                if directions.is_empty() {
+                    return Err(_lines.error_at(&line, &line, "L or R"));
+                }
             } else if i > 1 {
-                let mut _line = line.split_once(" = ").unwrap();
+                let mut _line = _lines.split_once(&line, &line, " = ")?;
                 let node_name = _line.0.to_string();
                 if node_name.ends_with('A') {
                     ends_with_a.push(node_name.clone());
@@ -30,13 +35,18 @@ impl TryFrom<FileLines> for Input {
                 if node_name.ends_with('Z') {
                     ends_with_z.push(node_name.clone());
                 }
-                let value_brackets_removed = &_line.1[1.._line.1.len() - 1];
-                let value_tuple = value_brackets_removed.split_once(", ").unwrap();
+                let value_brackets_removed = _lines.strip_prefix(&line, _line.1, "(")?;
+                let value_brackets_removed =
+                    _lines.strip_suffix(&line, value_brackets_removed, ")")?;
+                let value_tuple = _lines.split_once(&line, value_brackets_removed, ", ")?;
                 let left_node = value_tuple.0.to_string();
                 let right_node = value_tuple.1.to_string();
                 network.insert(node_name, (left_node, right_node));
             }
         }
+        if directions.is_empty() {
+            return error("There are no directions to follow");
+        }
         Ok(Input {
             _directions: directions,
             _network: network,
@@ -79,7 +89,7 @@ fn _get_steps_betwixt_nodes(
     steps
 }
 
-fn _part_1(input_file: &str) -> std::io::Result<u64> {
+pub fn part_1(input_file: &str) -> std::io::Result<u64> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
     let start = "AAA";
     let end = "ZZZ";
@@ -126,7 +136,7 @@ fn _part_2_bad(input_file: &str) -> std::io::Result<u64> {
     Ok(steps)
 }
 
-fn _part_2(input_file: &str) -> std::io::Result<u64> {
+pub fn part_2(input_file: &str) -> std::io::Result<u64> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
     let directions = &input._directions;
 
@@ -155,14 +165,19 @@ fn _part_2(input_file: &str) -> std::io::Result<u64> {
 
     println!("{:?}", steps_counts);
 
-    let result_lcm = steps_counts.into_iter().flatten().reduce(lcm).unwrap_or(0);
-
-    Ok(result_lcm)
+    let steps = steps_counts.into_iter().flatten().map(u128::from);
+    match lcm_all(steps) {
+        Some(result_lcm) => Ok(result_lcm as u64),
+        None => error("The step counts' lcm is too big"),
+    }
 }
 
+solution!(8);
+
 #[cfg(test)]
 mod tests {
-    use super::{_part_1, _part_2};
+    use super::{part_1, part_2, Input};
+    use crate::utils::parser::{FileLines, ParseError, Source};
 
     const INPUT: &str = "input/roar/q08_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q08_sample.txt";
@@ -171,31 +186,42 @@ mod tests {
 
     #[test]
     fn roar_q08_p1_sample() {
-        let result = _part_1(INPUT_SAMPLE);
+        let result = part_1(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 2);
     }
 
     #[test]
     fn roar_q08_p1_sample_with_cycle() {
-        let result = _part_1(INPUT_SAMPLE_WITH_CYCLE);
+        let result = part_1(INPUT_SAMPLE_WITH_CYCLE);
         assert_eq!(result.unwrap(), 6);
     }
 
     #[test]
     fn roar_q08_p1_main() {
-        let result = _part_1(INPUT);
+        let result = part_1(INPUT);
         assert_eq!(result.unwrap(), 13019);
     }
 
     #[test]
     fn roar_q08_p2_sample() {
-        let result = _part_2(INPUT_SAMPLE_P2);
+        let result = part_2(INPUT_SAMPLE_P2);
         assert_eq!(result.unwrap(), 6);
     }
 
     #[test]
     fn roar_q08_p2_main() {
-        let result = _part_2(INPUT);
+        let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 13524038372771);
     }
+
+    #[test]
+    fn roar_q08_no_directions() {
+        let input = |text: &str| Input::try_from(FileLines::open(Source::text(text)).unwrap());
+        let e = input("\n\nAAA = (ZZZ, ZZZ)\nZZZ = (ZZZ, ZZZ)\n")
+            .err()
+            .unwrap();
+        let e = ParseError::of(&e).unwrap();
+        assert_eq!((e.line, e.expected.as_str()), (1, "L or R"));
+        assert!(input("").is_err());
+    }
 }
@@ -1,3 +1,4 @@
+use crate::solution::solution;
 use crate::utils::parser::FileLines;
 
 struct Input {
@@ -7,14 +8,14 @@ struct Input {
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(_lines: FileLines) -> Result<Self, Self::Error> {
+    fn try_from(mut _lines: FileLines) -> Result<Self, Self::Error> {
         let mut histories: Vec<Vec<i64>> = Vec::new();
-        for line in _lines {
+        while let Some(line) = _lines.next() {
             // Split line by spaces into numbers which can be negative
             let numbers: Vec<i64> = line
                 .split_whitespace()
-                .map(|x| x.parse::<i64>().unwrap())
-                .collect();
+                .map(|x| _lines.parse_at::<i64>(&line, x, "a number"))
+                .collect::<Result<_, _>>()?;
             histories.push(numbers);
         }
         Ok(Input {
@@ -64,44 +65,46 @@ fn _build_predictions(histories: Vec<Vec<i64>>) -> (i64, i64) {
     (prediction_sum, backwards_sum)
 }
 
-fn _part_1(input_file: &str) -> std::io::Result<i64> {
+pub fn part_1(input_file: &str) -> std::io::Result<i64> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
     Ok(_build_predictions(input._histories).0)
 }
 
-fn _part_2(input_file: &str) -> std::io::Result<i64> {
+pub fn part_2(input_file: &str) -> std::io::Result<i64> {
     let input = Input::try_from(FileLines::new(input_file)?)?;
     Ok(_build_predictions(input._histories).1)
 }
 
+solution!(9);
+
 #[cfg(test)]
 mod tests {
-    use super::{_part_1, _part_2};
+    use super::{part_1, part_2};
 
     const INPUT: &str = "input/roar/q09_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q09_sample.txt";
 
     #[test]
     fn roar_q09_p1_sample() {
-        let result = _part_1(INPUT_SAMPLE);
+        let result = part_1(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 114);
     }
 
     #[test]
     fn roar_q09_p1_main() {
-        let result = _part_1(INPUT);
+        let result = part_1(INPUT);
         assert_eq!(result.unwrap(), 1789635132);
     }
 
     #[test]
     fn roar_q09_p2_sample() {
-        let result = _part_2(INPUT_SAMPLE);
+        let result = part_2(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 2);
     }
 
     #[test]
     fn roar_q09_p2_main() {
-        let result = _part_2(INPUT);
+        let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 913);
     }
 }
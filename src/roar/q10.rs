@@ -2,6 +2,8 @@
 
 use std::collections::{HashMap, HashSet, VecDeque};
 
+use crate::solution::solution;
+use crate::utils::dot::Dot;
 use crate::utils::parser::{parse, FileLines};
 
 /**
@@ -42,13 +44,14 @@ struct Input {
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(file_lines: FileLines) -> Result<Self, Self::Error> {
+    fn try_from(mut file_lines: FileLines) -> Result<Self, Self::Error> {
         let mut nodes: HashMap<Location, Node> = HashMap::new();
         let mut row: i64 = 0;
         let mut start_node_location = (0, 0);
         let mut width = 0;
-        for line in file_lines {
-            for (col, pipe) in line.chars().enumerate() {
+        while let Some(line) = file_lines.next() {
+            let pipes = file_lines.chars_of(&line, "|-LJ7FS.", "a pipe or ground")?;
+            for (col, pipe) in pipes.into_iter().enumerate() {
                 let col: i64 = col as i64;
                 let location = (row, col);
                 let node = Node {
@@ -135,7 +138,7 @@ impl TryFrom<FileLines> for Input {
                     start_node_location.1 = node.location.1;
                 }
                 '.' => {}
-                _ => panic!("Unexpected character in input file: {}", node.pipe),
+                _ => unreachable!("pipes are checked while reading the input"),
             }
         }
 
@@ -214,7 +217,51 @@ fn find_longest_loop(graph: &mut Graph, start: Location) -> (i64, HashSet<Locati
     (max_length, pipe_loop)
 }
 
-fn part_1(input_file: &str) -> std::io::Result<i64> {
+impl Graph {
+    /// A GraphViz graph of the pipes, each node pinned to its place in the
+    /// grid (for `neato -n`) and labelled with its pipe. The start is green
+    /// and the pipes in `pipe_loop` are filled in.
+    fn to_dot(&self, start: Location, pipe_loop: &HashSet<Location>) -> Dot {
+        let mut dot = Dot::graph("pipes");
+        dot.node_defaults(&[("shape", "square"), ("fixedsize", "true")]);
+        let mut locations: Vec<&Location> = self.nodes.keys().collect();
+        locations.sort();
+        for location in locations {
+            let node = &self.nodes[location];
+            let id = format!("{},{}", location.0, location.1);
+            let label = node.pipe.to_string();
+            let pos = format!("{},{}!", location.1 * 72, -location.0 * 72);
+            dot.node(&id, &[("label", &label), ("pos", &pos)]);
+            if *location == start {
+                dot.node(&id, &[("color", "green"), ("penwidth", "3")]);
+            }
+            if pipe_loop.contains(location) {
+                dot.node(&id, &[("style", "filled"), ("fillcolor", "lightblue")]);
+            }
+            for edge in &node.edges {
+                // pipes connect both ways, so only draw one side of each pair
+                let linked_back = self
+                    .nodes
+                    .get(&edge.destination)
+                    .is_some_and(|other| other.edges.iter().any(|e| e.destination == *location));
+                if *location < edge.destination || !linked_back {
+                    let to = format!("{},{}", edge.destination.0, edge.destination.1);
+                    dot.edge(&id, &to, &[]);
+                }
+            }
+        }
+        dot
+    }
+}
+
+/// The pipe maze as a GraphViz graph with the main loop highlighted.
+pub fn to_dot(input_file: &str) -> std::io::Result<Dot> {
+    let mut input = parse::<Input>(input_file)?;
+    let main_loop = find_longest_loop(&mut input.graph, input.start_node_location).1;
+    Ok(input.graph.to_dot(input.start_node_location, &main_loop))
+}
+
+pub fn part_1(input_file: &str) -> std::io::Result<i64> {
     let mut input = parse::<Input>(input_file)?;
     // Find the longest loop in the graph
     // Divide that by two to get the longest distance away from the start node.
@@ -264,7 +311,7 @@ fn get_points_inside_loop(graph: &Graph, loop_points: &HashSet<Location>) -> Has
     point_inside_loop
 }
 
-fn part_2(input_file: &str) -> std::io::Result<i64> {
+pub fn part_2(input_file: &str) -> std::io::Result<i64> {
     let mut input = parse::<Input>(input_file)?;
 
     let main_loop = find_longest_loop(&mut input.graph, input.start_node_location).1;
@@ -273,9 +320,11 @@ fn part_2(input_file: &str) -> std::io::Result<i64> {
     Ok(points_inside_loop.len() as i64)
 }
 
+solution!(10);
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{part_1, part_2, to_dot};
 
     const INPUT: &str = "input/roar/q10_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q10_sample.txt";
@@ -298,6 +347,18 @@ mod tests {
         assert_eq!(result.unwrap(), 1);
     }
 
+    #[test]
+    fn roar_q10_to_dot() {
+        let dot = to_dot(INPUT_SAMPLE).unwrap();
+        assert_eq!((dot.node_count(), dot.edge_count()), (25, 16));
+        let dot = dot.to_string();
+        assert_eq!(dot.matches("lightblue").count(), 16);
+        assert!(dot.contains(
+            "\"2,0\" [label=\"S\", pos=\"0,-144!\", color=\"green\", penwidth=\"3\", style=\"filled\", fillcolor=\"lightblue\"];"
+        ));
+        assert!(dot.contains("\"2,0\" -- \"3,0\";"));
+    }
+
     #[ignore = "Takes too long"]
     #[test]
     fn roar_q10_p2_main() {
@@ -1,15 +1,16 @@
 #![allow(dead_code, unused_variables)]
 
-use std::collections::{HashSet, VecDeque};
+use std::collections::HashSet;
 
+use crate::solution::solution;
+use crate::utils::grid::{Grid, Position};
 use crate::utils::parser::{parse, FileLines};
-
-type Point = (i64, i64);
+use crate::utils::search::dijkstra;
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct Galaxy {
     id: usize,
-    location: Point,
+    location: Position,
 }
 
 const GALAXY_SYMBOL: char = '#';
@@ -21,11 +22,11 @@ struct Tile {
 }
 
 struct Input {
-    grid: Vec<Vec<Tile>>,
+    grid: Grid<Tile>,
 }
 
-fn print_grid(grid: &Vec<Vec<Tile>>) {
-    for row in grid {
+fn print_grid(grid: &Grid<Tile>) {
+    for row in grid.rows() {
         for tile in row {
             print!("|{}:{}|", tile.symbol, tile.weight);
         }
@@ -33,95 +34,59 @@ fn print_grid(grid: &Vec<Vec<Tile>>) {
     }
 }
 
-fn parse_grid(lines: FileLines) -> Vec<Vec<Tile>> {
-    let grid: Vec<Vec<Tile>> = lines
-        .map(|line| {
-            let mut tiles = Vec::new();
-            for symbol in line.chars() {
-                tiles.push(Tile { symbol, weight: 1 });
-            }
-            tiles
-        })
-        .collect();
-
-    grid
+fn parse_grid(lines: FileLines) -> std::io::Result<Grid<Tile>> {
+    Grid::parse(lines, "a galaxy or empty space", |symbol| {
+        matches!(symbol, '.' | GALAXY_SYMBOL).then_some(Tile { symbol, weight: 1 })
+    })
 }
 
-fn expand_grid(grid: Vec<Vec<Tile>>, expansion_magnitude: u32) -> Vec<Vec<Tile>> {
-    let mut expanded_grid: Vec<Vec<Tile>> = grid;
-
-    let mut rows_to_expand = vec![false; expanded_grid.len()];
-    let mut cols_to_expand = vec![false; expanded_grid[0].len()];
+fn expand_grid(grid: Grid<Tile>, expansion_magnitude: u32) -> Grid<Tile> {
+    let mut expanded_grid = grid;
 
-    for (row_index, row) in expanded_grid.iter().enumerate() {
-        rows_to_expand[row_index] = row.iter().all(|x| x.symbol == '.');
-    }
-
-    for col_index in 0..expanded_grid[0].len() {
-        cols_to_expand[col_index] = expanded_grid.iter().all(|row| row[col_index].symbol == '.');
-    }
+    let rows_to_expand: Vec<bool> = expanded_grid
+        .rows()
+        .map(|row| row.iter().all(|x| x.symbol == '.'))
+        .collect();
+    let cols_to_expand: Vec<bool> = expanded_grid
+        .columns()
+        .map(|mut col| col.all(|x| x.symbol == '.'))
+        .collect();
 
-    for (row_index, row) in expanded_grid.iter_mut().enumerate() {
-        for (col_index, tile) in row.iter_mut().enumerate() {
-            if rows_to_expand[row_index] || cols_to_expand[col_index] {
-                tile.weight = expansion_magnitude;
-            }
+    for ((row_index, col_index), tile) in expanded_grid.iter_mut() {
+        if rows_to_expand[row_index] || cols_to_expand[col_index] {
+            tile.weight = expansion_magnitude;
         }
     }
 
     expanded_grid
 }
 
-fn get_galaxies(grid: &[Vec<Tile>]) -> Vec<Galaxy> {
-    let mut galaxies = Vec::new();
-    for (row_index, row) in grid.iter().enumerate() {
-        for (col, tile) in row.iter().enumerate() {
-            if tile.symbol == GALAXY_SYMBOL {
-                let point = (col as i64, row_index as i64); // x, y
-                galaxies.push(Galaxy {
-                    id: galaxies.len() + 1,
-                    location: point,
-                });
-            }
-        }
-    }
-    galaxies
+fn get_galaxies(grid: &Grid<Tile>) -> Vec<Galaxy> {
+    grid.iter()
+        .filter(|(_, tile)| tile.symbol == GALAXY_SYMBOL)
+        .enumerate()
+        .map(|(i, (location, _))| Galaxy {
+            id: i + 1,
+            location,
+        })
+        .collect()
 }
-fn calculate_shortest_path(grid: &Vec<Vec<Tile>>, start: Point, end: Point) -> i64 {
-    // Standard bfs implementation
-    let mut queue = VecDeque::new();
-    // Setup grid, same size as input grid, but all false
-    let mut visited = vec![vec![false; grid[0].len()]; grid.len()];
-    let directions = [(0, 1), (1, 0), (0, -1), (-1, 0)]; // Right, Down, Left, Up
-    queue.push_back((start, 0));
-    visited[start.1 as usize][start.0 as usize] = true;
-
-    while let Some((current, dist)) = queue.pop_front() {
-        if current == end {
-            return dist;
-        }
 
-        for (dx, dy) in directions.iter() {
-            let x = current.0 + dx;
-            let y = current.1 + dy;
-            if x >= 0
-                && x < grid[0].len() as i64
-                && y >= 0
-                && y < grid.len() as i64
-                && !visited[y as usize][x as usize]
-            {
-                // Using weight as distance
-                queue.push_back(((x, y), dist + grid[y as usize][x as usize].weight as i64));
-                visited[y as usize][x as usize] = true;
-            }
-        }
+fn calculate_shortest_path(grid: &Grid<Tile>, start: Position, end: Position) -> i64 {
+    // Using weight as distance
+    let moves = |&current: &Position| {
+        grid.neighbors4(current)
+            .map(|next| (next, grid[next].weight as i64))
+            .collect::<Vec<_>>()
+    };
+    match dijkstra([start], moves, |&current| current == end) {
+        Some(found) => found.cost,
+        None => panic!("No path found. Should not happen!"),
     }
-
-    panic!("No path found. Should not happen!")
 }
 
-fn get_distance_betwixt_galaxies(grid: &[Vec<Tile>], expansion_magnitude: u32) -> i64 {
-    let expanded_grid = expand_grid(grid.to_vec(), expansion_magnitude);
+fn get_distance_betwixt_galaxies(grid: &Grid<Tile>, expansion_magnitude: u32) -> i64 {
+    let expanded_grid = expand_grid(grid.clone(), expansion_magnitude);
     let mut total = 0;
     let galaxies = get_galaxies(&expanded_grid);
     let mut seen = HashSet::new();
@@ -147,22 +112,24 @@ impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
     fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
-        let grid = parse_grid(lines);
+        let grid = parse_grid(lines)?;
 
         Ok(Input { grid })
     }
 }
 
-fn part_1(input_file: &str) -> std::io::Result<i64> {
+pub fn part_1(input_file: &str) -> std::io::Result<i64> {
     let input = parse::<Input>(input_file)?;
     Ok(get_distance_betwixt_galaxies(&input.grid, 2))
 }
 
-fn part_2(input_file: &str) -> std::io::Result<i64> {
+pub fn part_2(input_file: &str) -> std::io::Result<i64> {
     let input = parse::<Input>(input_file)?;
     Ok(get_distance_betwixt_galaxies(&input.grid, 1000000))
 }
 
+solution!(11);
+
 #[cfg(test)]
 mod tests {
     use super::{part_1, part_2};
@@ -1,5 +1,6 @@
 #![allow(dead_code, unused_variables)]
 
+use crate::solution::solution;
 use crate::utils::parser::{parse, FileLines};
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -21,17 +22,16 @@ struct Input {
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
+    fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
         let mut condition_record = ConditionRecord {
             records: vec![],
             mappings: vec![],
         };
-        for line in lines {
-            let records: Vec<&str> = line.split_whitespace().collect();
-            let conditions = records[0];
-            let mapping: Vec<&str> = records[1].split(',').collect();
+        while let Some(line) = lines.next() {
+            let (conditions, mapping) = lines.split_once(&line, &line, " ")?;
+            let mapping: Vec<&str> = mapping.split(',').collect();
             let mut condition_record_row = vec![];
-            for c in conditions.chars() {
+            for c in lines.chars_of(conditions, "#.?", "a spring condition")? {
                 match c {
                     '#' => {
                         condition_record_row.push(Condition::Damaged);
@@ -46,7 +46,7 @@ impl TryFrom<FileLines> for Input {
             }
             let mut mapping_row = vec![];
             for m in mapping {
-                mapping_row.push(m.parse::<u32>().unwrap());
+                mapping_row.push(lines.parse_at::<u32>(&line, m, "a group size")?);
             }
             condition_record.records.push(condition_record_row);
             condition_record.mappings.push(mapping_row);
@@ -60,12 +60,14 @@ fn is_valid_condition_record(record: Vec<Condition>, mapping: &[u32]) -> bool {
     Check that the record is valid
     A valid record is a sequence of operational and damaged conditions
     where the number of damaged conditions matches the mapping.
+    A record with unknowns left in it isn't valid yet.
      */
     let seen_damaged = count_damaged_conditions(record);
-    seen_damaged == mapping.to_vec()
+    seen_damaged.as_deref() == Some(mapping)
 }
 
-fn count_damaged_conditions(record: Vec<Condition>) -> Vec<u32> {
+/// The sizes of the groups of damaged springs, or None if any are unknown.
+fn count_damaged_conditions(record: Vec<Condition>) -> Option<Vec<u32>> {
     let mut current_damaged = 0;
     let mut seen_damaged = Vec::new();
     for (i, condition) in record.iter().enumerate() {
@@ -79,15 +81,13 @@ fn count_damaged_conditions(record: Vec<Condition>) -> Vec<u32> {
             Condition::Damaged => {
                 current_damaged += 1;
             }
-            _ => {
-                panic!("Unknown condition")
-            }
+            Condition::Unknown => return None,
         }
     }
     if current_damaged > 0 {
         seen_damaged.push(current_damaged)
     }
-    seen_damaged
+    Some(seen_damaged)
 }
 
 fn count_possible_arrangements_for_row(
@@ -124,7 +124,7 @@ fn cound_possible_arrangements(records: Vec<Vec<Condition>>, mappings: Vec<Vec<u
     count
 }
 
-fn part_1(input_file: &str) -> std::io::Result<u32> {
+pub fn part_1(input_file: &str) -> std::io::Result<u32> {
     let input: Input = parse(input_file)?;
     Ok(cound_possible_arrangements(
         input.condition_record.records,
@@ -132,11 +132,13 @@ fn part_1(input_file: &str) -> std::io::Result<u32> {
     ))
 }
 
-fn part_2(input_file: &str) -> std::io::Result<u32> {
+pub fn part_2(input_file: &str) -> std::io::Result<u32> {
     let input: Input = parse(input_file)?;
     Ok(0)
 }
 
+solution!(12);
+
 #[cfg(test)]
 mod tests {
     use super::{part_1, part_2};
@@ -1,5 +1,6 @@
 #![allow(dead_code, unused_variables)]
 
+use crate::solution::solution;
 use crate::utils::parser::{parse, FileLines};
 use crate::utils::transposer::transpose_vec_of_strings;
 
@@ -45,61 +46,49 @@ fn find_reflection(pattern: Vec<String>) -> Option<usize> {
     None
 }
 
-fn calculate_pattern_summary(pattern: &[String]) -> usize {
+fn calculate_pattern_summary(pattern: &[String]) -> std::io::Result<usize> {
     let reflection_row = find_reflection(pattern.to_vec());
 
     if reflection_row.is_some() {
         let row = reflection_row.unwrap();
         println!("Found reflection at row {}", row);
-        return row * 100;
+        return Ok(row * 100);
     }
-    let transposed_pattern = transpose_vec_of_strings(pattern.to_vec());
+    let transposed_pattern = transpose_vec_of_strings(pattern.to_vec())?;
     let reflection_col = find_reflection(transposed_pattern).unwrap();
     println!("Found reflection at col {}", reflection_col);
 
-    reflection_col
+    Ok(reflection_col)
 }
 
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
     fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
-        let mut patterns = Vec::new();
-        let mut current_pattern = Vec::new();
-        for line in lines {
-            if line.is_empty() {
-                if !current_pattern.is_empty() {
-                    patterns.push(current_pattern.clone());
-                    current_pattern.clear();
-                }
-            } else {
-                current_pattern.push(line);
-            }
-        }
-        if !current_pattern.is_empty() {
-            patterns.push(current_pattern);
-        }
+        let patterns = lines.sections().map(|pattern| pattern.collect()).collect();
 
         Ok(Input { patterns })
     }
 }
 
-fn part_1(input_file: &str) -> std::io::Result<u32> {
+pub fn part_1(input_file: &str) -> std::io::Result<u32> {
     let input: Input = parse(input_file)?;
     let sum = input
         .patterns
         .iter()
         .map(|pattern| calculate_pattern_summary(&pattern[..]))
-        .sum::<usize>();
+        .sum::<std::io::Result<usize>>()?;
     println!("Total sum: {}", sum);
     Ok(sum as u32)
 }
 
-fn part_2(input_file: &str) -> std::io::Result<u32> {
+pub fn part_2(input_file: &str) -> std::io::Result<u32> {
     let input: Input = parse(input_file)?;
     Ok(0)
 }
 
+solution!(13);
+
 #[cfg(test)]
 mod tests {
     use super::{part_1, part_2};
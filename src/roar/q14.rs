@@ -1,22 +1,24 @@
 #![allow(dead_code, unused_variables)]
 
+use crate::solution::solution;
 use crate::utils::{
+    cycle::state_after,
+    grid::Grid,
     parser::{parse, FileLines},
-    transposer::transpose_vec_of_strings,
+    transposer::Transform,
 };
 
 struct Input {
-    platform: Vec<String>,
+    platform: Grid<char>,
 }
 
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
     fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
-        let mut platform = Vec::new();
-        for line in lines {
-            platform.push(line);
-        }
+        let platform = Grid::parse(lines, "a rock or empty space", |c| {
+            matches!(c, '.' | '#' | 'O').then_some(c)
+        })?;
         Ok(Input { platform })
     }
 }
@@ -73,63 +75,73 @@ fn tilt_row(row: String, direction: TiltDirection) -> String {
     format!("{}{}{}", start, sorted_sections.join("#"), end)
 }
 
-fn part_1(input_file: &str) -> std::io::Result<usize> {
-    let input: Input = parse(input_file)?;
-    let mut transposed_platform = transpose_vec_of_strings(input.platform);
-    for row in transposed_platform.iter_mut() {
-        *row = tilt_row(row.to_string(), TiltDirection::Left);
+fn tilt_rows_left(platform: &mut Grid<char>) {
+    for r in 0..platform.height() {
+        let row = platform.row_mut(r);
+        let tilted = tilt_row(String::from_iter(row.iter()), TiltDirection::Left);
+        for (cell, c) in row.iter_mut().zip(tilted.chars()) {
+            *cell = c;
+        }
     }
-    let tilted_platform = transpose_vec_of_strings(transposed_platform);
-    let mut total = 0;
-    for (i, row) in tilted_platform.iter().enumerate() {
-        let value = tilted_platform.len() - i;
-        println!("Row value: {}", value);
-        // count instances of O in string
-        let count = row.chars().filter(|&c| c == 'O').count();
-        total += count * value;
+}
+
+/*
+  One spin tilts north, west, south then east. The platform is kept turned
+  anticlockwise so north is on the left: tilt left, then turn it clockwise to
+  bring the next edge round to the left.
+*/
+fn spin(platform: &mut Grid<char>) -> std::io::Result<()> {
+    for _ in 0..4 {
+        tilt_rows_left(platform);
+        platform.rotate_90_in_place()?;
     }
-    Ok(total)
+    Ok(())
+}
+
+fn north_load(platform: &Grid<char>) -> usize {
+    platform
+        .find_all(&'O')
+        .map(|(r, _)| platform.height() - r)
+        .sum()
 }
 
-fn part_2(input_file: &str) -> std::io::Result<usize> {
+pub fn part_1(input_file: &str) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
+    let height = input.platform.height();
     let mut total = 0;
-    let mut tilted_platform = Vec::new();
-    for _ in 0..1000000000 {
-        let platform = input.platform.clone();
-        let mut transposed_platform = transpose_vec_of_strings(platform);
-        for row in transposed_platform.iter_mut() {
-            *row = tilt_row(row.to_string(), TiltDirection::Left);
-        }
-        let mut tilted_north = transpose_vec_of_strings(transposed_platform);
-        for row in tilted_north.iter_mut() {
-            *row = tilt_row(row.to_string(), TiltDirection::Left);
-        }
-        let mut tilted_west = transpose_vec_of_strings(tilted_north);
-        for row in tilted_west.iter_mut() {
-            *row = tilt_row(row.to_string(), TiltDirection::Right);
-        }
-        let mut tilted_south = transpose_vec_of_strings(tilted_west);
-        for row in tilted_south.iter_mut() {
-            *row = tilt_row(row.to_string(), TiltDirection::Right);
-        }
-        let tilted_east = transpose_vec_of_strings(tilted_south);
-
-        tilted_platform = transpose_vec_of_strings(tilted_east);
-    }
-    for (i, row) in tilted_platform.iter().enumerate() {
-        let value = tilted_platform.len() - i;
-        println!("Row value: {}", value);
-        // count instances of O in string
-        let count = row.chars().filter(|&c| c == 'O').count();
-        total += count * value;
+    for column in input.platform.columns() {
+        // Reading a column top to bottom, tilting north moves rocks to the front
+        let tilted = tilt_row(column.collect(), TiltDirection::Left);
+        total += tilted
+            .chars()
+            .enumerate()
+            .filter(|&(_, c)| c == 'O')
+            .map(|(i, _)| height - i)
+            .sum::<usize>();
     }
     Ok(total)
 }
 
+pub fn part_2(input_file: &str) -> std::io::Result<usize> {
+    let input: Input = parse(input_file)?;
+    let platform = input.platform.rotate_270()?;
+    // Spinning ends up repeating itself, so jump straight to the last spin
+    let mut platform = state_after(platform, 1000000000, |platform| {
+        let mut platform = platform.clone();
+        spin(&mut platform).expect("a parsed platform is never empty");
+        platform
+    });
+    platform.rotate_90_in_place()?;
+    Ok(north_load(&platform))
+}
+
+solution!(14);
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{part_1, part_2, spin, Input};
+    use crate::utils::parser::parse;
+    use crate::utils::transposer::Transform;
 
     const INPUT: &str = "input/roar/q14_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q14_sample.txt";
@@ -146,17 +158,36 @@ mod tests {
         assert_eq!(result.unwrap(), 108840);
     }
 
-    #[ignore = "doesn't work"]
+    #[test]
+    fn roar_q14_spin_sample() {
+        let input: Input = parse(INPUT_SAMPLE).unwrap();
+        let mut platform = input.platform.rotate_270().unwrap();
+        spin(&mut platform).unwrap();
+        platform.rotate_90_in_place().unwrap();
+        let expected = [
+            ".....#....",
+            "....#...O#",
+            "...OO##...",
+            ".OO#......",
+            ".....OOO#.",
+            ".O#...O#.#",
+            "....O#....",
+            "......OOOO",
+            "#...O###..",
+            "#..OO#....",
+        ];
+        assert_eq!(platform.to_string(), expected.join("\n"));
+    }
+
     #[test]
     fn roar_q14_p2_sample() {
         let result = part_2(INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 64);
     }
 
-    #[ignore = "doesn't work"]
     #[test]
     fn roar_q14_p2_main() {
         let result = part_2(INPUT);
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap(), 103445);
     }
 }
@@ -1,5 +1,6 @@
 #![allow(dead_code, unused_variables)]
 
+use crate::solution::solution;
 use crate::utils::parser::{parse, FileLines};
 
 #[derive(Debug, PartialEq)]
@@ -21,47 +22,42 @@ struct Input {
     boxes: Vec<Vec<Instruction>>,
 }
 
-fn parse_instruction(original_string: &str) -> Instruction {
-    let mut label = String::new();
-    let mut focal_length = 0;
-    let mut operation: Operation = Operation::Assign;
-
-    for character in original_string.chars() {
-        match character {
-            '0'..='9' => {
-                focal_length = character.to_digit(10).unwrap();
-            }
-            '=' | '-' => {
-                operation = match character {
-                    '=' => Operation::Assign,
-                    '-' => Operation::Remove,
-                    _ => panic!("Invalid operation"),
-                };
-            }
-            _ => {
-                label.push(character);
-            }
+/// e.g. `rn=1` or `cm-`, a slice of `line`
+fn parse_instruction(
+    lines: &FileLines,
+    line: &str,
+    original_string: &str,
+) -> std::io::Result<Instruction> {
+    let (label, focal_length, operation) = match original_string.strip_suffix('-') {
+        Some(label) => (label, 0, Operation::Remove),
+        None => {
+            let (label, focal_length) = lines.split_once(line, original_string, "=")?;
+            let focal_length = lines.parse_at(line, focal_length, "a focal length")?;
+            (label, focal_length, Operation::Assign)
         }
+    };
+    if label.is_empty() {
+        return Err(lines.error_at(line, label, "a label"));
     }
 
-    Instruction {
-        label,
+    Ok(Instruction {
+        label: label.to_string(),
         focal_length,
         operation,
         original_string: original_string.to_string(),
-    }
+    })
 }
 
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
+    fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
         let mut init_sequence = Vec::new();
         let mut boxes: Vec<Vec<Instruction>> = Vec::new();
-        for line in lines {
+        while let Some(line) = lines.next() {
             let sections = line.split(',');
             for section in sections {
-                init_sequence.push(parse_instruction(section));
+                init_sequence.push(parse_instruction(&lines, &line, section)?);
             }
         }
 
@@ -86,7 +82,7 @@ fn hash_string(input: &str) -> u64 {
     current_value
 }
 
-fn part_1(input_file: &str) -> std::io::Result<u64> {
+pub fn part_1(input_file: &str) -> std::io::Result<u64> {
     let input: Input = parse(input_file)?;
     let mut all_values: Vec<u64> = vec![];
     for instruction in input.init_sequence {
@@ -95,7 +91,7 @@ fn part_1(input_file: &str) -> std::io::Result<u64> {
     Ok(all_values.iter().sum::<u64>())
 }
 
-fn part_2(input_file: &str) -> std::io::Result<u32> {
+pub fn part_2(input_file: &str) -> std::io::Result<u32> {
     let mut input: Input = parse(input_file)?;
     for instruction in input.init_sequence {
         let box_index = hash_string(&instruction.label);
@@ -145,9 +141,12 @@ fn part_2(input_file: &str) -> std::io::Result<u32> {
     Ok(total)
 }
 
+solution!(15);
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{part_1, part_2, Input};
+    use crate::utils::parser::{parse, Source};
 
     const INPUT: &str = "input/roar/q15_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q15_sample.txt";
@@ -175,4 +174,12 @@ mod tests {
         let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 244199);
     }
+
+    #[test]
+    fn roar_q15_bad_instructions() {
+        assert!(parse::<Input>(Source::text("rn=1,cm-")).is_ok());
+        assert!(parse::<Input>(Source::text("rn=1,cm")).is_err());
+        assert!(parse::<Input>(Source::text("rn=a")).is_err());
+        assert!(parse::<Input>(Source::text("-")).is_err());
+    }
 }
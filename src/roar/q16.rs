@@ -1,5 +1,7 @@
 #![allow(dead_code, unused_variables)]
-use crate::utils::parser::{parse, FileLines};
+use crate::solution::solution;
+use crate::utils::geom::{Direction, Point};
+use crate::utils::parser::{error, parse, FileLines};
 use rayon::prelude::*;
 
 #[derive(PartialEq, Debug, Clone, Copy)]
@@ -20,18 +22,9 @@ struct Tile {
     is_energised: bool,
 }
 
-#[derive(PartialEq, Debug, Clone, Copy)]
-enum Direction {
-    Up,
-    Down,
-    Left,
-    Right,
-}
-
 #[derive(PartialEq, Debug, Clone, Copy)]
 struct Beam {
-    x: usize,
-    y: usize,
+    point: Point<usize>,
     direction: Direction,
 }
 
@@ -42,10 +35,11 @@ struct Input {
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
+    fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
         let mut grid = Vec::new();
 
-        for (y, line) in lines.enumerate() {
+        while let Some(line) = lines.next() {
+            let y = lines.line_number() - 1;
             let mut row = Vec::new();
             for (x, character) in line.chars().enumerate() {
                 let tile_type = match character {
@@ -54,7 +48,13 @@ impl TryFrom<FileLines> for Input {
                     '\\' => TileType::LeftAngleMirror,
                     '-' => TileType::HorizontalSplitter,
                     '|' => TileType::VerticalSplitter,
-                    _ => panic!("Invalid character"),
+                    _ => {
+                        return Err(lines.error_at_column(
+                            x + 1,
+                            "a mirror, splitter or empty space",
+                            &format!("{:?}", character),
+                        ))
+                    }
                 };
                 row.push(Tile {
                     tile_type,
@@ -64,8 +64,27 @@ impl TryFrom<FileLines> for Input {
                     y,
                 });
             }
+            // every row has to have something in it, and be as wide as the first
+            if row.is_empty() {
+                let expected = "a mirror, splitter or empty space";
+                return Err(lines.error_at_column(1, expected, "end of line"));
+            }
+            if let Some(width) = grid.first().map(|first: &Vec<Tile>| first.len()) {
+                if row.len() != width {
+                    let found = match line.chars().nth(width) {
+                        Some(character) => format!("{:?}", character),
+                        None => String::from("end of line"),
+                    };
+                    let expected = format!("a row of {} tiles", width);
+                    let column = width.min(row.len()) + 1;
+                    return Err(lines.error_at_column(column, &expected, &found));
+                }
+            }
             grid.push(row);
         }
+        if grid.is_empty() {
+            return error("The contraption is empty");
+        }
 
         Ok(Input { grid })
     }
@@ -82,8 +101,7 @@ fn count_energised_tiles_for_beam(grid: &Vec<Vec<Tile>>, beam: Beam) -> usize {
     while !beams.is_empty() && counter > 0 {
         counter -= 1;
         for i in 0..beams.len() {
-            let x = beams[i].x;
-            let y = beams[i].y;
+            let Point { row: y, column: x } = beams[i].point;
             let direction = beams[i].direction;
 
             // Energise current tile
@@ -92,105 +110,47 @@ fn count_energised_tiles_for_beam(grid: &Vec<Vec<Tile>>, beam: Beam) -> usize {
 
             // Check action on tile
             match tile.tile_type {
-                TileType::HorizontalSplitter => {
-                    if direction == Direction::Up || direction == Direction::Down {
-                        beams.remove(i);
-                        if x > 0 {
-                            let left_beam = Beam {
-                                x: x - 1,
-                                y,
-                                direction: Direction::Left,
-                            };
-                            beams.push(left_beam);
+                TileType::HorizontalSplitter if direction.is_vertical() => {
+                    beams.remove(i);
+                    for direction in [Direction::Left, Direction::Right] {
+                        if let Some(point) = Point::new(y, x).step(direction, height, width) {
+                            beams.push(Beam { point, direction });
                         }
-                        if x < width - 1 {
-                            let right_beam = Beam {
-                                x: x + 1,
-                                y,
-                                direction: Direction::Right,
-                            };
-                            beams.push(right_beam);
-                        }
-
-                        break;
                     }
+                    break;
                 }
-                TileType::VerticalSplitter => {
-                    if direction == Direction::Left || direction == Direction::Right {
-                        beams.remove(i);
-                        if y > 0 {
-                            let up_beam = Beam {
-                                x,
-                                y: y - 1,
-                                direction: Direction::Up,
-                            };
-                            beams.push(up_beam);
-                        }
-                        if y < height - 1 {
-                            let down_beam = Beam {
-                                x,
-                                y: y + 1,
-                                direction: Direction::Down,
-                            };
-                            beams.push(down_beam);
+                TileType::VerticalSplitter if direction.is_horizontal() => {
+                    beams.remove(i);
+                    for direction in [Direction::Up, Direction::Down] {
+                        if let Some(point) = Point::new(y, x).step(direction, height, width) {
+                            beams.push(Beam { point, direction });
                         }
-                        break;
                     }
+                    break;
                 }
                 TileType::RightAngleMirror => {
-                    if direction == Direction::Up {
-                        beams[i].direction = Direction::Right;
-                    } else if direction == Direction::Left {
-                        beams[i].direction = Direction::Down;
-                    } else if direction == Direction::Down {
-                        beams[i].direction = Direction::Left;
-                    } else if direction == Direction::Right {
-                        beams[i].direction = Direction::Up;
-                    }
+                    beams[i].direction = if direction.is_vertical() {
+                        direction.turn_right()
+                    } else {
+                        direction.turn_left()
+                    };
                 }
                 TileType::LeftAngleMirror => {
-                    if direction == Direction::Up {
-                        beams[i].direction = Direction::Left;
-                    } else if direction == Direction::Left {
-                        beams[i].direction = Direction::Up;
-                    } else if direction == Direction::Down {
-                        beams[i].direction = Direction::Right;
-                    } else if direction == Direction::Right {
-                        beams[i].direction = Direction::Down;
-                    }
+                    beams[i].direction = if direction.is_vertical() {
+                        direction.turn_left()
+                    } else {
+                        direction.turn_right()
+                    };
                 }
                 _ => {}
             }
 
             // Move
-            match beams[i].direction {
-                Direction::Up => {
-                    if beams[i].y == 0 {
-                        beams.remove(i);
-                        break;
-                    }
-                    beams[i].y -= 1;
-                }
-                Direction::Down => {
-                    if beams[i].y == height - 1 {
-                        beams.remove(i);
-                        break;
-                    }
-                    beams[i].y += 1;
-                }
-                Direction::Left => {
-                    if beams[i].x == 0 {
-                        beams.remove(i);
-                        break;
-                    }
-                    beams[i].x -= 1;
-                }
-                Direction::Right => {
-                    if beams[i].x == width - 1 {
-                        beams.remove(i);
-                        break;
-                    }
-                    beams[i].x += 1;
+            match beams[i].point.step(beams[i].direction, height, width) {
+                Some(point) => beams[i].point = point,
+                None => {
+                    beams.remove(i);
+                    break;
                 }
             }
         }
@@ -219,13 +179,12 @@ fn count_energised_tiles_for_beam(grid: &Vec<Vec<Tile>>, beam: Beam) -> usize {
     count
 }
 
-fn part_1(input_file: &str) -> std::io::Result<usize> {
+pub fn part_1(input_file: &str) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
     let count = count_energised_tiles_for_beam(
         &input.grid,
         Beam {
-            x: 0,
-            y: 0,
+            point: Point::new(0, 0),
             direction: Direction::Right,
         },
     );
@@ -233,22 +192,20 @@ fn part_1(input_file: &str) -> std::io::Result<usize> {
     Ok(count)
 }
 
-fn part_2(input_file: &str) -> std::io::Result<usize> {
+pub fn part_2(input_file: &str) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
     let mut start_beams = vec![];
     for row in 0..input.grid.len() {
         start_beams.push((
             Beam {
-                x: 0,
-                y: row,
+                point: Point::new(row, 0),
                 direction: Direction::Right,
             },
             input.grid.clone(),
         ));
         start_beams.push((
             Beam {
-                x: input.grid[0].len() - 1,
-                y: row,
+                point: Point::new(row, input.grid[0].len() - 1),
                 direction: Direction::Left,
             },
             input.grid.clone(),
@@ -257,16 +214,14 @@ fn part_2(input_file: &str) -> std::io::Result<usize> {
     for col in 0..input.grid[0].len() {
         start_beams.push((
             Beam {
-                x: col,
-                y: 0,
+                point: Point::new(0, col),
                 direction: Direction::Down,
             },
             input.grid.clone(),
         ));
         start_beams.push((
             Beam {
-                x: col,
-                y: input.grid.len() - 1,
+                point: Point::new(input.grid.len() - 1, col),
                 direction: Direction::Up,
             },
             input.grid.clone(),
@@ -275,7 +230,7 @@ fn part_2(input_file: &str) -> std::io::Result<usize> {
     for beam_grid in &mut start_beams {
         println!(
             "beam_grid: (col: {}, row: {}) {:?}",
-            beam_grid.0.x, beam_grid.0.y, beam_grid.0.direction
+            beam_grid.0.point.column, beam_grid.0.point.row, beam_grid.0.direction
         );
     }
 
@@ -291,9 +246,12 @@ fn part_2(input_file: &str) -> std::io::Result<usize> {
     Ok(*results.iter().max().unwrap())
 }
 
+solution!(16);
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{part_1, part_2, Input};
+    use crate::utils::parser::{parse, ParseError, Source};
 
     const INPUT: &str = "input/roar/q16_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q16_sample.txt";
@@ -323,4 +281,16 @@ mod tests {
         let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 0);
     }
+
+    #[test]
+    fn roar_q16_bad_contraptions() {
+        let column = |text: &str| {
+            let e = parse::<Input>(Source::text(text)).err().unwrap();
+            ParseError::of(&e).map(|e| (e.line, e.column))
+        };
+        assert_eq!(column(".|.\n..\n"), Some((2, 3)));
+        assert_eq!(column(".|\n...\n"), Some((2, 3)));
+        assert_eq!(column(".|.\n\n"), Some((2, 1)));
+        assert_eq!(column(""), None);
+    }
 }
@@ -1,11 +1,10 @@
 #![allow(dead_code, unused_variables)]
-use crate::utils::parser::{parse, FileLines};
-use std::{
-    collections::{BinaryHeap, HashMap},
-    isize,
-};
-
-const MAX_CONSECUTIVE_STEPS: usize = 3;
+use crate::solution::solution;
+use crate::utils::crucible::CrucibleRules;
+use crate::utils::geom::{Direction, Point};
+use crate::utils::grid::Grid;
+use crate::utils::parser::{error, parse, FileLines};
+use crate::utils::search::dijkstra;
 
 struct Block {
     heat_loss: usize,
@@ -13,7 +12,7 @@ struct Block {
 
 // Basically a graph
 struct City {
-    blocks: Vec<Vec<Block>>,
+    blocks: Grid<Block>,
 }
 
 struct Input {
@@ -24,14 +23,12 @@ impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
     fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
-        let mut blocks = Vec::new();
-        for line in lines {
-            let mut row = Vec::new();
-            for character in line.chars() {
-                let heat_loss = character.to_digit(10).unwrap() as usize;
-                row.push(Block { heat_loss });
-            }
-            blocks.push(row);
+        let blocks = Grid::parse(lines, "a heat loss digit", |c| {
+            let heat_loss = c.to_digit(10)? as usize;
+            Some(Block { heat_loss })
+        })?;
+        if blocks.width() == 0 {
+            return error("The city is empty");
         }
         Ok(Input {
             city: City { blocks },
@@ -39,129 +36,77 @@ impl TryFrom<FileLines> for Input {
     }
 }
 
+#[derive(Clone, Copy, Eq, Hash, PartialEq)]
 struct State {
-    position: (usize, usize), // (row, column)
-    direction: isize,
-    steps: isize,
-    distance: usize,
+    position: (usize, usize),     // (row, column)
+    direction: Option<Direction>, // None at the start
+    steps: usize,
 }
 
-impl Ord for State {
-    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        other
-            .distance
-            .cmp(&self.distance)
-            .then_with(|| self.position.cmp(&other.position))
-    }
-}
+fn calculate_least_heat_loss(city: &City, rules: &CrucibleRules) -> Option<usize> {
+    let (height, width) = (city.blocks.height(), city.blocks.width());
+    let target = rules.target_in(height, width);
+    let start = State {
+        position: rules.start,
+        direction: None,
+        steps: 0,
+    };
+    let moves = |state: &State| {
+        let mut moves = Vec::new();
+        if state.position == target && rules.can_stop(state.steps) {
+            return moves;
+        }
+        for (new_direction, new_steps) in rules.moves(state.direction, state.steps) {
+            let Some(new_position) = Point::from(state.position).step(new_direction, height, width)
+            else {
+                continue;
+            };
+            let cost = city.blocks[new_position.into()].heat_loss;
+            let next = State {
+                position: new_position.into(),
+                direction: Some(new_direction),
+                steps: new_steps,
+            };
+            moves.push((next, cost));
+        }
+        moves
+    };
 
-impl PartialOrd for State {
-    fn partial_cmp(&self, other: &State) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(other))
-    }
+    // https://en.wikipedia.org/wiki/Dijkstra%27s_algorithm
+    dijkstra([start], moves, |state| {
+        state.position == target && rules.can_stop(state.steps)
+    })
+    .map(|found| found.cost)
 }
 
-impl Eq for State {}
-
-impl PartialEq for State {
-    fn eq(&self, other: &Self) -> bool {
-        self.distance == other.distance && self.position == other.position
+pub fn least_heat_loss(input_file: &str, rules: &CrucibleRules) -> std::io::Result<usize> {
+    let input: Input = parse(input_file)?;
+    let (height, width) = (input.city.blocks.height(), input.city.blocks.width());
+    let (start, target) = (rules.start, rules.target_in(height, width));
+    if start.0 >= height || start.1 >= width || target.0 >= height || target.1 >= width {
+        return error("Start or target is outside the city");
     }
-}
-
-fn check_not_reversing(new_direction: isize, direction: isize) -> bool {
-    (new_direction + 2) % 4 != direction
-}
-
-fn validate_part_1(new_steps: isize, _: isize, direction: isize, new_direction: isize) -> bool {
-    new_steps <= 3 && check_not_reversing(new_direction, direction)
-}
-
-fn validate_part_2(new_steps: isize, steps: isize, direction: isize, new_direction: isize) -> bool {
-    new_steps <= 10
-        && (new_direction == direction || steps >= 4 || steps == -1)
-        && check_not_reversing(new_direction, direction)
-}
-
-fn calculate_least_heat_loss(
-    city: &City,
-    validate_move: fn(isize, isize, isize, isize) -> bool,
-) -> usize {
-    let mut queue = BinaryHeap::new();
-    queue.push(State {
-        position: (0, 0),
-        direction: -1,
-        steps: -1,
-        distance: 0,
-    });
-
-    let mut distances: HashMap<(usize, usize, isize, isize), usize> = HashMap::new();
-    let directions = [(-1, 0), (0, 1), (1, 0), (0, -1)];
-    // https://en.wikipedia.org/wiki/Dijkstra%27s_algorithm
-    while let Some(State {
-        position,
-        direction,
-        steps,
-        distance,
-    }) = queue.pop()
-    {
-        if distances.contains_key(&(position.0, position.1, direction, steps)) {
-            continue;
-        }
-
-        distances.insert((position.0, position.1, direction, steps), distance);
-
-        for (i, &(row_moves, colum_moves)) in directions.iter().enumerate() {
-            let new_position = (
-                position.0 as isize + row_moves,
-                position.1 as isize + colum_moves,
-            );
-            if new_position.0 >= 0
-                && new_position.0 < city.blocks.len() as isize
-                && new_position.1 >= 0
-                && new_position.1 < city.blocks[0].len() as isize
-            {
-                let new_direction = i as isize;
-                let new_steps = if new_direction != direction {
-                    1
-                } else {
-                    steps + 1
-                };
-                if validate_move(new_steps, steps, direction, new_direction) {
-                    let cost =
-                        city.blocks[new_position.0 as usize][new_position.1 as usize].heat_loss;
-                    queue.push(State {
-                        position: (new_position.0 as usize, new_position.1 as usize),
-                        direction: new_direction,
-                        steps: new_steps,
-                        distance: distance + cost,
-                    });
-                }
-            }
-        }
+    match calculate_least_heat_loss(&input.city, rules) {
+        Some(heat_loss) => Ok(heat_loss),
+        None => error("No way to the target with these rules"),
     }
-
-    distances
-        .iter()
-        .filter(|&(&(r, c, _, _), _)| r == city.blocks.len() - 1 && c == city.blocks[0].len() - 1)
-        .map(|(_, &v)| v)
-        .min()
-        .unwrap_or(usize::MAX)
 }
 
-fn part_1(input_file: &str) -> std::io::Result<usize> {
-    let input: Input = parse(input_file)?;
-    Ok(calculate_least_heat_loss(&input.city, validate_part_1))
+pub fn part_1(input_file: &str) -> std::io::Result<usize> {
+    least_heat_loss(input_file, &CrucibleRules::crucible())
 }
 
-fn part_2(input_file: &str) -> std::io::Result<usize> {
-    let input: Input = parse(input_file)?;
-    Ok(calculate_least_heat_loss(&input.city, validate_part_2))
+pub fn part_2(input_file: &str) -> std::io::Result<usize> {
+    least_heat_loss(input_file, &CrucibleRules::ultra_crucible())
 }
 
+solution!(17);
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{least_heat_loss, part_1, part_2, Input};
+    use crate::utils::crucible::CrucibleRules;
+    use crate::utils::parser::{parse, ParseError, Source};
 
     const INPUT: &str = "input/roar/q17_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q17_sample.txt";
@@ -172,6 +117,28 @@ mod tests {
         assert_eq!(result.unwrap(), 102);
     }
 
+    #[test]
+    fn roar_q17_other_vehicles() {
+        let wobbly = CrucibleRules {
+            max_straight: 1,
+            ..CrucibleRules::crucible()
+        };
+        // Turning after every block
+        let result = least_heat_loss(INPUT_SAMPLE, &wobbly);
+        assert_eq!(result.unwrap(), 133);
+        let short_trip = CrucibleRules {
+            target: Some((0, 4)),
+            ..CrucibleRules::ultra_crucible()
+        };
+        // 4 + 1 + 3 + 4 straight along the top row
+        assert_eq!(least_heat_loss(INPUT_SAMPLE, &short_trip).unwrap(), 12);
+        let off_the_map = CrucibleRules {
+            target: Some((0, 13)),
+            ..CrucibleRules::crucible()
+        };
+        assert!(least_heat_loss(INPUT_SAMPLE, &off_the_map).is_err());
+    }
+
     #[test]
     fn roar_q17_p1_main() {
         let result = part_1(INPUT);
@@ -189,4 +156,26 @@ mod tests {
         let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 809);
     }
+
+    #[test]
+    fn roar_q17_agrees_with_gee() {
+        let rules = CrucibleRules {
+            min_straight: 2,
+            max_straight: 5,
+            ..CrucibleRules::crucible()
+        };
+        let roar = least_heat_loss(INPUT_SAMPLE, &rules).unwrap();
+        let gee = crate::gee::q17::least_heat_loss("input/gee/q17_sample.txt", &rules);
+        assert_eq!(roar, 101);
+        assert_eq!(gee.unwrap(), roar);
+    }
+
+    #[test]
+    fn roar_q17_bad_cities() {
+        let e = parse::<Input>(Source::text("123\n12\n")).err().unwrap();
+        let e = ParseError::of(&e).unwrap();
+        assert_eq!((e.line, e.column), (2, 3));
+        assert!(parse::<Input>(Source::text("")).is_err());
+        assert!(parse::<Input>(Source::text("\n")).is_err());
+    }
 }
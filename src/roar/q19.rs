@@ -1,113 +1,373 @@
-#![allow(dead_code, unused_variables)]
-
 use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::solution::solution;
+use crate::utils::parser::{error, parse, FileLines};
+use crate::utils::ranges::intersect;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Category {
+    X,
+    M,
+    A,
+    S,
+}
+
+impl Category {
+    fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "x" => Some(Category::X),
+            "m" => Some(Category::M),
+            "a" => Some(Category::A),
+            "s" => Some(Category::S),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Comparison {
+    LessThan,
+    GreaterThan,
+}
+
+/// Where a rule sends a part. Workflows are referred to by their index in
+/// `System::names`, whether or not they were ever defined.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Target {
+    Accept,
+    Reject,
+    Workflow(usize),
+}
+
+/// e.g. `a<2006:qkq`
+#[derive(Debug)]
+struct Rule {
+    category: Category,
+    comparison: Comparison,
+    value: u32,
+    target: Target,
+}
+
+impl Rule {
+    fn matches(&self, part: &Part) -> bool {
+        let rating = part[self.category as usize];
+        match self.comparison {
+            Comparison::LessThan => rating < self.value,
+            Comparison::GreaterThan => rating > self.value,
+        }
+    }
+
+    /// The ratings that match the rule and those that don't.
+    fn split(&self, range: &Range<u64>) -> (Option<Range<u64>>, Option<Range<u64>>) {
+        let value = u64::from(self.value);
+        match self.comparison {
+            Comparison::LessThan => (
+                intersect(range, &(0..value)),
+                intersect(range, &(value..u64::MAX)),
+            ),
+            Comparison::GreaterThan => (
+                intersect(range, &(value + 1..u64::MAX)),
+                intersect(range, &(0..value + 1)),
+            ),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Workflow {
+    rules: Vec<Rule>,
+    fallback: Target,
+}
+
+impl Workflow {
+    fn targets(&self) -> impl Iterator<Item = Target> + '_ {
+        self.rules
+            .iter()
+            .map(|rule| rule.target)
+            .chain([self.fallback])
+    }
+}
+
+/// Ratings in x, m, a, s order.
+type Part = [u32; 4];
+
+/// A box of parts, one half-open range of ratings per category.
+type Ratings = [Range<u64>; 4];
+
+/// Something wrong with a set of workflows.
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum Problem {
+    /// A workflow that is sent to but never defined.
+    Missing(String),
+    /// A workflow no part can reach from `in`.
+    Unreachable(String),
+    /// Workflows that can send a part round forever, in the order they do.
+    Cycle(Vec<String>),
+}
+
+#[derive(Debug, Default)]
+struct System {
+    names: Vec<String>,
+    ids: HashMap<String, usize>,
+    workflows: Vec<Option<Workflow>>,
+}
+
+impl System {
+    fn id(&mut self, name: &str) -> usize {
+        if let Some(&id) = self.ids.get(name) {
+            return id;
+        }
+        self.names.push(name.to_string());
+        self.workflows.push(None);
+        self.ids.insert(name.to_string(), self.names.len() - 1);
+        self.names.len() - 1
+    }
+
+    fn target(&mut self, name: &str) -> Target {
+        match name {
+            "A" => Target::Accept,
+            "R" => Target::Reject,
+            _ => Target::Workflow(self.id(name)),
+        }
+    }
+
+    fn start(&self) -> Option<usize> {
+        self.ids.get("in").copied()
+    }
+
+    fn workflow(&self, id: usize) -> &Workflow {
+        self.workflows[id]
+            .as_ref()
+            .expect("only validated systems are run")
+    }
+
+    fn validate(&self) -> Vec<Problem> {
+        let mut problems: Vec<Problem> = self
+            .workflows
+            .iter()
+            .enumerate()
+            .filter(|(_, w)| w.is_none())
+            .map(|(id, _)| Problem::Missing(self.names[id].clone()))
+            .collect();
+        if self.start().is_none() {
+            problems.push(Problem::Missing("in".to_string()));
+        }
+
+        // depth first from `in`, where going back to a workflow still on the
+        // path means a cycle
+        let mut reached = vec![false; self.names.len()];
+        let mut path = Vec::new();
+        if let Some(start) = self.start() {
+            self.search(start, &mut reached, &mut path, &mut problems);
+        }
+        for (id, workflow) in self.workflows.iter().enumerate() {
+            if workflow.is_some() && !reached[id] {
+                problems.push(Problem::Unreachable(self.names[id].clone()));
+            }
+        }
+        problems
+    }
+
+    fn search(
+        &self,
+        id: usize,
+        reached: &mut Vec<bool>,
+        path: &mut Vec<usize>,
+        problems: &mut Vec<Problem>,
+    ) {
+        if let Some(i) = path.iter().position(|&p| p == id) {
+            let cycle = path[i..].iter().map(|&p| self.names[p].clone()).collect();
+            problems.push(Problem::Cycle(cycle));
+            return;
+        }
+        if reached[id] {
+            return;
+        }
+        reached[id] = true;
+        let Some(workflow) = &self.workflows[id] else {
+            return;
+        };
+        path.push(id);
+        for target in workflow.targets() {
+            if let Target::Workflow(next) = target {
+                self.search(next, reached, path, problems);
+            }
+        }
+        path.pop();
+    }
 
-use crate::utils::parser::{parse, FileLines};
+    /// Errors on any problem that would stop a part getting sorted.
+    fn check(&self) -> std::io::Result<()> {
+        match self
+            .validate()
+            .into_iter()
+            .find(|p| !matches!(p, Problem::Unreachable(_)))
+        {
+            Some(problem) => error(&format!("Invalid workflows: {:?}", problem)),
+            None => Ok(()),
+        }
+    }
+
+    fn is_accepted(&self, part: &Part, start: usize) -> bool {
+        let mut id = start;
+        loop {
+            let workflow = self.workflow(id);
+            let target = workflow
+                .rules
+                .iter()
+                .find(|rule| rule.matches(part))
+                .map_or(workflow.fallback, |rule| rule.target);
+            match target {
+                Target::Accept => return true,
+                Target::Reject => return false,
+                Target::Workflow(next) => id = next,
+            }
+        }
+    }
+
+    fn count_accepted(&self, target: Target, mut ratings: Ratings) -> u64 {
+        let id = match target {
+            Target::Accept => return ratings.iter().map(|r| r.end - r.start).product(),
+            Target::Reject => return 0,
+            Target::Workflow(id) => id,
+        };
+        let workflow = self.workflow(id);
+        let mut total = 0;
+        for rule in &workflow.rules {
+            let category = rule.category as usize;
+            let (matched, unmatched) = rule.split(&ratings[category]);
+            if let Some(range) = matched {
+                let mut matched_ratings = ratings.clone();
+                matched_ratings[category] = range;
+                total += self.count_accepted(rule.target, matched_ratings);
+            }
+            match unmatched {
+                Some(range) => ratings[category] = range,
+                None => return total,
+            }
+        }
+        total + self.count_accepted(workflow.fallback, ratings)
+    }
+}
 
 struct Input {
-    workflows: HashMap<String, Vec<String>>,
-    part_ratings: Vec<HashMap<String, u32>>,
+    system: System,
+    part_ratings: Vec<Part>,
 }
 
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
     fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
-        let mut workflows_parsed = false;
-        let mut workflows = HashMap::new();
+        let mut system = System::default();
         let mut part_ratings = Vec::new();
+        let mut sections = lines.sections();
 
-        for line in lines {
-            if line.is_empty() {
-                workflows_parsed = true;
-                continue;
+        // parse workflows, e.g. px{a<2006:qkq,m>2090:A,rfg}
+        let mut lines = sections.expect("workflows")?;
+        while let Some(line) = lines.next() {
+            let (key, workflow) = lines.split_once(&line, &line, "{")?;
+            let workflow_str = lines.strip_suffix(&line, workflow, "}")?;
+            let (rules_str, fallback) = match workflow_str.rsplit_once(',') {
+                Some((rules_str, fallback)) => (Some(rules_str), fallback),
+                None => (None, workflow_str),
+            };
+            let mut rules = Vec::new();
+            for rule in rules_str.into_iter().flat_map(|s| s.split(',')) {
+                let (condition, target) = lines.split_once(&line, rule, ":")?;
+                // the variable is one character, which needn't be one byte
+                let variable_len = condition.chars().next().map_or(0, char::len_utf8);
+                let (variable, rest) = condition.split_at(variable_len);
+                let Some(category) = Category::from_name(variable) else {
+                    return Err(lines.error_at(&line, condition, "x, m, a or s"));
+                };
+                let comparison = match rest.chars().next() {
+                    Some('<') => Comparison::LessThan,
+                    Some('>') => Comparison::GreaterThan,
+                    _ => return Err(lines.error_at(&line, rest, "< or >")),
+                };
+                let value = lines.parse_at(&line, &rest[1..], "a rating")?;
+                rules.push(Rule {
+                    category,
+                    comparison,
+                    value,
+                    target: system.target(target),
+                });
+            }
+            if fallback.is_empty() || fallback.contains(':') {
+                return Err(lines.error_at(&line, fallback, "a fallback workflow"));
             }
-            if workflows_parsed {
-                // parse part ratings
-                let mut part_rating: HashMap<String, u32> = HashMap::new();
-                let input = line.trim_matches('{').trim_matches('}');
-                let pairs: Vec<&str> = input.split(',').collect();
-                for pair in pairs {
-                    let kv: Vec<&str> = pair.split('=').collect();
-                    part_rating.insert(kv[0].to_string(), kv[1].parse::<u32>().unwrap());
-                }
-                part_ratings.push(part_rating);
-            } else {
-                // parse workflows
-                let (key, workflow) = line.split_once('{').unwrap();
-                let workflow_str = workflow.trim_matches('}');
-                workflows.insert(
-                    key.to_string(),
-                    workflow_str.split(',').map(|s| s.to_string()).collect(),
-                );
+            let fallback = system.target(fallback);
+            let id = system.id(key);
+            if system.workflows[id].is_some() {
+                return Err(lines.error_at(&line, key, "a new workflow name"));
+            }
+            system.workflows[id] = Some(Workflow { rules, fallback });
+        }
+
+        // parse part ratings
+        let mut lines = sections.expect("part ratings")?;
+        while let Some(line) = lines.next() {
+            let mut part_rating = [0; 4];
+            let input = lines.strip_prefix(&line, &line, "{")?;
+            let input = lines.strip_suffix(&line, input, "}")?;
+            for pair in input.split(',') {
+                let (key, value) = lines.split_once(&line, pair, "=")?;
+                let Some(category) = Category::from_name(key) else {
+                    return Err(lines.error_at(&line, key, "x, m, a or s"));
+                };
+                part_rating[category as usize] = lines.parse_at(&line, value, "a rating")?;
             }
+            part_ratings.push(part_rating);
         }
 
-        // parse each line: operation: function
         Ok(Input {
-            workflows,
+            system,
             part_ratings,
         })
     }
 }
 
-fn part_1(input_file: &str) -> std::io::Result<u32> {
+pub fn part_1(input_file: &str) -> std::io::Result<u32> {
     let input: Input = parse(input_file)?;
-    let mut total = 0;
-    println!("workflows: {:?}", input.workflows);
-    for part_rating in input.part_ratings {
-        // process workflow
-        let mut current_workflow = "in";
-        while current_workflow != "R" && current_workflow != "A" {
-            println!("current_workflow: {}", current_workflow);
-            let workflow = &input.workflows[current_workflow];
-            for operation in workflow {
-                if operation == "R" || operation == "A" || input.workflows.contains_key(operation) {
-                    current_workflow = operation;
-                    break;
-                }
-                // This must be an operation
-                let (variable, rest) = operation.split_at(1);
-                let operator = if rest.starts_with('<') { "<" } else { ">" };
-                let rest = rest[1..].split(":").collect::<Vec<&str>>();
-                let value: u32 = rest[0].parse().unwrap();
-                let value_to_compare = part_rating[variable];
-                let next_workflow = rest[1];
-                println!(
-                    "variable {}, val: {}, next: {},  operator: {}",
-                    variable, value_to_compare, next_workflow, operator
-                );
-
-                if operator == "<" && value_to_compare < value {
-                    current_workflow = next_workflow;
-                    break;
-                } else if operator == ">" && value_to_compare > value {
-                    current_workflow = next_workflow;
-                    break;
-                }
-            }
-        }
-
-        if current_workflow == "A" {
-            total += part_rating["x"] + part_rating["m"] + part_rating["a"] + part_rating["s"];
-        }
-    }
-
+    input.system.check()?;
+    let start = input.system.start().unwrap();
+    let total = input
+        .part_ratings
+        .iter()
+        .filter(|part| input.system.is_accepted(part, start))
+        .map(|part| part.iter().sum::<u32>())
+        .sum();
     Ok(total)
 }
 
-fn part_2(input_file: &str) -> std::io::Result<u32> {
+pub fn part_2(input_file: &str) -> std::io::Result<u64> {
     let input: Input = parse(input_file)?;
-    Ok(0)
+    input.system.check()?;
+    let start = input.system.start().unwrap();
+    let ratings: Ratings = std::array::from_fn(|_| 1..4001);
+    Ok(input
+        .system
+        .count_accepted(Target::Workflow(start), ratings))
 }
 
+solution!(19);
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{part_1, part_2, Input, Problem};
+    use crate::utils::parser::{parse, ParseError, Source};
 
     const INPUT: &str = "input/roar/q19_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q19_sample.txt";
 
+    fn problems(workflows: &str) -> Vec<Problem> {
+        let text = format!("{}\n\n{{x=1,m=2,a=3,s=4}}\n", workflows);
+        let input: Input = parse(Source::text(text)).unwrap();
+        input.system.validate()
+    }
+
     #[test]
     fn roar_q19_p1_sample() {
         let result = part_1(INPUT_SAMPLE);
@@ -123,12 +383,48 @@ mod tests {
     #[test]
     fn roar_q19_p2_sample() {
         let result = part_2(INPUT_SAMPLE);
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap(), 167409079868000);
     }
 
     #[test]
     fn roar_q19_p2_main() {
         let result = part_2(INPUT);
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap(), 121464316215623);
+    }
+
+    #[test]
+    fn roar_q19_validate() {
+        assert_eq!(problems("in{x<5:A,R}"), []);
+        assert_eq!(
+            problems("in{x<5:ab,R}\nzz{A}"),
+            [
+                Problem::Missing("ab".to_string()),
+                Problem::Unreachable("zz".to_string())
+            ]
+        );
+        assert_eq!(
+            problems("in{a>1:one,R}\none{m<2:A,two}\ntwo{one}"),
+            [Problem::Cycle(vec!["one".to_string(), "two".to_string()])]
+        );
+        assert_eq!(
+            problems("px{A}"),
+            [
+                Problem::Missing("in".to_string()),
+                Problem::Unreachable("px".to_string())
+            ]
+        );
+        assert!(part_1(INPUT_SAMPLE).is_ok());
+    }
+
+    #[test]
+    fn roar_q19_bad_conditions() {
+        let column = |workflows: &str| {
+            let text = format!("{}\n\n{{x=1,m=2,a=3,s=4}}\n", workflows);
+            let e = parse::<Input>(Source::text(text)).err().unwrap();
+            ParseError::of(&e).unwrap().column
+        };
+        assert_eq!(column("in{é<5:A,R}"), 4);
+        assert_eq!(column("in{x≤5:A,R}"), 5);
+        assert_eq!(column("in{:A,R}"), 4);
     }
 }
@@ -1,8 +1,10 @@
 #![allow(dead_code, unused_variables)]
 
-use std::collections::{HashSet, VecDeque};
+use std::collections::HashSet;
 
-use crate::utils::parser::{parse, FileLines};
+use crate::solution::{Answer, Solution};
+use crate::utils::grid::Grid;
+use crate::utils::parser::{error, parse, FileLines};
 
 #[derive(PartialEq, Eq, Hash)]
 struct Point {
@@ -23,140 +25,234 @@ struct GardenPlot {
 
 struct Input {
     starting_position: Point,
-    garden: Vec<Vec<GardenPlot>>,
+    garden: Grid<GardenPlot>,
 }
 
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
     fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
-        let mut starting_position = Point { row: 0, column: 0 };
-        let mut garden = Vec::new();
-        for line in lines {
-            let mut row = Vec::new();
-            for character in line.chars() {
-                let plot_type = match character {
-                    '.' => PlotType::GARDEN,
-                    '#' => PlotType::ROCKS,
-                    'S' => {
-                        starting_position = Point {
-                            row: garden.len(),
-                            column: row.len(),
-                        };
-                        PlotType::GARDEN
-                    }
-                    _ => panic!("Invalid character in input"),
-                };
-                row.push(GardenPlot { plot_type });
-            }
-            garden.push(row);
+        let map = Grid::parse(lines, "a garden plot, rock or start", |c| {
+            "S.#".contains(c).then_some(c)
+        })?;
+        if map.height() == 0 {
+            return error("The map is empty");
         }
+        let mut starts = map.find_all(&'S');
+        let (Some((row, column)), None) = (starts.next(), starts.next()) else {
+            return error("The map needs exactly one start");
+        };
+        let garden = map.map(|&c| GardenPlot {
+            plot_type: match c {
+                '#' => PlotType::ROCKS,
+                _ => PlotType::GARDEN,
+            },
+        });
         Ok(Input {
-            starting_position,
+            starting_position: Point { row, column },
             garden,
         })
     }
 }
 
-fn get_reachable_plots(
-    garden_map: &Vec<Vec<GardenPlot>>,
-    start: (usize, usize),
-    steps: usize,
-) -> HashSet<Point> {
-    let mut visited = HashSet::new();
-    let mut queue = VecDeque::new();
-    let mut points = HashSet::new();
-
-    queue.push_back((start, 0));
-    visited.insert((start, 0));
-
-    while let Some((position, step)) = queue.pop_front() {
-        if step == steps {
-            points.insert(Point {
-                row: position.0,
-                column: position.1,
-            });
-            continue;
+/// What lies past the edges of the map.
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Edges {
+    /// Nothing: the map is all there is.
+    Walled,
+    /// The map again, repeating forever in every direction.
+    Repeating,
+}
+
+fn is_garden(garden_map: &Grid<GardenPlot>, (row, column): (i64, i64), edges: Edges) -> bool {
+    let (height, width) = (garden_map.height() as i64, garden_map.width() as i64);
+    let (row, column) = match edges {
+        Edges::Walled if !(0..height).contains(&row) || !(0..width).contains(&column) => {
+            return false
         }
+        Edges::Walled => (row, column),
+        // modular coordinates map any tile back onto the original map
+        Edges::Repeating => (row.rem_euclid(height), column.rem_euclid(width)),
+    };
+    garden_map[(row as usize, column as usize)].plot_type == PlotType::GARDEN
+}
+
+/// How many plots can be reached in exactly 0, 1, 2, ... `steps` steps.
+/// A plot first reached in d steps can be reached again in d + 2 by
+/// stepping away and back, so it counts for every later step of the same
+/// parity.
+fn get_reachable_counts(
+    garden_map: &Grid<GardenPlot>,
+    start: (i64, i64),
+    steps: usize,
+    edges: Edges,
+) -> Vec<usize> {
+    let mut visited = HashSet::from([start]);
+    let mut frontier = vec![start];
+    let mut counts: Vec<usize> = Vec::with_capacity(steps + 1);
+
+    for step in 0..=steps {
+        let before = match step {
+            0 | 1 => 0,
+            _ => counts[step - 2],
+        };
+        counts.push(before + frontier.len());
 
-        for neighbor in get_neighbors(position, garden_map) {
-            let next_step = step + 1;
-            if !visited.contains(&(neighbor, next_step)) && next_step <= steps {
-                visited.insert((neighbor, next_step));
-                queue.push_back((neighbor, next_step));
+        let mut next = Vec::new();
+        for (row, column) in frontier {
+            for neighbor in [
+                (row - 1, column),
+                (row + 1, column),
+                (row, column - 1),
+                (row, column + 1),
+            ] {
+                if is_garden(garden_map, neighbor, edges) && visited.insert(neighbor) {
+                    next.push(neighbor);
+                }
             }
         }
+        frontier = next;
     }
-    points
+    counts
 }
 
-fn get_neighbors(
-    position: (usize, usize),
-    garden_map: &Vec<Vec<GardenPlot>>,
-) -> Vec<(usize, usize)> {
-    let (x, y) = position;
-    let mut neighbors = Vec::new();
+/// Plots reachable in exactly `steps` steps on the repeating map, by brute
+/// force.
+fn count_reachable(garden_map: &Grid<GardenPlot>, start: (i64, i64), steps: usize) -> usize {
+    get_reachable_counts(garden_map, start, steps, Edges::Repeating)[steps]
+}
 
-    if x > 0 && garden_map[x - 1][y].plot_type != PlotType::ROCKS {
-        neighbors.push((x - 1, y));
-    }
-    if x < garden_map.len() - 1 && garden_map[x + 1][y].plot_type != PlotType::ROCKS {
-        neighbors.push((x + 1, y));
-    }
-    if y > 0 && garden_map[x][y - 1].plot_type != PlotType::ROCKS {
-        neighbors.push((x, y - 1));
+/// Plots reachable in exactly `steps` steps on the repeating map, for step
+/// counts far too big to walk.
+///
+/// Once the walk has spread over a few copies of the map, every `size`
+/// more steps adds another ring of copies, so the counts at steps, steps -
+/// size, steps - 2 * size, ... grow quadratically: their second differences
+/// settle to a constant. Walk far enough to see three equal second
+/// differences in a row, then extend the quadratic out to `steps`.
+fn count_reachable_far(
+    garden_map: &Grid<GardenPlot>,
+    start: (i64, i64),
+    steps: usize,
+) -> std::io::Result<usize> {
+    let size = garden_map.height();
+    if garden_map.width() != size {
+        return error("The map has to be square to repeat the same way both ways");
     }
-    if y < garden_map[0].len() - 1 && garden_map[x][y + 1].plot_type != PlotType::ROCKS {
-        neighbors.push((x, y + 1));
+    let remainder = steps % size;
+    let mut repeats = 4;
+    while repeats <= 64 {
+        let walked = remainder + repeats * size;
+        if walked >= steps {
+            return Ok(count_reachable(garden_map, start, steps));
+        }
+        let counts = get_reachable_counts(garden_map, start, walked, Edges::Repeating);
+        let samples: Vec<i64> = (0..=repeats)
+            .map(|k| counts[remainder + k * size] as i64)
+            .collect();
+        let firsts: Vec<i64> = samples.windows(2).map(|w| w[1] - w[0]).collect();
+        let seconds: Vec<i64> = firsts.windows(2).map(|w| w[1] - w[0]).collect();
+        if let [.., a, b, c] = seconds[..] {
+            if a == b && b == c {
+                // n more rings past the last sample, each growing by c more
+                // than the one before
+                let n = ((steps - walked) / size) as i64;
+                let last = samples[repeats] as i128;
+                let first = firsts[repeats - 1] as i128;
+                let (n, c) = (n as i128, c as i128);
+                let total = last + n * first + c * n * (n + 1) / 2;
+                return Ok(total as usize);
+            }
+        }
+        repeats *= 2;
     }
+    error("The reachable plots never settle into quadratic growth")
+}
 
-    neighbors
+pub fn part_1(steps: usize, input_file: &str) -> std::io::Result<usize> {
+    let input: Input = parse(input_file)?;
+    let start = (
+        input.starting_position.row as i64,
+        input.starting_position.column as i64,
+    );
+    let counts = get_reachable_counts(&input.garden, start, steps, Edges::Walled);
+    Ok(counts[steps])
 }
 
-fn part_1(steps: usize, input_file: &str) -> std::io::Result<usize> {
+pub fn part_2(steps: usize, input_file: &str) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
-    let points = get_reachable_plots(
-        &input.garden,
-        (input.starting_position.row, input.starting_position.column),
-        steps,
+    let start = (
+        input.starting_position.row as i64,
+        input.starting_position.column as i64,
     );
+    count_reachable_far(&input.garden, start, steps)
+}
 
-    // print garden
-    for (i, row) in input.garden.iter().enumerate() {
-        for (j, plot) in row.iter().enumerate() {
-            if points.contains(&Point { row: i, column: j }) {
-                print!("O");
-            } else {
-                match plot.plot_type {
-                    PlotType::GARDEN => print!("."),
-                    PlotType::ROCKS => print!("#"),
-                }
-            }
+/// How many steps the elf takes in part 1, and on the infinite map in part 2.
+pub struct Params {
+    pub steps: usize,
+    pub far_steps: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            steps: 64,
+            far_steps: 26_501_365,
         }
-        println!();
     }
-
-    Ok(points.len())
 }
 
-fn part_2(input_file: &str) -> std::io::Result<usize> {
-    let input: Input = parse(input_file)?;
-    Ok(0)
+pub struct Solver;
+
+impl Solution for Solver {
+    type Params = Params;
+
+    const DAY: u8 = 21;
+
+    fn part_1(input_file: &str, params: &Params) -> std::io::Result<Answer> {
+        part_1(params.steps, input_file).map(Answer::from)
+    }
+
+    fn part_2(input_file: &str, params: &Params) -> std::io::Result<Answer> {
+        part_2(params.far_steps, input_file).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{count_reachable, count_reachable_far, part_1, part_2, Input, Params, Solver};
+    use crate::solution::{Answer, Solution};
+    use crate::utils::parser::{parse, Source};
 
     const INPUT: &str = "input/roar/q21_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q21_sample.txt";
 
+    fn garden(input_file: &str) -> (Input, (i64, i64)) {
+        let input: Input = parse(input_file).unwrap();
+        let start = (
+            input.starting_position.row as i64,
+            input.starting_position.column as i64,
+        );
+        (input, start)
+    }
+
     #[test]
     fn roar_q21_p1_sample() {
         let result = part_1(6, INPUT_SAMPLE);
         assert_eq!(result.unwrap(), 16);
     }
 
+    #[test]
+    fn roar_q21_p1_sample_params() {
+        let params = Params {
+            steps: 6,
+            ..Params::default()
+        };
+        let result = Solver::part_1(INPUT_SAMPLE, &params);
+        assert_eq!(result.unwrap(), Answer(16));
+    }
+
     #[test]
     fn roar_q21_p1_main() {
         let result = part_1(64, INPUT);
@@ -165,13 +261,60 @@ mod tests {
 
     #[test]
     fn roar_q21_p2_sample() {
-        let result = part_2(INPUT_SAMPLE);
-        assert_eq!(result.unwrap(), 0);
+        let expected = [
+            (6, 16),
+            (10, 50),
+            (50, 1594),
+            (100, 6536),
+            (500, 167004),
+            (1000, 668697),
+            (5000, 16733044),
+        ];
+        for (steps, plots) in expected {
+            assert_eq!(part_2(steps, INPUT_SAMPLE).unwrap(), plots);
+        }
     }
 
     #[test]
     fn roar_q21_p2_main() {
-        let result = part_2(INPUT);
-        assert_eq!(result.unwrap(), 0);
+        let result = part_2(26_501_365, INPUT);
+        assert_eq!(result.unwrap(), 608193767979991);
+    }
+
+    #[test]
+    fn roar_q21_brute_force() {
+        let (input, start) = garden(INPUT_SAMPLE);
+        assert_eq!(count_reachable(&input.garden, start, 100), 6536);
+        // Past the first few repeats of the map the extrapolation takes over
+        // from walking, and has to agree with it
+        for steps in [200, 301, 405] {
+            assert_eq!(
+                count_reachable_far(&input.garden, start, steps).unwrap(),
+                count_reachable(&input.garden, start, steps)
+            );
+        }
+
+        let (input, start) = garden(INPUT);
+        for steps in [65 + 131 * 5, 700] {
+            assert_eq!(
+                count_reachable_far(&input.garden, start, steps).unwrap(),
+                count_reachable(&input.garden, start, steps)
+            );
+        }
+    }
+
+    #[test]
+    fn roar_q21_bad_maps() {
+        let map = |text: &str| parse::<Input>(Source::text(text));
+        assert!(map("").is_err());
+        assert!(map("...\n.#.\n...\n").is_err());
+        assert!(map("S..\n.#.\n..S\n").is_err());
+        assert!(map("S..\n.#\n...\n").is_err());
+        assert!(map("S..\n.x.\n...\n").is_err());
+        let input = map("...\n.#S\n...\n").unwrap();
+        assert_eq!(
+            (input.starting_position.row, input.starting_position.column),
+            (1, 2)
+        );
     }
 }
@@ -1,32 +1,188 @@
-#![allow(dead_code, unused_variables)]
+use std::collections::{HashSet, VecDeque};
 
+use crate::solution::solution;
 use crate::utils::parser::{parse, FileLines};
 
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Cube {
+    x: usize,
+    y: usize,
+    z: usize,
+}
+
+/// A line of cubes from `start` to `end`, e.g. `1,0,1~1,2,1`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Brick {
+    start: Cube,
+    end: Cube,
+}
+
+impl Brick {
+    /// Every (x, y) the brick covers, which is what it lands on.
+    fn footprint(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        (self.start.x..=self.end.x)
+            .flat_map(move |x| (self.start.y..=self.end.y).map(move |y| (x, y)))
+    }
+
+    fn height(&self) -> usize {
+        self.end.z - self.start.z + 1
+    }
+}
+
 struct Input {
-    value: usize,
+    bricks: Vec<Brick>,
 }
 
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
-        Ok(Input { value: 0 })
+    fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
+        let mut bricks = Vec::new();
+        while let Some(line) = lines.next() {
+            let (start, end) = lines.split_once(&line, &line, "~")?;
+            let mut cubes = Vec::new();
+            for cube in [start, end] {
+                let (x, rest) = lines.split_once(&line, cube, ",")?;
+                let (y, z) = lines.split_once(&line, rest, ",")?;
+                cubes.push(Cube {
+                    x: lines.parse_at(&line, x, "an x coordinate")?,
+                    y: lines.parse_at(&line, y, "a y coordinate")?,
+                    z: lines.parse_at(&line, z, "a z coordinate")?,
+                });
+            }
+            let (start, end) = (cubes[0], cubes[1]);
+            // ends can come either way round, but the ground is at z = 0
+            let brick = Brick {
+                start: Cube {
+                    x: start.x.min(end.x),
+                    y: start.y.min(end.y),
+                    z: start.z.min(end.z),
+                },
+                end: Cube {
+                    x: start.x.max(end.x),
+                    y: start.y.max(end.y),
+                    z: start.z.max(end.z),
+                },
+            };
+            if brick.start.z == 0 {
+                return Err(lines.error_at(&line, &line, "a brick above the ground"));
+            }
+            bricks.push(brick);
+        }
+        Ok(Input { bricks })
     }
 }
 
-fn part_1(input_file: &str) -> std::io::Result<usize> {
+/// The bricks once they've all fallen as far as they can, and which bricks
+/// rest on which. Bricks keep their order from the input.
+#[derive(Debug)]
+struct Settled {
+    bricks: Vec<Brick>,
+    /// The bricks resting directly on each brick.
+    supports: Vec<Vec<usize>>,
+    /// The bricks each brick rests directly on.
+    supported_by: Vec<Vec<usize>>,
+}
+
+fn settle(bricks: &[Brick]) -> Settled {
+    let width = bricks.iter().map(|b| b.end.x + 1).max().unwrap_or(0);
+    let depth = bricks.iter().map(|b| b.end.y + 1).max().unwrap_or(0);
+    // the top of the pile at each (x, y), and which brick is there
+    let mut height_map: Vec<Vec<(usize, Option<usize>)>> = vec![vec![(0, None); depth]; width];
+
+    let mut settled = bricks.to_vec();
+    let mut supports = vec![Vec::new(); bricks.len()];
+    let mut supported_by = vec![Vec::new(); bricks.len()];
+
+    // lowest first, so everything underneath a brick has already landed
+    let mut order: Vec<usize> = (0..bricks.len()).collect();
+    order.sort_by_key(|&i| bricks[i].start.z);
+
+    for i in order {
+        let brick = bricks[i];
+        let top = brick
+            .footprint()
+            .map(|(x, y)| height_map[x][y].0)
+            .max()
+            .unwrap_or(0);
+        let mut below: Vec<usize> = brick
+            .footprint()
+            .filter_map(|(x, y)| match height_map[x][y] {
+                (z, Some(other)) if z == top => Some(other),
+                _ => None,
+            })
+            .collect();
+        below.sort();
+        below.dedup();
+        for &other in &below {
+            supports[other].push(i);
+        }
+        supported_by[i] = below;
+
+        let fall = brick.start.z - (top + 1);
+        settled[i].start.z -= fall;
+        settled[i].end.z -= fall;
+        for (x, y) in brick.footprint() {
+            height_map[x][y] = (top + brick.height(), Some(i));
+        }
+    }
+
+    Settled {
+        bricks: settled,
+        supports,
+        supported_by,
+    }
+}
+
+impl Settled {
+    /// A brick can go if everything it holds up has something else to rest on.
+    fn is_safe_to_disintegrate(&self, brick: usize) -> bool {
+        self.supports[brick]
+            .iter()
+            .all(|&above| self.supported_by[above].len() > 1)
+    }
+
+    /// How many other bricks fall if `brick` is disintegrated: any brick whose
+    /// supports have all gone falls too, and so on up the pile.
+    fn count_falling(&self, brick: usize) -> usize {
+        let mut fallen = HashSet::from([brick]);
+        let mut queue = VecDeque::from([brick]);
+        while let Some(current) = queue.pop_front() {
+            for &above in &self.supports[current] {
+                if !fallen.contains(&above)
+                    && self.supported_by[above].iter().all(|b| fallen.contains(b))
+                {
+                    fallen.insert(above);
+                    queue.push_back(above);
+                }
+            }
+        }
+        fallen.len() - 1
+    }
+}
+
+pub fn part_1(input_file: &str) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
-    Ok(0)
+    let settled = settle(&input.bricks);
+    Ok((0..settled.bricks.len())
+        .filter(|&brick| settled.is_safe_to_disintegrate(brick))
+        .count())
 }
 
-fn part_2(input_file: &str) -> std::io::Result<usize> {
+pub fn part_2(input_file: &str) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
-    Ok(0)
+    let settled = settle(&input.bricks);
+    Ok((0..settled.bricks.len())
+        .map(|brick| settled.count_falling(brick))
+        .sum())
 }
 
+solution!(22);
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{part_1, part_2, settle, Input};
+    use crate::utils::parser::{parse, Source};
 
     const INPUT: &str = "input/roar/q22_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q22_sample.txt";
@@ -34,24 +190,49 @@ mod tests {
     #[test]
     fn roar_q22_p1_sample() {
         let result = part_1(INPUT_SAMPLE);
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap(), 5);
     }
 
     #[test]
     fn roar_q22_p1_main() {
         let result = part_1(INPUT);
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap(), 398);
     }
 
     #[test]
     fn roar_q22_p2_sample() {
         let result = part_2(INPUT_SAMPLE);
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap(), 7);
     }
 
     #[test]
     fn roar_q22_p2_main() {
         let result = part_2(INPUT);
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap(), 70727);
+    }
+
+    #[test]
+    fn roar_q22_settle() {
+        // Bricks A to G of the sample
+        let input: Input = parse(INPUT_SAMPLE).unwrap();
+        let settled = settle(&input.bricks);
+        let lowest: Vec<usize> = settled.bricks.iter().map(|b| b.start.z).collect();
+        assert_eq!(lowest, [1, 2, 2, 3, 3, 4, 5]);
+        assert_eq!(settled.supports[0], [1, 2]);
+        assert_eq!(settled.supported_by[3], [1, 2]);
+        assert_eq!(settled.supported_by[6], [5]);
+        assert_eq!(settled.count_falling(0), 6);
+        assert_eq!(settled.count_falling(5), 1);
+        assert!(!settled.is_safe_to_disintegrate(0));
+        assert!(settled.is_safe_to_disintegrate(1));
+
+        // Ends given top first still make a vertical brick
+        let input: Input = parse(Source::text("0,0,5~0,0,3\n0,0,9~0,0,9\n")).unwrap();
+        let settled = settle(&input.bricks);
+        assert_eq!((settled.bricks[0].start.z, settled.bricks[0].end.z), (1, 3));
+        assert_eq!(settled.bricks[1].start.z, 4);
+
+        assert!(parse::<Input>(Source::text("0,0,0~0,0,1\n")).is_err());
+        assert!(parse::<Input>(Source::text("0,0,1-0,0,1\n")).is_err());
     }
 }
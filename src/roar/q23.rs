@@ -1,32 +1,287 @@
-#![allow(dead_code, unused_variables)]
+use std::collections::HashMap;
 
-use crate::utils::parser::{parse, FileLines};
+use petgraph::graph::{DiGraph, NodeIndex};
+use petgraph::visit::EdgeRef;
+
+use crate::solution::solution;
+use crate::utils::dot::Dot;
+use crate::utils::geom::Direction;
+use crate::utils::grid::{Grid, Position};
+use crate::utils::parser::{error, parse, FileLines};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Tile {
+    Path,
+    Forest,
+    /// A slope that can only be walked down, the way it points.
+    Slope(Direction),
+}
 
 struct Input {
-    value: usize,
+    map: Grid<Tile>,
+    start: Position,
+    end: Position,
 }
 
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
     fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
-        Ok(Input { value: 0 })
+        let map = Grid::parse(lines, "a path, forest or slope", |c| match c {
+            '.' => Some(Tile::Path),
+            '#' => Some(Tile::Forest),
+            '^' => Some(Tile::Slope(Direction::Up)),
+            '>' => Some(Tile::Slope(Direction::Right)),
+            'v' => Some(Tile::Slope(Direction::Down)),
+            '<' => Some(Tile::Slope(Direction::Left)),
+            _ => None,
+        })?;
+        if map.height() < 2 {
+            return error("The map needs a top and bottom row");
+        }
+        let path_in = |r: usize| (0..map.width()).find(|&c| map[(r, c)] == Tile::Path);
+        let (Some(start), Some(end)) = (path_in(0), path_in(map.height() - 1)) else {
+            return error("No path in the top or bottom row");
+        };
+        Ok(Input {
+            start: (0, start),
+            end: (map.height() - 1, end),
+            map,
+        })
+    }
+}
+
+/// The trails with every corridor squashed into one weighted edge between
+/// the junctions at its ends, the start and the end.
+pub struct Trails {
+    pub graph: DiGraph<Position, usize>,
+    pub start: NodeIndex,
+    pub end: NodeIndex,
+}
+
+impl Trails {
+    /// The junction graph of the map. With `slippery` slopes can only be
+    /// walked down, so some corridors only go one way.
+    fn new(input: &Input, slippery: bool) -> Self {
+        let map = &input.map;
+        let open = |p: Position| map[p] != Tile::Forest;
+        let can_step = |from: Position, direction: Direction| {
+            let to = map.step(from, direction.offset()).filter(|&p| open(p))?;
+            match map[from] {
+                Tile::Slope(downhill) if slippery && downhill != direction => None,
+                _ => Some(to),
+            }
+        };
+
+        let mut graph = DiGraph::new();
+        let mut junctions = HashMap::new();
+        for (position, _) in map.iter().filter(|&(p, _)| open(p)) {
+            let exits = map.neighbors4(position).filter(|&p| open(p)).count();
+            if exits > 2 || position == input.start || position == input.end {
+                junctions.insert(position, graph.add_node(position));
+            }
+        }
+
+        // follow each corridor out of each junction to whatever it leads to
+        for node in graph.node_indices() {
+            let from = graph[node];
+            for direction in Direction::ALL {
+                let Some(mut position) = can_step(from, direction) else {
+                    continue;
+                };
+                let mut previous = from;
+                let mut length = 1;
+                let to = loop {
+                    if let Some(&to) = junctions.get(&position) {
+                        break Some(to);
+                    }
+                    let next = Direction::ALL
+                        .into_iter()
+                        .filter_map(|d| can_step(position, d))
+                        .find(|&p| p != previous);
+                    match next {
+                        Some(next) => {
+                            (previous, position) = (position, next);
+                            length += 1;
+                        }
+                        None => break None,
+                    }
+                };
+                // Some corridors don't go anywhere, or only lead back to
+                // where they started
+                let Some(to) = to.filter(|&to| to != node) else {
+                    continue;
+                };
+                match graph.find_edge(node, to) {
+                    Some(edge) if graph[edge] >= length => {}
+                    Some(edge) => graph[edge] = length,
+                    None => {
+                        graph.add_edge(node, to, length);
+                    }
+                }
+            }
+        }
+
+        Trails {
+            graph,
+            start: junctions[&input.start],
+            end: junctions[&input.end],
+        }
+    }
+
+    /// The most steps of any hike from the start to the end that never
+    /// goes back over itself, if there is one.
+    ///
+    /// A depth first search over every path, with the junctions visited so
+    /// far as bits of a mask. Once at the junction next to the end, the hike
+    /// has to go straight there, since any other way cuts it off.
+    fn longest_hike(&self) -> std::io::Result<Option<usize>> {
+        let count = self.graph.node_count();
+        if count > 64 {
+            return error(&format!("{} junctions don't fit in a bit mask", count));
+        }
+        let neighbors: Vec<Vec<(usize, usize)>> = self
+            .graph
+            .node_indices()
+            .map(|node| {
+                self.graph
+                    .edges(node)
+                    .map(|edge| (edge.target().index(), *edge.weight()))
+                    .collect()
+            })
+            .collect();
+        let end = self.end.index();
+        let before_end: Vec<(usize, usize)> = self
+            .graph
+            .edges_directed(self.end, petgraph::Direction::Incoming)
+            .map(|edge| (edge.source().index(), *edge.weight()))
+            .collect();
+        let last_leg = match before_end[..] {
+            [(node, length)] => Some((node, length)),
+            _ => None,
+        };
+
+        let mut longest = None;
+        search(
+            &neighbors,
+            self.start.index(),
+            end,
+            last_leg,
+            1 << self.start.index(),
+            0,
+            &mut longest,
+        );
+        Ok(longest)
+    }
+
+    /// A GraphViz digraph of the junctions, labelled with their (row, column)
+    /// and the length of each corridor. Two-way corridors are drawn once.
+    pub fn to_dot(&self) -> Dot {
+        let mut dot = Dot::digraph("trails");
+        for node in self.graph.node_indices() {
+            let (row, column) = self.graph[node];
+            let id = node.index().to_string();
+            let label = format!("{},{}", row, column);
+            dot.node(&id, &[("label", &label)]);
+            if node == self.start || node == self.end {
+                dot.node(&id, &[("shape", "doublecircle"), ("color", "green")]);
+            }
+        }
+        for edge in self.graph.edge_references() {
+            let (from, to) = (edge.source(), edge.target());
+            let length = edge.weight().to_string();
+            match self.graph.find_edge(to, from) {
+                Some(_) if from > to => {}
+                Some(_) => {
+                    let attributes = [("label", length.as_str()), ("dir", "none")];
+                    dot.edge(
+                        &from.index().to_string(),
+                        &to.index().to_string(),
+                        &attributes,
+                    );
+                }
+                None => {
+                    let attributes = [("label", length.as_str())];
+                    dot.edge(
+                        &from.index().to_string(),
+                        &to.index().to_string(),
+                        &attributes,
+                    );
+                }
+            }
+        }
+        dot
+    }
+}
+
+fn search(
+    neighbors: &[Vec<(usize, usize)>],
+    node: usize,
+    end: usize,
+    last_leg: Option<(usize, usize)>,
+    visited: u64,
+    length: usize,
+    longest: &mut Option<usize>,
+) {
+    if node == end {
+        *longest = Some(longest.map_or(length, |l| l.max(length)));
+        return;
+    }
+    if let Some((before_end, leg)) = last_leg {
+        if node == before_end {
+            search(neighbors, end, end, None, visited, length + leg, longest);
+            return;
+        }
+    }
+    for &(next, steps) in &neighbors[node] {
+        if visited & (1 << next) == 0 {
+            let visited = visited | (1 << next);
+            search(
+                neighbors,
+                next,
+                end,
+                last_leg,
+                visited,
+                length + steps,
+                longest,
+            );
+        }
     }
 }
 
-fn part_1(input_file: &str) -> std::io::Result<usize> {
+fn longest_hike(input_file: &str, slippery: bool) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
-    Ok(0)
+    match Trails::new(&input, slippery).longest_hike()? {
+        Some(length) => Ok(length),
+        None => error("There's no way from the start to the end"),
+    }
 }
 
-fn part_2(input_file: &str) -> std::io::Result<usize> {
+/// The compressed trails, with slopes or without.
+pub fn trails(input_file: &str, slippery: bool) -> std::io::Result<Trails> {
     let input: Input = parse(input_file)?;
-    Ok(0)
+    Ok(Trails::new(&input, slippery))
+}
+
+/// The junction graph of the dry trails as GraphViz.
+pub fn to_dot(input_file: &str) -> std::io::Result<Dot> {
+    Ok(trails(input_file, false)?.to_dot())
+}
+
+pub fn part_1(input_file: &str) -> std::io::Result<usize> {
+    longest_hike(input_file, true)
 }
 
+pub fn part_2(input_file: &str) -> std::io::Result<usize> {
+    longest_hike(input_file, false)
+}
+
+solution!(23);
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{part_1, part_2, to_dot, trails, Input, Trails};
+    use crate::utils::parser::{parse, Source};
 
     const INPUT: &str = "input/roar/q23_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q23_sample.txt";
@@ -34,24 +289,45 @@ mod tests {
     #[test]
     fn roar_q23_p1_sample() {
         let result = part_1(INPUT_SAMPLE);
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap(), 94);
     }
 
     #[test]
     fn roar_q23_p1_main() {
         let result = part_1(INPUT);
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap(), 2106);
     }
 
     #[test]
     fn roar_q23_p2_sample() {
         let result = part_2(INPUT_SAMPLE);
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap(), 154);
     }
 
     #[test]
     fn roar_q23_p2_main() {
         let result = part_2(INPUT);
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap(), 6350);
+    }
+
+    #[test]
+    fn roar_q23_trails() {
+        // The start, the end and seven junctions in between
+        let slippery = trails(INPUT_SAMPLE, true).unwrap();
+        let dry = trails(INPUT_SAMPLE, false).unwrap();
+        assert_eq!(slippery.graph.node_count(), 9);
+        assert_eq!(dry.graph.node_count(), 9);
+        assert_eq!(slippery.graph[slippery.start], (0, 1));
+        assert_eq!(slippery.graph[slippery.end], (22, 21));
+        // Without slopes every corridor goes both ways
+        assert_eq!(dry.graph.edge_count(), 2 * slippery.graph.edge_count());
+
+        let dot = to_dot(INPUT_SAMPLE).unwrap();
+        assert_eq!(dot.edge_count(), slippery.graph.edge_count());
+
+        // A slope pointing the wrong way blocks the only path
+        let input: Input = parse(Source::text("#.#\n#^#\n#.#\n")).unwrap();
+        assert_eq!(Trails::new(&input, true).longest_hike().unwrap(), None);
+        assert_eq!(Trails::new(&input, false).longest_hike().unwrap(), Some(2));
     }
 }
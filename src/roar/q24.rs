@@ -1,16 +1,76 @@
 #![allow(dead_code, unused_variables)]
 
-use crate::utils::parser::{parse, FileLines};
+use crate::solution::{Answer, Solution};
+use crate::utils::parser::{error, parse, FileLines};
+use num::rational::Ratio;
+use num::{BigInt, BigRational, Signed, Zero};
+
+/// Exact fractions, so crossings far out at 10^14 don't lose precision.
+type Rational = Ratio<i128>;
+
+fn rational(n: i128) -> Rational {
+    Rational::from_integer(n)
+}
 
 #[derive(PartialEq, Debug)]
 struct Hailstone {
     px: i128,
     py: i128,
+    pz: i128,
     vx: i128,
     vy: i128,
+    vz: i128,
+}
+
+/// Where two hailstones' paths cross in x and y, and the time each of them
+/// gets there. A negative time means the crossing was in the past.
+#[derive(PartialEq, Debug)]
+struct Crossing {
+    x: Rational,
+    y: Rational,
+    t_self: Rational,
+    t_other: Rational,
+}
+
+impl Crossing {
+    fn in_past(&self) -> bool {
+        self.t_self.is_negative() || self.t_other.is_negative()
+    }
+
+    fn within_area(&self, min_x: i128, max_x: i128, min_y: i128, max_y: i128) -> bool {
+        (rational(min_x)..=rational(max_x)).contains(&self.x)
+            && (rational(min_y)..=rational(max_y)).contains(&self.y)
+    }
 }
 
 impl Hailstone {
+    fn position(&self) -> [i128; 3] {
+        [self.px, self.py, self.pz]
+    }
+
+    fn velocity(&self) -> [i128; 3] {
+        [self.vx, self.vy, self.vz]
+    }
+
+    /// Where the paths cross, ignoring z, or `None` if they're parallel.
+    fn crossing_xy(&self, other: &Hailstone) -> Option<Crossing> {
+        // self.p + self.v * t = other.p + other.v * s, solved by Cramer's rule
+        let cross = |ax: i128, ay: i128, bx: i128, by: i128| ax * by - ay * bx;
+        let determinant = cross(self.vx, self.vy, other.vx, other.vy);
+        if determinant == 0 {
+            return None;
+        }
+        let (dx, dy) = (other.px - self.px, other.py - self.py);
+        let t_self = Rational::new(cross(dx, dy, other.vx, other.vy), determinant);
+        let t_other = Rational::new(cross(dx, dy, self.vx, self.vy), determinant);
+        Some(Crossing {
+            x: rational(self.px) + rational(self.vx) * t_self,
+            y: rational(self.py) + rational(self.vy) * t_self,
+            t_self,
+            t_other,
+        })
+    }
+
     fn will_intersect_within_area(
         &self,
         other: &Hailstone,
@@ -19,23 +79,120 @@ impl Hailstone {
         min_y: i128,
         max_y: i128,
     ) -> bool {
-        // Check if the paths are parallel
-        if self.vx * other.vy == self.vy * other.vx {
-            return false;
+        match self.crossing_xy(other) {
+            Some(crossing) => {
+                !crossing.in_past() && crossing.within_area(min_x, max_x, min_y, max_y)
+            }
+            None => false,
         }
+    }
 
-        // https://brilliant.org/wiki/linear-equations-intersection-of-lines/
-        let t = (other.py - self.py) as f64 / self.vy as f64
-            - (other.px - self.px) as f64 / self.vx as f64;
-        let intersect_x = self.px as f64 + self.vx as f64 * t;
-        let intersect_y = self.py as f64 + self.vy as f64 * t;
+    /// Whether the two are in the same place at the same time, now or later.
+    fn collides_with(&self, other: &Hailstone) -> bool {
+        let (p, q) = (self.position(), other.position());
+        let (v, w) = (self.velocity(), other.velocity());
+        // p + v * t = q + w * t in every axis, for one shared t >= 0
+        let mut time = None;
+        for axis in 0..3 {
+            let (gap, closing) = (q[axis] - p[axis], v[axis] - w[axis]);
+            if closing == 0 {
+                if gap != 0 {
+                    return false;
+                }
+                continue;
+            }
+            if gap % closing != 0 || gap / closing < 0 {
+                return false;
+            }
+            if *time.get_or_insert(gap / closing) != gap / closing {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Solves `matrix * x = rhs` by Gaussian elimination, or `None` if there's no
+/// single solution. Big fractions, as the products get far too big for i128.
+fn solve(mut matrix: Vec<Vec<BigRational>>, mut rhs: Vec<BigRational>) -> Option<Vec<BigRational>> {
+    let n = rhs.len();
+    for column in 0..n {
+        let pivot = (column..n).find(|&row| !matrix[row][column].is_zero())?;
+        matrix.swap(column, pivot);
+        rhs.swap(column, pivot);
+        for row in 0..n {
+            if row == column || matrix[row][column].is_zero() {
+                continue;
+            }
+            let factor = &matrix[row][column] / &matrix[column][column];
+            let pivot_row = matrix[column].clone();
+            for (value, pivot) in matrix[row].iter_mut().zip(&pivot_row).skip(column) {
+                *value -= &factor * pivot;
+            }
+            let delta = &factor * &rhs[column];
+            rhs[row] -= delta;
+        }
+    }
+    Some((0..n).map(|i| &rhs[i] / &matrix[i][i]).collect())
+}
 
-        // Check range
-        intersect_x >= min_x as f64
-            && intersect_x <= max_x as f64
-            && intersect_y >= min_y as f64
-            && intersect_y <= max_y as f64
+/// The rock that hits every hailstone, worked out from three of them.
+fn find_rock(stones: &[Hailstone]) -> Option<Hailstone> {
+    // For the rock P, V and any stone p, v: (P - p) x (V - v) = 0. The P x V
+    // term is the same for every stone, so subtracting one stone's equation
+    // from another's leaves three linear equations in P and V:
+    //   P x (vj - vi) + (pj - pi) x V = pj x vj - pi x vi
+    let cross = |a: [i128; 3], b: [i128; 3]| {
+        [
+            a[1] * b[2] - a[2] * b[1],
+            a[2] * b[0] - a[0] * b[2],
+            a[0] * b[1] - a[1] * b[0],
+        ]
+    };
+    let minus = |a: [i128; 3], b: [i128; 3]| [a[0] - b[0], a[1] - b[1], a[2] - b[2]];
+    // Keep trying triples in case some have parallel velocities
+    for window in stones.windows(3) {
+        let [a, b, c] = window else { unreachable!() };
+        let mut matrix = Vec::new();
+        let mut rhs = Vec::new();
+        for other in [b, c] {
+            let w = minus(other.velocity(), a.velocity());
+            let u = minus(other.position(), a.position());
+            let target = minus(
+                cross(other.position(), other.velocity()),
+                cross(a.position(), a.velocity()),
+            );
+            // P x w and u x V, written out as coefficients of P and V
+            let rows = [
+                [0, w[2], -w[1], 0, -u[2], u[1]],
+                [-w[2], 0, w[0], u[2], 0, -u[0]],
+                [w[1], -w[0], 0, -u[1], u[0], 0],
+            ];
+            let big = |n: i128| BigRational::from_integer(BigInt::from(n));
+            for (row, value) in rows.iter().zip(target) {
+                matrix.push(row.iter().map(|&n| big(n)).collect());
+                rhs.push(big(value));
+            }
+        }
+        let Some(solution) = solve(matrix, rhs) else {
+            continue;
+        };
+        let integers: Option<Vec<i128>> = solution
+            .iter()
+            .map(|r| r.is_integer().then(|| r.to_integer().try_into().ok())?)
+            .collect();
+        if let Some([px, py, pz, vx, vy, vz]) = integers.as_deref() {
+            return Some(Hailstone {
+                px: *px,
+                py: *py,
+                pz: *pz,
+                vx: *vx,
+                vy: *vy,
+                vz: *vz,
+            });
+        }
     }
+    None
 }
 
 struct Input {
@@ -45,35 +202,36 @@ struct Input {
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
+    fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
         let mut points = Vec::new();
-        for line in lines {
+        while let Some(line) = lines.next() {
             let parts: Vec<i128> = line
                 .split(&[',', '@'][..])
-                .map(|s| s.trim().parse::<i128>().unwrap())
-                .collect();
+                .map(|s| lines.parse_at::<i128>(&line, s.trim(), "a number"))
+                .collect::<Result<_, _>>()?;
+            let [px, py, pz, vx, vy, vz] = parts[..] else {
+                return Err(lines.error_at(&line, &line, "a position and velocity"));
+            };
             points.push(Hailstone {
-                px: parts[0],
-                py: parts[1],
-                vx: parts[2],
-                vy: parts[3],
+                px,
+                py,
+                pz,
+                vx,
+                vy,
+                vz,
             });
         }
         Ok(Input { hailstone: points })
     }
 }
 
-fn part_1(input_file: &str, min: i128, max: i128) -> std::io::Result<i128> {
+pub fn part_1(input_file: &str, min: i128, max: i128) -> std::io::Result<i128> {
     let input: Input = parse(input_file)?;
     let mut total_intersections = 0;
 
-    for stone in input.hailstone.iter() {
-        for other_stone in input.hailstone.iter() {
-            if stone == other_stone {
-                continue;
-            }
+    for (i, stone) in input.hailstone.iter().enumerate() {
+        for other_stone in &input.hailstone[i + 1..] {
             if stone.will_intersect_within_area(other_stone, min, max, min, max) {
-                println!("{} {:?}", stone.px, stone);
                 total_intersections += 1;
             }
         }
@@ -81,14 +239,50 @@ fn part_1(input_file: &str, min: i128, max: i128) -> std::io::Result<i128> {
     Ok(total_intersections)
 }
 
-fn part_2(input_file: &str) -> std::io::Result<i128> {
+pub fn part_2(input_file: &str) -> std::io::Result<i128> {
     let input: Input = parse(input_file)?;
-    Ok(0)
+    let Some(rock) = find_rock(&input.hailstone) else {
+        return error("No rock can hit every hailstone");
+    };
+    Ok(rock.px + rock.py + rock.pz)
+}
+
+/// Bounds of the square test area, applied to both x and y.
+pub struct Params {
+    pub min: i128,
+    pub max: i128,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params {
+            min: 200000000000000,
+            max: 400000000000000,
+        }
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Params = Params;
+
+    const DAY: u8 = 24;
+
+    fn part_1(input_file: &str, params: &Params) -> std::io::Result<Answer> {
+        part_1(input_file, params.min, params.max).map(Answer::from)
+    }
+
+    fn part_2(input_file: &str, _: &Params) -> std::io::Result<Answer> {
+        part_2(input_file).map(Answer::from)
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2, Hailstone};
+    use super::{find_rock, part_1, part_2, Hailstone, Input};
+    use crate::utils::parser::parse;
+    use num::Signed;
 
     const INPUT: &str = "input/roar/q24_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q24_sample.txt";
@@ -99,38 +293,50 @@ mod tests {
         assert_eq!(result.unwrap(), 2);
     }
 
-    #[ignore = "aaaargh!!!"]
     #[test]
     fn roar_q24_p1_main() {
         let result = part_1(INPUT, 200000000000000, 400000000000000);
-        // -1 is not the answer - need to figure the answer out
-        assert_eq!(result.unwrap(), -1);
+        assert_eq!(result.unwrap(), 25433);
     }
 
     #[test]
     fn roar_q24_p2_sample() {
         let result = part_2(INPUT_SAMPLE);
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap(), 47);
     }
 
     #[test]
     fn roar_q24_p2_main() {
         let result = part_2(INPUT);
-        assert_eq!(result.unwrap(), 0);
+        assert_eq!(result.unwrap(), 885093461440405);
     }
+
+    #[test]
+    fn roar_q24_rock_hits_every_stone() {
+        for file in [INPUT_SAMPLE, INPUT] {
+            let input: Input = parse(file).unwrap();
+            let rock = find_rock(&input.hailstone).unwrap();
+            assert!(input.hailstone.iter().all(|h| rock.collides_with(h)));
+        }
+    }
+
     #[test]
     fn test_hailstones_intersect_within_area_1() {
         let hailstone_a = Hailstone {
             px: 19,
             py: 13,
+            pz: 30,
             vx: -2,
             vy: 1,
+            vz: -2,
         };
         let hailstone_b = Hailstone {
             px: 18,
             py: 19,
+            pz: 22,
             vx: -1,
             vy: -1,
+            vz: -2,
         };
 
         let min_x = 7;
@@ -146,14 +352,18 @@ mod tests {
         let hailstone_a = Hailstone {
             px: 19,
             py: 13,
+            pz: 30,
             vx: -2,
             vy: 1,
+            vz: -2,
         };
         let hailstone_b = Hailstone {
             px: 12,
             py: 31,
+            pz: 28,
             vx: -1,
             vy: -2,
+            vz: -1,
         };
 
         let min_x = 7;
@@ -169,14 +379,18 @@ mod tests {
         let hailstone_a = Hailstone {
             px: 19,
             py: 13,
+            pz: 30,
             vx: -2,
             vy: 1,
+            vz: -2,
         };
         let hailstone_b = Hailstone {
             px: 20,
             py: 19,
+            pz: 15,
             vx: 1,
             vy: -5,
+            vz: -3,
         };
 
         let min_x = 7;
@@ -185,5 +399,9 @@ mod tests {
         let max_y = 27;
 
         assert!(!hailstone_a.will_intersect_within_area(&hailstone_b, min_x, max_x, min_y, max_y));
+        // They do cross, but only in hailstone A's past
+        let crossing = hailstone_a.crossing_xy(&hailstone_b).unwrap();
+        assert!(crossing.in_past());
+        assert!(crossing.t_self.is_negative() && !crossing.t_other.is_negative());
     }
 }
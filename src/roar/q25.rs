@@ -1,7 +1,9 @@
 #![allow(dead_code, unused_variables)]
-use crate::utils::parser::{parse, FileLines};
-use std::collections::VecDeque;
-use std::collections::{HashMap, HashSet};
+use crate::solution::{Answer, Solution};
+use crate::utils::dot::Dot;
+use crate::utils::parser::{error, parse, FileLines};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet};
 
 struct Input {
     graph: HashMap<String, HashSet<String>>,
@@ -10,13 +12,13 @@ struct Input {
 impl TryFrom<FileLines> for Input {
     type Error = std::io::Error;
 
-    fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
+    fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
         let mut graph: HashMap<String, HashSet<String>> = HashMap::new();
 
-        for line in lines {
-            let parts: Vec<_> = line.split(": ").map(String::from).collect();
-            let node = parts[0].clone();
-            let neighbors = parts[1]
+        while let Some(line) = lines.next() {
+            let (node, neighbors) = lines.split_once(&line, &line, ": ")?;
+            let node = String::from(node);
+            let neighbors = neighbors
                 .split_whitespace()
                 .map(String::from)
                 .collect::<HashSet<_>>();
@@ -37,94 +39,203 @@ impl TryFrom<FileLines> for Input {
     }
 }
 
-fn find_bridge(graph: &HashMap<String, HashSet<String>>) -> (String, String) {
-    let mut paths: HashMap<(String, String), usize> = HashMap::new();
-
-    for start in graph.keys() {
-        let mut to_see = VecDeque::new();
-        to_see.push_back(start.clone());
-        let mut seen = HashSet::new();
-        seen.insert(start.clone());
-
-        while let Some(node) = to_see.pop_front() {
-            for n in &graph[&node] {
-                if !seen.contains(n) {
-                    to_see.push_back(n.clone());
-                    seen.insert(n.clone());
-                    let edge = if n < &node {
-                        (n.clone(), node.clone())
-                    } else {
-                        (node.clone(), n.clone())
-                    };
-                    *paths.entry(edge).or_default() += 1;
-                }
-            }
-        }
+/// A minimum cut: the wires to cut and the two groups that leaves, each
+/// sorted so the same graph always gives the same answer.
+#[derive(Debug, PartialEq)]
+struct MinCut {
+    edges: Vec<(String, String)>,
+    partitions: (Vec<String>, Vec<String>),
+}
+
+/// Stoer-Wagner's global minimum cut. Each phase adds the node most tightly
+/// connected to those already added, then merges the last two; the last
+/// node's connections are a cut, and the smallest of those is the minimum.
+/// A graph that's already in pieces needs no wires cut: one piece is split
+/// off from the rest.
+fn min_cut(graph: &HashMap<String, HashSet<String>>) -> Option<MinCut> {
+    let mut names: Vec<&String> = graph.keys().collect();
+    names.sort();
+    if names.len() < 2 {
+        return None;
     }
+    let index: HashMap<&String, usize> = names.iter().enumerate().map(|(i, &n)| (n, i)).collect();
 
-    paths.into_iter().max_by_key(|&(_, v)| v).unwrap().0
-}
+    // weights between merged groups of nodes, keyed by their lowest index
+    let mut weights: Vec<HashMap<usize, usize>> = names
+        .iter()
+        .map(|n| graph[*n].iter().map(|m| (index[m], 1)).collect())
+        .collect();
+    let mut members: Vec<Vec<usize>> = (0..names.len()).map(|i| vec![i]).collect();
+    let mut active: Vec<usize> = (0..names.len()).collect();
+    let mut best: Option<(usize, Vec<usize>)> = None;
 
-fn bfs_reach(graph: &HashMap<String, HashSet<String>>, start: &str) -> usize {
-    let mut visited = HashSet::new();
-    let mut queue = VecDeque::new();
-    queue.push_back(start.to_string());
+    while active.len() > 1 {
+        let mut added = vec![false; names.len()];
+        let mut connection = vec![0; names.len()];
+        let mut queue = BinaryHeap::from([(0, Reverse(active[0]))]);
+        let mut order = Vec::new();
+        while let Some((weight, Reverse(node))) = queue.pop() {
+            if added[node] || weight != connection[node] {
+                continue;
+            }
+            added[node] = true;
+            order.push(node);
+            for (&next, &w) in &weights[node] {
+                if !added[next] {
+                    connection[next] += w;
+                    queue.push((connection[next], Reverse(next)));
+                }
+            }
+        }
+        if order.len() < active.len() {
+            // the first node's piece never reached the rest
+            let side = order.iter().flat_map(|&n| members[n].clone()).collect();
+            best = Some((0, side));
+            break;
+        }
+        let (s, t) = (order[order.len() - 2], order[order.len() - 1]);
 
-    while let Some(node) = queue.pop_front() {
-        if !visited.insert(node.clone()) {
-            continue;
+        if best.as_ref().is_none_or(|(cut, _)| connection[t] < *cut) {
+            best = Some((connection[t], members[t].clone()));
         }
 
-        if let Some(neighbors) = graph.get(&node) {
-            for neighbor in neighbors {
-                if !visited.contains(neighbor) {
-                    queue.push_back(neighbor.clone());
-                }
+        // merge t into s
+        let t_weights = std::mem::take(&mut weights[t]);
+        for (next, w) in t_weights {
+            weights[next].remove(&t);
+            if next != s {
+                *weights[s].entry(next).or_default() += w;
+                *weights[next].entry(s).or_default() += w;
             }
         }
+        let t_members = std::mem::take(&mut members[t]);
+        members[s].extend(t_members);
+        active.retain(|&n| n != t);
     }
 
-    visited.len()
+    let (_, side) = best?;
+    let mut in_side = vec![false; names.len()];
+    for &n in &side {
+        in_side[n] = true;
+    }
+    let mut edges = Vec::new();
+    for &n in &side {
+        for m in &graph[names[n]] {
+            if !in_side[index[m]] {
+                edges.push(if names[n] < m {
+                    (names[n].clone(), m.clone())
+                } else {
+                    (m.clone(), names[n].clone())
+                });
+            }
+        }
+    }
+    edges.sort();
+    let (a, b): (Vec<usize>, Vec<usize>) = (0..names.len()).partition(|&n| in_side[n]);
+    let group = |g: Vec<usize>| g.into_iter().map(|n| names[n].clone()).collect();
+    Some(MinCut {
+        edges,
+        partitions: (group(a), group(b)),
+    })
 }
 
-fn part_1(input_file: &str) -> std::io::Result<usize> {
-    let mut input: Input = parse(input_file)?;
-
-    for _ in 0..3 {
-        let bridge = find_bridge(&input.graph);
-        input.graph.get_mut(&bridge.0).unwrap().remove(&bridge.1);
-        input.graph.get_mut(&bridge.1).unwrap().remove(&bridge.0);
+pub fn part_1(input_file: &str, k: usize) -> std::io::Result<usize> {
+    let input: Input = parse(input_file)?;
+    let Some(cut) = min_cut(&input.graph) else {
+        return error("There aren't two connected groups to split");
+    };
+    if cut.edges.len() != k {
+        return error(&format!(
+            "The fewest wires to cut is {}, not {}",
+            cut.edges.len(),
+            k
+        ));
     }
+    Ok(cut.partitions.0.len() * cut.partitions.1.len())
+}
 
-    let start = input.graph.keys().next().unwrap().clone();
-    let group_size = bfs_reach(&input.graph, &start);
-    Ok(group_size * (input.graph.len() - group_size))
+/// The wiring as a GraphViz graph with the two groups filled in different
+/// colours and the wires to cut drawn thick and red.
+pub fn to_dot(input_file: &str) -> std::io::Result<Dot> {
+    let input: Input = parse(input_file)?;
+    let cut = min_cut(&input.graph);
+    let mut dot = Dot::graph("wiring");
+    dot.node_defaults(&[("style", "filled")]);
+    if let Some(cut) = &cut {
+        for name in &cut.partitions.0 {
+            dot.node(name, &[("fillcolor", "lightblue")]);
+        }
+        for name in &cut.partitions.1 {
+            dot.node(name, &[("fillcolor", "lightpink")]);
+        }
+    }
+    let mut wires: Vec<(&String, &String)> = input
+        .graph
+        .iter()
+        .flat_map(|(a, bs)| bs.iter().filter(move |b| a < *b).map(move |b| (a, b)))
+        .collect();
+    wires.sort();
+    for (a, b) in wires {
+        let is_cut = cut
+            .as_ref()
+            .is_some_and(|cut| cut.edges.contains(&(a.clone(), b.clone())));
+        match is_cut {
+            true => dot.edge(a, b, &[("color", "red"), ("penwidth", "3")]),
+            false => dot.edge(a, b, &[]),
+        };
+    }
+    Ok(dot)
 }
 
-fn part_2(input_file: &str) -> std::io::Result<usize> {
+pub fn part_2(input_file: &str) -> std::io::Result<usize> {
     let input: Input = parse(input_file)?;
     Ok(0)
 }
 
+/// How many wires have to be cut to split the machine in two.
+pub struct Params {
+    pub k: usize,
+}
+
+impl Default for Params {
+    fn default() -> Self {
+        Params { k: 3 }
+    }
+}
+
+pub struct Solver;
+
+impl Solution for Solver {
+    type Params = Params;
+
+    const DAY: u8 = 25;
+
+    fn part_1(input_file: &str, params: &Params) -> std::io::Result<Answer> {
+        part_1(input_file, params.k).map(Answer::from)
+    }
+
+    fn part_2(input_file: &str, _: &Params) -> std::io::Result<Answer> {
+        part_2(input_file).map(Answer::from)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{part_1, part_2};
+    use super::{min_cut, part_1, part_2, to_dot, Input};
+    use crate::utils::parser::{parse, Source};
 
     const INPUT: &str = "input/roar/q25_input.txt";
     const INPUT_SAMPLE: &str = "input/roar/q25_sample.txt";
 
-    #[ignore]
     #[test]
     fn roar_q25_p1_sample() {
-        let result = part_1(INPUT_SAMPLE);
+        let result = part_1(INPUT_SAMPLE, 3);
         assert_eq!(result.unwrap(), 54);
     }
 
-    #[ignore]
     #[test]
     fn roar_q25_p1_main() {
-        let result = part_1(INPUT);
-        // TODO: Find the actual value
+        let result = part_1(INPUT, 3);
         assert_eq!(result.unwrap(), 583632);
     }
 
@@ -139,4 +250,50 @@ mod tests {
         let result = part_2(INPUT);
         assert_eq!(result.unwrap(), 0);
     }
+
+    #[test]
+    fn roar_q25_min_cut() {
+        let input: Input = parse(INPUT_SAMPLE).unwrap();
+        let cut = min_cut(&input.graph).unwrap();
+        let edge = |a: &str, b: &str| (a.to_string(), b.to_string());
+        assert_eq!(
+            cut.edges,
+            [edge("bvb", "cmg"), edge("hfx", "pzl"), edge("jqt", "nvd")]
+        );
+        let mut sizes = [cut.partitions.0.len(), cut.partitions.1.len()];
+        sizes.sort();
+        assert_eq!(sizes, [6, 9]);
+        assert_eq!(min_cut(&input.graph), Some(cut));
+
+        // No two wires split the sample, and four is more than needed
+        assert!(part_1(INPUT_SAMPLE, 2).is_err());
+        assert!(part_1(INPUT_SAMPLE, 4).is_err());
+
+        // Two triangles with no wires between them are already apart
+        let input: Input = parse(Source::text("a: b c\nb: c\nx: y z\ny: z\n")).unwrap();
+        let cut = min_cut(&input.graph).unwrap();
+        assert_eq!(cut.edges, []);
+        let group = |names: &[&str]| names.iter().map(|n| n.to_string()).collect::<Vec<_>>();
+        let mut partitions = [cut.partitions.0, cut.partitions.1];
+        partitions.sort();
+        assert_eq!(
+            partitions,
+            [group(&["a", "b", "c"]), group(&["x", "y", "z"])]
+        );
+    }
+
+    #[test]
+    fn roar_q25_to_dot() {
+        let dot = to_dot(INPUT_SAMPLE).unwrap();
+        assert_eq!((dot.node_count(), dot.edge_count()), (15, 33));
+        let dot = dot.to_string();
+        assert_eq!(dot.matches("color=\"red\"").count(), 3);
+        assert!(dot.contains("\"hfx\" -- \"pzl\" [color=\"red\", penwidth=\"3\"];"));
+        let mut sizes = [
+            dot.matches("lightblue").count(),
+            dot.matches("lightpink").count(),
+        ];
+        sizes.sort();
+        assert_eq!(sizes, [6, 9]);
+    }
 }
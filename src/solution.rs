@@ -0,0 +1,75 @@
+use std::fmt;
+use std::io;
+
+/// A puzzle answer. Days return whichever integer type suits them, so this is
+/// wide enough to hold all of them and compare answers across authors.
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Answer(pub i128);
+
+macro_rules! answer_from {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for Answer {
+                fn from(n: $t) -> Self {
+                    Answer(n as i128)
+                }
+            }
+        )*
+    };
+}
+
+answer_from!(i32, i64, i128, u32, u64, usize);
+
+impl fmt::Display for Answer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+pub trait Solution {
+    /// Extra inputs a day needs beyond the input file, e.g. a step count.
+    /// The default is whatever the real puzzle input expects.
+    type Params: Default;
+
+    const DAY: u8;
+
+    fn part_1(input_file: &str, params: &Self::Params) -> io::Result<Answer>;
+
+    fn part_2(input_file: &str, params: &Self::Params) -> io::Result<Answer>;
+}
+
+/// Implements `Solution` as `Solver` for a day whose parts only take the
+/// input file, delegating to the module's `part_1`/`part_2`.
+macro_rules! solution {
+    ($day:literal) => {
+        pub struct Solver;
+
+        impl $crate::solution::Solution for Solver {
+            type Params = ();
+
+            const DAY: u8 = $day;
+
+            fn part_1(input_file: &str, _: &()) -> std::io::Result<$crate::solution::Answer> {
+                part_1(input_file).map($crate::solution::Answer::from)
+            }
+
+            fn part_2(input_file: &str, _: &()) -> std::io::Result<$crate::solution::Answer> {
+                part_2(input_file).map($crate::solution::Answer::from)
+            }
+        }
+    };
+}
+
+pub(crate) use solution;
+
+#[cfg(test)]
+mod tests {
+    use super::Answer;
+
+    #[test]
+    fn answer_from_any_integer() {
+        assert_eq!(Answer::from(42u32), Answer::from(42usize));
+        assert_eq!(Answer::from(-3i32), Answer(-3));
+        assert_eq!(Answer::from(u64::MAX).to_string(), "18446744073709551615");
+    }
+}
@@ -0,0 +1,123 @@
+#![allow(dead_code)]
+
+use crate::utils::geom::Direction;
+use crate::utils::grid::Position;
+
+/// How a crucible may move across the city in q17.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CrucibleRules {
+    /// Blocks it must move in a straight line before it can turn or stop.
+    pub min_straight: usize,
+    /// Blocks it can move in a straight line before it has to turn.
+    pub max_straight: usize,
+    /// Whether it can turn straight back the way it came.
+    pub allow_reverse: bool,
+    /// The directions it can set off in.
+    pub start_directions: Vec<Direction>,
+    pub start: Position,
+    /// `None` for the bottom-right block.
+    pub target: Option<Position>,
+}
+
+impl CrucibleRules {
+    /// The part 1 crucible: at most three blocks in a line.
+    pub fn crucible() -> Self {
+        CrucibleRules {
+            min_straight: 1,
+            max_straight: 3,
+            allow_reverse: false,
+            start_directions: vec![Direction::Right, Direction::Down],
+            start: (0, 0),
+            target: None,
+        }
+    }
+
+    /// The part 2 ultra crucible: between four and ten blocks in a line.
+    pub fn ultra_crucible() -> Self {
+        CrucibleRules {
+            min_straight: 4,
+            max_straight: 10,
+            ..CrucibleRules::crucible()
+        }
+    }
+
+    /// The target on a map of `height` rows and `width` columns.
+    pub fn target_in(&self, height: usize, width: usize) -> Position {
+        self.target.unwrap_or((height - 1, width - 1))
+    }
+
+    /// The moves allowed after `straight` blocks heading in `direction`
+    /// (`None` before the first move), each with its new straight count.
+    pub fn moves(&self, direction: Option<Direction>, straight: usize) -> Vec<(Direction, usize)> {
+        let Some(current) = direction else {
+            return self.start_directions.iter().map(|&d| (d, 1)).collect();
+        };
+        Direction::ALL
+            .into_iter()
+            .filter(|&d| {
+                if d == current {
+                    straight < self.max_straight
+                } else if d == current.opposite() {
+                    self.allow_reverse && straight >= self.min_straight
+                } else {
+                    straight >= self.min_straight
+                }
+            })
+            .map(|d| (d, if d == current { straight + 1 } else { 1 }))
+            .collect()
+    }
+
+    /// Whether it can stop after `straight` blocks in a line.
+    pub fn can_stop(&self, straight: usize) -> bool {
+        straight >= self.min_straight
+    }
+}
+
+impl Default for CrucibleRules {
+    fn default() -> Self {
+        CrucibleRules::crucible()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CrucibleRules;
+    use crate::utils::geom::Direction;
+
+    #[test]
+    fn crucible_moves() {
+        let rules = CrucibleRules::crucible();
+        assert_eq!(
+            rules.moves(None, 0),
+            [(Direction::Right, 1), (Direction::Down, 1)]
+        );
+        assert_eq!(
+            rules.moves(Some(Direction::Right), 2),
+            [
+                (Direction::Up, 1),
+                (Direction::Right, 3),
+                (Direction::Down, 1)
+            ]
+        );
+        let moves = rules.moves(Some(Direction::Right), 3);
+        assert_eq!(moves, [(Direction::Up, 1), (Direction::Down, 1)]);
+        assert_eq!(rules.target_in(5, 7), (4, 6));
+    }
+
+    #[test]
+    fn crucible_ultra_and_reversing() {
+        let rules = CrucibleRules::ultra_crucible();
+        assert_eq!(
+            rules.moves(Some(Direction::Down), 3),
+            [(Direction::Down, 4)]
+        );
+        assert!(!rules.can_stop(3) && rules.can_stop(4));
+        assert_eq!(rules.moves(Some(Direction::Down), 10).len(), 2);
+
+        let rules = CrucibleRules {
+            allow_reverse: true,
+            ..CrucibleRules::crucible()
+        };
+        assert_eq!(rules.moves(Some(Direction::Left), 1).len(), 4);
+    }
+}
@@ -0,0 +1,146 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Where a sequence of states starts repeating: the state after `start`
+/// steps comes round again every `length` steps after that.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Cycle {
+    pub start: usize,
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The first step that reaches the same state as step `n`.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+}
+
+/// Finds the cycle by remembering the step each state was first seen at.
+pub fn find_cycle<S: Clone + Eq + Hash>(initial: S, step: impl FnMut(&S) -> S) -> Cycle {
+    let (cycle, _) = walk(initial, usize::MAX, step);
+    cycle.expect("states repeat before usize::MAX steps")
+}
+
+/// Brent's algorithm, which only keeps two states at a time at the cost of
+/// stepping further than `find_cycle`.
+pub fn brent<S: Clone + Eq>(initial: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // Find the length by looking ahead in ever larger powers of two
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = initial.clone();
+    let mut hare = step(&initial);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    // Then walk two states `length` apart until they meet at the start
+    let mut tortoise = initial.clone();
+    let mut hare = initial;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+/// The state after `n` steps, skipping whole cycles once one is found.
+pub fn state_after<S: Clone + Eq + Hash>(initial: S, n: usize, step: impl FnMut(&S) -> S) -> S {
+    walk(initial, n, step).1
+}
+
+/// Steps until a state repeats or `n` steps are taken, returning the cycle
+/// (if found) and the state after `n` steps.
+fn walk<S: Clone + Eq + Hash>(
+    initial: S,
+    n: usize,
+    mut step: impl FnMut(&S) -> S,
+) -> (Option<Cycle>, S) {
+    let mut states = vec![initial.clone()];
+    let mut seen = HashMap::from([(initial, 0)]);
+    loop {
+        let i = states.len() - 1;
+        if i == n {
+            return (None, states.swap_remove(i));
+        }
+        let next = step(&states[i]);
+        if let Some(&start) = seen.get(&next) {
+            let cycle = Cycle {
+                start,
+                length: i + 1 - start,
+            };
+            let state = states.swap_remove(cycle.equivalent_step(n));
+            return (Some(cycle), state);
+        }
+        seen.insert(next.clone(), i + 1);
+        states.push(next);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{brent, find_cycle, state_after, Cycle};
+
+    // (x + 1)^2 mod 100 from 3 goes 3, 16, 89, 0, 1, 4, 25, 76, 29, 0, ...
+    fn next(x: &u32) -> u32 {
+        (x + 1) * (x + 1) % 100
+    }
+
+    #[test]
+    fn cycle_detection() {
+        let expected = Cycle {
+            start: 3,
+            length: 6,
+        };
+        assert_eq!(find_cycle(3, next), expected);
+        assert_eq!(brent(3, next), expected);
+        assert_eq!(
+            brent(0, next),
+            Cycle {
+                start: 0,
+                length: 6
+            }
+        );
+        assert_eq!(
+            find_cycle(7, |_| 7),
+            Cycle {
+                start: 0,
+                length: 1
+            }
+        );
+        assert_eq!(expected.equivalent_step(2), 2);
+        assert_eq!(expected.equivalent_step(9), 3);
+        assert_eq!(expected.equivalent_step(1_000_000_000), 3 + 999_999_997 % 6);
+    }
+
+    #[test]
+    fn cycle_state_after() {
+        let slow = |n| (0..n).fold(3, |x, _| next(&x));
+        for n in [0, 1, 2, 3, 8, 9, 10, 100, 101] {
+            assert_eq!(state_after(3, n, next), slow(n));
+        }
+        assert_eq!(
+            state_after(3, 1_000_000_000_000, next),
+            slow(3 + (1_000_000_000_000 - 3) % 6)
+        );
+        // Counting up never repeats, so it just takes the steps
+        assert_eq!(state_after(0u64, 500, |x| x + 1), 500);
+    }
+}
@@ -0,0 +1,157 @@
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::fmt;
+
+/// Attributes as (name, value) pairs, e.g. `[("shape", "box")]`.
+pub type Attributes = Vec<(String, String)>;
+
+/// A GraphViz graph, built up a node and an edge at a time and rendered in
+/// DOT with `to_string()`, for pasting into `dot -Tsvg`.
+#[derive(Clone, Debug, Default)]
+pub struct Dot {
+    name: String,
+    directed: bool,
+    defaults: Attributes,
+    nodes: Vec<(String, Attributes)>,
+    ids: HashMap<String, usize>,
+    edges: Vec<(String, String, Attributes)>,
+}
+
+impl Dot {
+    /// A graph whose edges have a direction, drawn with arrows.
+    pub fn digraph(name: &str) -> Self {
+        Dot {
+            name: name.to_string(),
+            directed: true,
+            ..Dot::default()
+        }
+    }
+
+    /// A graph whose edges go both ways.
+    pub fn graph(name: &str) -> Self {
+        Dot {
+            name: name.to_string(),
+            ..Dot::default()
+        }
+    }
+
+    /// Attributes every node gets unless it sets its own.
+    pub fn node_defaults(&mut self, attributes: &[(&str, &str)]) -> &mut Self {
+        self.defaults = owned(attributes);
+        self
+    }
+
+    /// Adds a node, or adds to the attributes of one already there.
+    pub fn node(&mut self, id: &str, attributes: &[(&str, &str)]) -> &mut Self {
+        match self.ids.get(id) {
+            Some(&i) => self.nodes[i].1.extend(owned(attributes)),
+            None => {
+                self.ids.insert(id.to_string(), self.nodes.len());
+                self.nodes.push((id.to_string(), owned(attributes)));
+            }
+        }
+        self
+    }
+
+    pub fn edge(&mut self, from: &str, to: &str, attributes: &[(&str, &str)]) -> &mut Self {
+        self.edges
+            .push((from.to_string(), to.to_string(), owned(attributes)));
+        self
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.edges.len()
+    }
+}
+
+fn owned(attributes: &[(&str, &str)]) -> Attributes {
+    attributes
+        .iter()
+        .map(|&(k, v)| (k.to_string(), v.to_string()))
+        .collect()
+}
+
+/// An id or value in double quotes, so names like `%ff` or `&con` are safe.
+pub fn quote(text: &str) -> String {
+    format!("\"{}\"", text.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn attribute_list(attributes: &Attributes) -> String {
+    if attributes.is_empty() {
+        return String::new();
+    }
+    let pairs: Vec<String> = attributes
+        .iter()
+        .map(|(k, v)| format!("{}={}", k, quote(v)))
+        .collect();
+    format!(" [{}]", pairs.join(", "))
+}
+
+impl fmt::Display for Dot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (keyword, arrow) = match self.directed {
+            true => ("digraph", "->"),
+            false => ("graph", "--"),
+        };
+        writeln!(f, "{} {} {{", keyword, quote(&self.name))?;
+        if !self.defaults.is_empty() {
+            writeln!(f, "    node{};", attribute_list(&self.defaults))?;
+        }
+        for (id, attributes) in &self.nodes {
+            writeln!(f, "    {}{};", quote(id), attribute_list(attributes))?;
+        }
+        for (from, to, attributes) in &self.edges {
+            writeln!(
+                f,
+                "    {} {} {}{};",
+                quote(from),
+                arrow,
+                quote(to),
+                attribute_list(attributes)
+            )?;
+        }
+        writeln!(f, "}}")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{quote, Dot};
+
+    #[test]
+    fn dot_digraph() {
+        let mut dot = Dot::digraph("q08");
+        dot.node_defaults(&[("shape", "circle")])
+            .node("AAA", &[("color", "green")])
+            .node("ZZZ", &[])
+            .node("AAA", &[("style", "bold")])
+            .edge("AAA", "ZZZ", &[("label", "L")]);
+        assert_eq!((dot.node_count(), dot.edge_count()), (2, 1));
+        assert_eq!(
+            dot.to_string(),
+            "digraph \"q08\" {
+    node [shape=\"circle\"];
+    \"AAA\" [color=\"green\", style=\"bold\"];
+    \"ZZZ\";
+    \"AAA\" -> \"ZZZ\" [label=\"L\"];
+}
+"
+        );
+    }
+
+    #[test]
+    fn dot_graph_and_quoting() {
+        let mut dot = Dot::graph("wires");
+        dot.edge("a", "b", &[]);
+        assert_eq!(
+            dot.to_string(),
+            "graph \"wires\" {\n    \"a\" -- \"b\";\n}\n"
+        );
+        assert_eq!(quote("say \"hi\"\\"), "\"say \\\"hi\\\"\\\\\"");
+    }
+}
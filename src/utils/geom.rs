@@ -0,0 +1,167 @@
+#![allow(dead_code)]
+
+use crate::utils::grid::Position;
+use std::ops::{Add, Mul, Sub};
+
+/// A point as a row and column, the same way round as a grid `Position`.
+/// Rows grow downwards, so `Direction::Up` takes a row away.
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub struct Point<T> {
+    pub row: T,
+    pub column: T,
+}
+
+impl<T> Point<T> {
+    pub const fn new(row: T, column: T) -> Self {
+        Point { row, column }
+    }
+}
+
+impl<T: Copy + Ord + Sub<Output = T> + Add<Output = T>> Point<T> {
+    pub fn manhattan(&self, other: &Point<T>) -> T {
+        let distance = |a: T, b: T| if a > b { a - b } else { b - a };
+        distance(self.row, other.row) + distance(self.column, other.column)
+    }
+}
+
+impl<T: Copy + From<i8> + Add<Output = T> + Mul<Output = T>> Point<T> {
+    /// The point `distance` steps away in `direction`, with no bounds.
+    pub fn moved(&self, direction: Direction, distance: T) -> Self {
+        let (dr, dc) = direction.offset();
+        Point {
+            row: self.row + T::from(dr as i8) * distance,
+            column: self.column + T::from(dc as i8) * distance,
+        }
+    }
+}
+
+impl Point<usize> {
+    /// The neighbouring point in `direction`, if it is still inside a grid of
+    /// `height` rows and `width` columns.
+    pub fn step(&self, direction: Direction, height: usize, width: usize) -> Option<Self> {
+        let (dr, dc) = direction.offset();
+        let point = Point {
+            row: self.row.checked_add_signed(dr)?,
+            column: self.column.checked_add_signed(dc)?,
+        };
+        (point.row < height && point.column < width).then_some(point)
+    }
+}
+
+impl From<Position> for Point<usize> {
+    fn from((row, column): Position) -> Self {
+        Point { row, column }
+    }
+}
+
+impl From<Point<usize>> for Position {
+    fn from(point: Point<usize>) -> Self {
+        (point.row, point.column)
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+pub enum Direction {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+/// A left or right turn, e.g. gee's q08 instructions.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Turn {
+    Left,
+    Right,
+}
+
+impl Direction {
+    /// Clockwise from `Up`, the same order as the grid's 4-way neighbours.
+    pub const ALL: [Direction; 4] = [
+        Direction::Up,
+        Direction::Right,
+        Direction::Down,
+        Direction::Left,
+    ];
+
+    /// The (row, column) change of one step this way.
+    pub fn offset(&self) -> (isize, isize) {
+        match self {
+            Direction::Up => (-1, 0),
+            Direction::Right => (0, 1),
+            Direction::Down => (1, 0),
+            Direction::Left => (0, -1),
+        }
+    }
+
+    pub fn turn_left(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Left,
+            Direction::Right => Direction::Up,
+            Direction::Down => Direction::Right,
+            Direction::Left => Direction::Down,
+        }
+    }
+
+    pub fn turn_right(&self) -> Direction {
+        match self {
+            Direction::Up => Direction::Right,
+            Direction::Right => Direction::Down,
+            Direction::Down => Direction::Left,
+            Direction::Left => Direction::Up,
+        }
+    }
+
+    pub fn turn(&self, turn: Turn) -> Direction {
+        match turn {
+            Turn::Left => self.turn_left(),
+            Turn::Right => self.turn_right(),
+        }
+    }
+
+    pub fn opposite(&self) -> Direction {
+        self.turn_left().turn_left()
+    }
+
+    pub fn is_vertical(&self) -> bool {
+        matches!(self, Direction::Up | Direction::Down)
+    }
+
+    pub fn is_horizontal(&self) -> bool {
+        !self.is_vertical()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Direction, Point, Turn};
+
+    #[test]
+    fn geom_turns() {
+        for d in Direction::ALL {
+            assert_eq!(d.turn_left().turn_right(), d);
+            assert_eq!(d.opposite().opposite(), d);
+            assert_ne!(d.opposite(), d);
+            assert_eq!(d.turn(Turn::Right).turn(Turn::Right), d.opposite());
+            assert_eq!(d.is_vertical(), d.opposite().is_vertical());
+        }
+        assert_eq!(Direction::Up.turn_right(), Direction::Right);
+        assert_eq!(Direction::Left.opposite(), Direction::Right);
+        assert_eq!(Direction::Down.offset(), (1, 0));
+    }
+
+    #[test]
+    fn geom_points() {
+        let a = Point::new(2i64, -3);
+        assert_eq!(a.moved(Direction::Up, 5), Point::new(-3, -3));
+        assert_eq!(a.moved(Direction::Right, 4).manhattan(&a), 4);
+        assert_eq!(Point::new(1usize, 7).manhattan(&Point::new(4, 2)), 8);
+
+        let corner = Point::new(0usize, 0);
+        assert_eq!(corner.step(Direction::Up, 3, 3), None);
+        assert_eq!(corner.step(Direction::Down, 3, 3), Some(Point::new(1, 0)));
+        assert_eq!(Point::new(2usize, 1).step(Direction::Down, 3, 3), None);
+        assert_eq!(Point::new(1usize, 2).step(Direction::Right, 3, 3), None);
+        assert_eq!(<(usize, usize)>::from(Point::from((4, 5))), (4, 5));
+    }
+}
@@ -0,0 +1,339 @@
+#![allow(dead_code)]
+
+use crate::utils::parser::{error, FileLines};
+use std::fmt;
+use std::io;
+use std::ops::{Index, IndexMut};
+
+/// A (row, column) position in a grid.
+pub type Position = (usize, usize);
+
+const OFFSETS_4: [(isize, isize); 4] = [(-1, 0), (0, 1), (1, 0), (0, -1)];
+
+const OFFSETS_8: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A rectangular grid stored row by row in one `Vec`.
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    pub fn new(width: usize, height: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            width,
+            height,
+            cells: vec![value; width * height],
+        }
+    }
+
+    /// Builds a grid by calling `f` for every position, row by row.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(Position) -> T) -> Self {
+        Grid {
+            width,
+            height,
+            cells: (0..height)
+                .flat_map(|r| (0..width).map(move |c| (r, c)))
+                .map(&mut f)
+                .collect(),
+        }
+    }
+
+    /// Builds a grid from its rows, which must all be the same length.
+    pub fn from_rows(rows: Vec<Vec<T>>) -> io::Result<Self> {
+        let width = rows.first().map_or(0, |row| row.len());
+        if let Some(r) = rows.iter().position(|row| row.len() != width) {
+            return error(&format!(
+                "Row {} has {} cells, expected {}",
+                r,
+                rows[r].len(),
+                width
+            ));
+        }
+        Ok(Grid {
+            width,
+            height: rows.len(),
+            cells: rows.into_iter().flatten().collect(),
+        })
+    }
+
+    /// Reads one row per line, turning each character into a cell with
+    /// `cell`. Unknown characters and ragged rows are parse errors.
+    pub fn parse(
+        mut lines: FileLines,
+        expected: &str,
+        mut cell: impl FnMut(char) -> Option<T>,
+    ) -> io::Result<Self> {
+        let mut width = None;
+        let mut height = 0;
+        let mut cells = Vec::new();
+        while let Some(line) = lines.next() {
+            let mut count = 0;
+            for c in line.chars() {
+                if width == Some(count) {
+                    let expected = format!("a row of {} cells", count);
+                    return Err(lines.error_at_column(count + 1, &expected, &format!("{:?}", c)));
+                }
+                match cell(c) {
+                    Some(value) => cells.push(value),
+                    None => {
+                        let found = format!("{:?}", c);
+                        return Err(lines.error_at_column(count + 1, expected, &found));
+                    }
+                }
+                count += 1;
+            }
+            if *width.get_or_insert(count) != count {
+                let expected = format!("a row of {} cells", width.unwrap_or_default());
+                return Err(lines.error_at_column(count + 1, &expected, "end of line"));
+            }
+            height += 1;
+        }
+        Ok(Grid {
+            width: width.unwrap_or_default(),
+            height,
+            cells,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn contains(&self, (r, c): Position) -> bool {
+        r < self.height && c < self.width
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        match self.contains(position) {
+            true => Some(&self.cells[position.0 * self.width + position.1]),
+            false => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        match self.contains(position) {
+            true => Some(&mut self.cells[position.0 * self.width + position.1]),
+            false => None,
+        }
+    }
+
+    pub fn row(&self, r: usize) -> &[T] {
+        &self.cells[r * self.width..(r + 1) * self.width]
+    }
+
+    pub fn row_mut(&mut self, r: usize) -> &mut [T] {
+        &mut self.cells[r * self.width..(r + 1) * self.width]
+    }
+
+    /// Swaps the cells at two positions.
+    pub fn swap(&mut self, (r1, c1): Position, (r2, c2): Position) {
+        assert!(self.contains((r1, c1)) && self.contains((r2, c2)));
+        self.cells.swap(r1 * self.width + c1, r2 * self.width + c2);
+    }
+
+    pub fn column(&self, c: usize) -> impl DoubleEndedIterator<Item = &T> + '_ {
+        assert!(c < self.width, "Column {} out of bounds", c);
+        self.cells.iter().skip(c).step_by(self.width)
+    }
+
+    pub fn rows(&self) -> impl DoubleEndedIterator<Item = &[T]> + '_ {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl DoubleEndedIterator<Item = &T> + '_> + '_ {
+        (0..self.width).map(|c| self.column(c))
+    }
+
+    /// Every position, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let width = self.width;
+        (0..self.height).flat_map(move |r| (0..width).map(move |c| (r, c)))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> + '_ {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Position, &mut T)> + '_ {
+        self.positions().zip(self.cells.iter_mut())
+    }
+
+    /// `position` moved by `(rows, columns)`, if that is still in the grid.
+    pub fn step(&self, (r, c): Position, (dr, dc): (isize, isize)) -> Option<Position> {
+        let position = (r.checked_add_signed(dr)?, c.checked_add_signed(dc)?);
+        self.contains(position).then_some(position)
+    }
+
+    /// The positions above, right of, below and left of `position`, in that
+    /// order, skipping any outside the grid.
+    pub fn neighbors4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        OFFSETS_4
+            .iter()
+            .filter_map(move |&offset| self.step(position, offset))
+    }
+
+    /// The up to eight positions around `position`, row by row.
+    pub fn neighbors8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        OFFSETS_8
+            .iter()
+            .filter_map(move |&offset| self.step(position, offset))
+    }
+
+    /// The first position holding `value`, row by row.
+    pub fn find(&self, value: &T) -> Option<Position>
+    where
+        T: PartialEq,
+    {
+        self.find_all(value).next()
+    }
+
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Position> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, v)| *v == value)
+            .map(|(p, _)| p)
+    }
+
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self.cells.iter().map(f).collect(),
+        }
+    }
+
+    pub fn into_rows(self) -> Vec<Vec<T>> {
+        let mut rows = Vec::with_capacity(self.height);
+        let mut cells = self.cells.into_iter();
+        for _ in 0..self.height {
+            rows.push(cells.by_ref().take(self.width).collect());
+        }
+        rows
+    }
+}
+
+impl TryFrom<FileLines> for Grid<char> {
+    type Error = io::Error;
+
+    fn try_from(lines: FileLines) -> Result<Self, Self::Error> {
+        Grid::parse(lines, "a grid cell", Some)
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, (r, c): Position) -> &T {
+        assert!(self.contains((r, c)), "Position {:?} out of bounds", (r, c));
+        &self.cells[r * self.width + c]
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, (r, c): Position) -> &mut T {
+        assert!(self.contains((r, c)), "Position {:?} out of bounds", (r, c));
+        &mut self.cells[r * self.width + c]
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (r, row) in self.rows().enumerate().take(self.height) {
+            if r > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{}", cell)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Grid;
+    use crate::utils::parser::{parse, FileLines, ParseError, Source};
+
+    fn sample() -> Grid<char> {
+        parse(Source::text("#..\n.S.\n..#\n")).unwrap()
+    }
+
+    #[test]
+    fn grid_parse_and_display() {
+        let grid = sample();
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid[(1, 1)], 'S');
+        assert_eq!(grid.to_string(), "#..\n.S.\n..#");
+        let digits = Grid::parse(FileLines::from_text("12\n34"), "a digit", |c| {
+            c.to_digit(10)
+        });
+        assert_eq!(digits.unwrap().row(1), &[3, 4]);
+    }
+
+    #[test]
+    fn grid_parse_errors() {
+        let e = Grid::parse(FileLines::from_text("12\n3x"), "a digit", |c| {
+            c.to_digit(10)
+        })
+        .unwrap_err();
+        let error = ParseError::of(&e).unwrap();
+        assert_eq!((error.line, error.column), (2, 2));
+        let e = parse::<Grid<char>>(Source::text("...\n..\n")).unwrap_err();
+        assert_eq!(ParseError::of(&e).unwrap().found, "end of line");
+        let e = parse::<Grid<char>>(Source::text("..\n...\n")).unwrap_err();
+        assert_eq!(ParseError::of(&e).unwrap().column, 3);
+        assert!(Grid::from_rows(vec![vec![1, 2], vec![3]]).is_err());
+    }
+
+    #[test]
+    fn grid_neighbors() {
+        let grid = sample();
+        let corner: Vec<_> = grid.neighbors4((0, 0)).collect();
+        assert_eq!(corner, vec![(0, 1), (1, 0)]);
+        assert_eq!(grid.neighbors4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbors8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbors8((2, 2)).count(), 3);
+        assert_eq!(grid.step((0, 0), (-1, 0)), None);
+        assert_eq!(grid.step((0, 0), (2, 1)), Some((2, 1)));
+    }
+
+    #[test]
+    fn grid_views_and_find() {
+        let grid = Grid::from_rows(vec![vec![1, 2, 3], vec![4, 5, 6]]).unwrap();
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![2, 5]);
+        assert_eq!(grid.rows().count(), 2);
+        assert_eq!(grid.columns().count(), 3);
+        assert_eq!(grid.find(&5), Some((1, 1)));
+        assert_eq!(grid.find(&7), None);
+        assert_eq!(
+            sample().find_all(&'#').collect::<Vec<_>>(),
+            vec![(0, 0), (2, 2)]
+        );
+        assert_eq!(grid.get((2, 0)), None);
+        assert_eq!(grid.map(|n| n * 2).row(0), &[2, 4, 6]);
+        assert_eq!(Grid::from_fn(3, 2, |(r, c)| r * 3 + c + 1), grid);
+        assert_eq!(grid.clone().into_rows(), vec![vec![1, 2, 3], vec![4, 5, 6]]);
+    }
+}
@@ -0,0 +1,110 @@
+#![allow(dead_code)]
+
+pub fn gcd(mut a: u128, mut b: u128) -> u128 {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// `None` if the result doesn't fit in a u128.
+pub fn lcm(a: u128, b: u128) -> Option<u128> {
+    if a == 0 || b == 0 {
+        return Some(0);
+    }
+    (a / gcd(a, b)).checked_mul(b)
+}
+
+/// The gcd of every number, or 0 if there are none.
+pub fn gcd_all(numbers: impl IntoIterator<Item = u128>) -> u128 {
+    numbers.into_iter().fold(0, gcd)
+}
+
+/// The lcm of every number, 1 if there are none, or `None` on overflow.
+pub fn lcm_all(numbers: impl IntoIterator<Item = u128>) -> Option<u128> {
+    numbers.into_iter().try_fold(1, lcm)
+}
+
+/// The inverse of `a` modulo `m`, if they are coprime.
+fn mod_inverse(a: u128, m: u128) -> Option<u128> {
+    // Extended Euclid, keeping the Bezout coefficient of `a` modulo `m`
+    let (mut r0, mut r1) = (a % m, m);
+    let (mut s0, mut s1) = (1 % m, 0);
+    while r1 != 0 {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        let qs1 = mul_mod(q % m, s1, m)?;
+        (s0, s1) = (s1, (s0 + m - qs1) % m);
+    }
+    (r0 == 1).then_some(s0)
+}
+
+/// `a * b % m`, or `None` if `a * b` overflows.
+fn mul_mod(a: u128, b: u128, m: u128) -> Option<u128> {
+    Some(a.checked_mul(b)? % m)
+}
+
+/// Solves the Chinese remainder problem for events that happen at
+/// `offset + k * period` for every `k >= 0`, even when the periods share
+/// factors. Returns the first time they all happen together and how often
+/// they do after that, or `None` if they never do (or the answer would
+/// overflow).
+pub fn crt(events: impl IntoIterator<Item = (u128, u128)>) -> Option<(u128, u128)> {
+    let mut first = 0;
+    let (mut residue, mut modulus) = (0, 1);
+    for (offset, period) in events {
+        if period == 0 {
+            return None;
+        }
+        first = first.max(offset);
+        let g = gcd(modulus, period);
+        // modulus * k = offset - residue (mod period)
+        let difference = (offset % period + period - residue % period) % period;
+        if difference % g != 0 {
+            return None;
+        }
+        let reduced = period / g;
+        let inverse = mod_inverse(modulus / g % reduced, reduced)?;
+        let k = mul_mod(difference / g, inverse, reduced)?;
+        let combined = lcm(modulus, period)?;
+        residue = (residue + modulus.checked_mul(k)?) % combined;
+        modulus = combined;
+    }
+    // The earliest time no earlier than every offset
+    let behind = first.saturating_sub(residue);
+    let time = residue.checked_add(behind.div_ceil(modulus).checked_mul(modulus)?)?;
+    Some((time, modulus))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{crt, gcd, gcd_all, lcm, lcm_all};
+
+    #[test]
+    fn math_gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 5), 5);
+        assert_eq!(lcm(4, 6), Some(12));
+        assert_eq!(gcd_all([24, 36, 60]), 12);
+        assert_eq!(lcm_all([2, 3, 4, 5]), Some(60));
+        assert_eq!(lcm_all([]), Some(1));
+        assert_eq!(lcm_all([u128::MAX, u128::MAX - 1]), None);
+    }
+
+    #[test]
+    fn math_crt() {
+        // Cycles that start at 0 just meet at the lcm
+        assert_eq!(crt([(4, 4), (6, 6)]), Some((12, 12)));
+        // x = 2 (mod 3), x = 3 (mod 5), x = 2 (mod 7)
+        assert_eq!(crt([(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        // Shared factors are fine as long as the offsets agree on them
+        assert_eq!(crt([(3, 4), (5, 6)]), Some((11, 12)));
+        assert_eq!(crt([(1, 4), (2, 6)]), None);
+        // A late start pushes the answer round to a later lap
+        assert_eq!(crt([(1, 2), (9, 3)]), Some((9, 6)));
+        assert_eq!(crt([(0, 0)]), None);
+        assert_eq!(crt([]), Some((0, 1)));
+        let brute = (0..1000).find(|t| t % 8 == 5 && t % 12 == 9 && t % 15 == 9);
+        assert_eq!(crt([(5, 8), (9, 12), (9, 15)]).map(|(t, _)| t), brute);
+    }
+}
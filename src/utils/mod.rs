@@ -1,2 +1,12 @@
+pub mod crucible;
+pub mod cycle;
+pub mod dot;
+pub mod geom;
+pub mod grid;
+pub mod math;
 pub mod parser;
+pub mod polygon;
+pub mod pulse;
+pub mod ranges;
+pub mod search;
 pub mod transposer;
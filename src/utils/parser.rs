@@ -1,26 +1,147 @@
 #![allow(dead_code)]
 
+use std::fmt;
 use std::fs::File;
 use std::io;
 use std::io::prelude::*;
+use std::str::FromStr;
 
 pub fn error<T>(message: &str) -> io::Result<T> {
     Err(io::Error::new(io::ErrorKind::Other, message))
 }
 
-pub fn parse<T>(input: &str) -> io::Result<T>
+/// Where and why an input could not be parsed. Lines and columns count from 1,
+/// columns in characters. It converts into an `io::Error` of kind
+/// `InvalidData`, so parsers can keep returning `io::Result`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ParseError {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub expected: String,
+    pub found: String,
+}
+
+impl ParseError {
+    /// Recovers the `ParseError` inside an `io::Error`, if there is one.
+    pub fn of(e: &io::Error) -> Option<&ParseError> {
+        e.get_ref()?.downcast_ref()
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{}:{}:{}: expected {}, found {}",
+            self.file, self.line, self.column, self.expected, self.found
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+impl From<ParseError> for io::Error {
+    fn from(e: ParseError) -> Self {
+        io::Error::new(io::ErrorKind::InvalidData, e)
+    }
+}
+
+/// The column of `token` within `line`, when `token` is a slice of `line`.
+/// Anything else is reported at the start of the line.
+fn column_of(line: &str, token: &str) -> usize {
+    let offset = (token.as_ptr() as usize).wrapping_sub(line.as_ptr() as usize);
+    match line.get(..offset) {
+        Some(before) if offset + token.len() <= line.len() => before.chars().count() + 1,
+        _ => 1,
+    }
+}
+
+fn describe(token: &str) -> String {
+    if token.is_empty() {
+        String::from("nothing")
+    } else {
+        format!("{:?}", token)
+    }
+}
+
+/// Where puzzle input is read from. A `&str` or `String` names a file, with
+/// `-` meaning stdin, so `parse(input_file)` keeps working for every day; use
+/// `Source::Text` for input held in memory.
+pub enum Source {
+    File(String),
+    Text(String),
+    Stdin,
+    Reader(String, Box<dyn BufRead>),
+}
+
+impl Source {
+    pub fn text(text: impl Into<String>) -> Self {
+        Source::Text(text.into())
+    }
+
+    /// Any buffered reader, named `name` in parse errors.
+    pub fn reader(name: &str, reader: impl BufRead + 'static) -> Self {
+        Source::Reader(String::from(name), Box::new(reader))
+    }
+
+    fn open(self) -> io::Result<(String, Box<dyn BufRead>)> {
+        Ok(match self {
+            Source::File(filename) => {
+                let f = File::open(&filename)?;
+                (filename, Box::new(io::BufReader::new(f)))
+            }
+            Source::Text(text) => (
+                String::from("<text>"),
+                Box::new(io::Cursor::new(text.into_bytes())),
+            ),
+            Source::Stdin => (String::from("<stdin>"), Box::new(io::stdin().lock())),
+            Source::Reader(name, reader) => (name, reader),
+        })
+    }
+}
+
+impl From<&str> for Source {
+    fn from(filename: &str) -> Self {
+        match filename {
+            "-" => Source::Stdin,
+            _ => Source::File(String::from(filename)),
+        }
+    }
+}
+
+impl From<String> for Source {
+    fn from(filename: String) -> Self {
+        Source::from(filename.as_str())
+    }
+}
+
+impl From<&String> for Source {
+    fn from(filename: &String) -> Self {
+        Source::from(filename.as_str())
+    }
+}
+
+pub fn parse<T>(input: impl Into<Source>) -> io::Result<T>
 where
     T: TryFrom<FileLines, Error = io::Error>,
 {
-    T::try_from(FileLines::new(input)?)
+    T::try_from(FileLines::open(input)?)
 }
 
-pub fn parse_lines<T>(input: &str) -> io::Result<FileInput<FileLines, T>> {
-    FileInput::new(FileLines::new(input)?)
+pub fn parse_lines<T>(input: impl Into<Source>) -> io::Result<FileInput<FileLines, T>> {
+    FileInput::new(FileLines::open(input)?)
 }
 
-pub fn parse_bytes<T>(input: &str, split: &u8) -> io::Result<FileInput<FileSplit, T>> {
-    FileInput::new(FileSplit::new(input, split)?)
+pub fn parse_bytes<T>(input: impl Into<Source>, split: &u8) -> io::Result<FileInput<FileSplit, T>> {
+    FileInput::new(FileSplit::open(input, split)?)
+}
+
+pub fn parse_sections<T>(input: impl Into<Source>) -> io::Result<Vec<T>>
+where
+    T: TryFrom<FileLines, Error = io::Error>,
+{
+    FileLines::open(input)?.sections().parse_all()
 }
 
 pub fn parse_from<'a, T, U>(source: &'a mut T) -> Option<U>
@@ -49,20 +170,74 @@ impl<T, U> FileInput<T, U> {
 }
 
 pub struct FileLines {
-    lines: io::Lines<io::BufReader<File>>,
+    lines: io::Lines<Box<dyn BufRead>>,
+    file: String,
+    line: usize,
 }
 
 impl FileLines {
     pub fn new(filename: &str) -> io::Result<Self> {
-        let f = File::open(filename)?;
+        Self::open(filename)
+    }
+
+    pub fn open(source: impl Into<Source>) -> io::Result<Self> {
+        let (file, reader) = source.into().open()?;
         Ok(FileLines {
-            lines: io::BufReader::new(f).lines(),
+            lines: reader.lines(),
+            file,
+            line: 0,
         })
     }
 
+    pub fn from_text(text: impl Into<String>) -> Self {
+        Self::from_reader("<text>", io::Cursor::new(text.into().into_bytes()))
+    }
+
+    pub fn from_reader(name: &str, reader: impl BufRead + 'static) -> Self {
+        let reader: Box<dyn BufRead> = Box::new(reader);
+        FileLines {
+            lines: reader.lines(),
+            file: String::from(name),
+            line: 0,
+        }
+    }
+
+    pub fn stdin() -> Self {
+        Self::from_reader("<stdin>", io::stdin().lock())
+    }
+
+    /// Splits the remaining lines into blocks separated by blank lines.
+    pub fn sections(self) -> Sections {
+        Sections { lines: self }
+    }
+
+    /// Parses each remaining line with `f`, stopping at the first error.
+    pub fn parse_each<T>(
+        &mut self,
+        mut f: impl FnMut(&Self, &str) -> io::Result<T>,
+    ) -> io::Result<Vec<T>> {
+        let mut items = Vec::new();
+        while let Some(line) = self.next() {
+            items.push(f(self, &line)?);
+        }
+        Ok(items)
+    }
+
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    /// The number of the line last returned, or 0 before the first.
+    pub fn line_number(&self) -> usize {
+        self.line
+    }
+
     pub fn next_result(&mut self) -> io::Result<String> {
         match self.lines.next() {
-            Some(Ok(s)) => Ok(s),
+            Some(Ok(s)) => {
+                self.line += 1;
+                Ok(s)
+            }
             Some(Err(e)) => {
                 eprintln!("Error! {:}", e);
                 Err(e)
@@ -70,6 +245,112 @@ impl FileLines {
             None => Err(io::Error::from(io::ErrorKind::NotFound)),
         }
     }
+
+    /// The next line, or an error saying what was expected instead of the
+    /// end of the input.
+    pub fn expect_line(&mut self, expected: &str) -> io::Result<String> {
+        match self.next_result() {
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Err(ParseError {
+                file: self.file.clone(),
+                line: self.line + 1,
+                column: 1,
+                expected: String::from(expected),
+                found: String::from("end of input"),
+            }
+            .into()),
+            result => result,
+        }
+    }
+
+    /// An error on the current line at a given column.
+    pub fn error_at_column(&self, column: usize, expected: &str, found: &str) -> io::Error {
+        ParseError {
+            file: self.file.clone(),
+            line: self.line,
+            column,
+            expected: String::from(expected),
+            found: String::from(found),
+        }
+        .into()
+    }
+
+    /// An error at `token`, a slice of the current `line`.
+    pub fn error_at(&self, line: &str, token: &str, expected: &str) -> io::Error {
+        self.error_at_column(column_of(line, token), expected, &describe(token))
+    }
+
+    /// An error at `token` in an earlier `line`, for checks that can only be
+    /// made once the lines after it have been read.
+    pub fn error_on_line(
+        &self,
+        line_number: usize,
+        line: &str,
+        token: &str,
+        expected: &str,
+    ) -> io::Error {
+        ParseError {
+            file: self.file.clone(),
+            line: line_number,
+            column: column_of(line, token),
+            expected: String::from(expected),
+            found: describe(token),
+        }
+        .into()
+    }
+
+    /// Parses `token`, a slice of the current `line`.
+    pub fn parse_at<T: FromStr>(&self, line: &str, token: &str, expected: &str) -> io::Result<T> {
+        token
+            .parse()
+            .map_err(|_| self.error_at(line, token, expected))
+    }
+
+    /// The characters of `line`, checking each is one of `allowed`.
+    pub fn chars_of(&self, line: &str, allowed: &str, expected: &str) -> io::Result<Vec<char>> {
+        line.chars()
+            .enumerate()
+            .map(|(i, c)| match allowed.contains(c) {
+                true => Ok(c),
+                false => Err(self.error_at_column(i + 1, expected, &format!("{:?}", c))),
+            })
+            .collect()
+    }
+
+    /// `token` without `prefix`, or an error if it does not start with it.
+    pub fn strip_prefix<'a>(
+        &self,
+        line: &str,
+        token: &'a str,
+        prefix: &str,
+    ) -> io::Result<&'a str> {
+        token
+            .strip_prefix(prefix)
+            .ok_or_else(|| self.error_at(line, token, &format!("{:?}", prefix)))
+    }
+
+    /// `token` without `suffix`, or an error if it does not end with it.
+    pub fn strip_suffix<'a>(
+        &self,
+        line: &str,
+        token: &'a str,
+        suffix: &str,
+    ) -> io::Result<&'a str> {
+        token
+            .strip_suffix(suffix)
+            .ok_or_else(|| self.error_at(line, token, &format!("{:?}", suffix)))
+    }
+
+    /// `token` split around the first `delimiter`, or an error if there is none.
+    pub fn split_once<'a>(
+        &self,
+        line: &str,
+        token: &'a str,
+        delimiter: &str,
+    ) -> io::Result<(&'a str, &'a str)> {
+        token
+            .split_once(delimiter)
+            .ok_or_else(|| self.error_at(line, token, &format!("{:?}", delimiter)))
+    }
 }
 
 impl Iterator for FileLines {
@@ -77,7 +358,10 @@ impl Iterator for FileLines {
 
     fn next(&mut self) -> Option<String> {
         match self.lines.next() {
-            Some(Ok(s)) => Some(s),
+            Some(Ok(s)) => {
+                self.line += 1;
+                Some(s)
+            }
             Some(Err(e)) => {
                 eprintln!("Error! {:}", e);
                 None
@@ -87,21 +371,186 @@ impl Iterator for FileLines {
     }
 }
 
+/// Blocks of lines separated by one or more blank lines, each yielded as its
+/// own `FileLines` that reports errors at its lines' positions in the input.
+pub struct Sections {
+    lines: FileLines,
+}
+
+impl Sections {
+    /// The next section, or an error saying what was expected instead of the
+    /// end of the input.
+    pub fn expect(&mut self, expected: &str) -> io::Result<FileLines> {
+        match self.next() {
+            Some(section) => Ok(section),
+            None => Err(ParseError {
+                file: self.lines.file.clone(),
+                line: self.lines.line + 1,
+                column: 1,
+                expected: String::from(expected),
+                found: String::from("end of input"),
+            }
+            .into()),
+        }
+    }
+
+    /// Parses the next section as a `T`.
+    pub fn parse_next<T>(&mut self, expected: &str) -> io::Result<T>
+    where
+        T: TryFrom<FileLines, Error = io::Error>,
+    {
+        T::try_from(self.expect(expected)?)
+    }
+
+    /// Parses every remaining section as a `T`.
+    pub fn parse_all<T>(self) -> io::Result<Vec<T>>
+    where
+        T: TryFrom<FileLines, Error = io::Error>,
+    {
+        self.map(T::try_from).collect()
+    }
+}
+
+impl Iterator for Sections {
+    type Item = FileLines;
+
+    fn next(&mut self) -> Option<FileLines> {
+        let mut block = String::new();
+        let mut first_line = 0;
+        while let Some(line) = self.lines.next() {
+            if line.is_empty() {
+                if first_line > 0 {
+                    break;
+                }
+                continue;
+            }
+            if first_line == 0 {
+                first_line = self.lines.line;
+            }
+            block.push_str(&line);
+            block.push('\n');
+        }
+        if first_line == 0 {
+            return None;
+        }
+        let mut section = FileLines::from_text(block);
+        section.file = self.lines.file.clone();
+        section.line = first_line - 1;
+        Some(section)
+    }
+}
+
+/// A section whose first line is a header, like the almanac's
+/// `seed-to-soil map:`. The header is parsed as an `H` without its trailing
+/// colon and the lines after it as a `B`.
+#[derive(Debug)]
+pub struct Headed<H, B> {
+    pub header: H,
+    pub body: B,
+}
+
+impl<H, B> TryFrom<FileLines> for Headed<H, B>
+where
+    H: FromStr,
+    B: TryFrom<FileLines, Error = io::Error>,
+{
+    type Error = io::Error;
+
+    fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
+        let line = lines.expect_line("a section header")?;
+        let header = line.strip_suffix(':').unwrap_or(&line);
+        let header = lines.parse_at(&line, header, "a section header")?;
+        Ok(Headed {
+            header,
+            body: B::try_from(lines)?,
+        })
+    }
+}
+
 pub struct FileSplit {
-    split: io::Split<io::BufReader<File>>,
+    split: io::Split<Box<dyn BufRead>>,
+    delimiter: u8,
+    file: String,
+    line: usize,
+    column: usize,
+    next_line: usize,
+    next_column: usize,
 }
 
 impl FileSplit {
     pub fn new(filename: &str, split: &u8) -> io::Result<Self> {
-        let f = File::open(filename)?;
-        Ok(FileSplit {
-            split: io::BufReader::new(f).split(*split),
-        })
+        Self::open(filename, split)
+    }
+
+    pub fn open(source: impl Into<Source>, split: &u8) -> io::Result<Self> {
+        let (file, reader) = source.into().open()?;
+        Ok(Self::split(file, reader, *split))
+    }
+
+    pub fn from_text(text: impl Into<String>, split: &u8) -> Self {
+        Self::from_reader("<text>", io::Cursor::new(text.into().into_bytes()), split)
+    }
+
+    pub fn from_reader(name: &str, reader: impl BufRead + 'static, split: &u8) -> Self {
+        Self::split(String::from(name), Box::new(reader), *split)
+    }
+
+    pub fn stdin(split: &u8) -> Self {
+        Self::from_reader("<stdin>", io::stdin().lock(), split)
+    }
+
+    fn split(file: String, reader: Box<dyn BufRead>, delimiter: u8) -> Self {
+        FileSplit {
+            split: reader.split(delimiter),
+            delimiter,
+            file,
+            line: 0,
+            column: 0,
+            next_line: 1,
+            next_column: 1,
+        }
+    }
+
+    pub fn file(&self) -> &str {
+        &self.file
+    }
+
+    /// The line and column where the item last returned starts.
+    pub fn position(&self) -> (usize, usize) {
+        (self.line, self.column)
+    }
+
+    /// An error at the start of the item last returned.
+    pub fn error(&self, expected: &str, found: &str) -> io::Error {
+        ParseError {
+            file: self.file.clone(),
+            line: self.line,
+            column: self.column,
+            expected: String::from(expected),
+            found: String::from(found),
+        }
+        .into()
+    }
+
+    fn advance(&mut self, item: &[u8]) {
+        self.line = self.next_line;
+        self.column = self.next_column;
+        for &b in item.iter().chain([self.delimiter].iter()) {
+            if b == b'\n' {
+                self.next_line += 1;
+                self.next_column = 1;
+            } else if b & 0xc0 != 0x80 {
+                self.next_column += 1;
+            }
+        }
     }
 
     pub fn next_result(&mut self) -> io::Result<Vec<u8>> {
         match self.split.next() {
-            Some(Ok(s)) => Ok(s),
+            Some(Ok(s)) => {
+                self.advance(&s);
+                Ok(s)
+            }
             Some(Err(e)) => {
                 eprintln!("Error! {:}", e);
                 Err(e)
@@ -116,7 +565,10 @@ impl Iterator for FileSplit {
 
     fn next(&mut self) -> Option<Vec<u8>> {
         match self.split.next() {
-            Some(Ok(s)) => Some(s),
+            Some(Ok(s)) => {
+                self.advance(&s);
+                Some(s)
+            }
             Some(Err(e)) => {
                 eprintln!("Error! {:}", e);
                 None
@@ -125,3 +577,149 @@ impl Iterator for FileSplit {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        parse, parse_bytes, parse_sections, FileLines, FileSplit, Headed, ParseError, Source,
+    };
+    use std::io;
+
+    #[derive(Debug)]
+    struct Numbers(Vec<u32>);
+
+    impl TryFrom<FileLines> for Numbers {
+        type Error = io::Error;
+
+        fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
+            let mut numbers = Vec::new();
+            while let Some(line) = lines.next() {
+                numbers.push(lines.parse_at(&line, &line, "a number")?);
+            }
+            Ok(Numbers(numbers))
+        }
+    }
+
+    #[test]
+    fn parser_sources() {
+        let Numbers(numbers) = parse(Source::text("1\n2\n3")).unwrap();
+        assert_eq!(numbers, vec![1, 2, 3]);
+        let Numbers(numbers) = parse(Source::text(String::from("4\n"))).unwrap();
+        assert_eq!(numbers, vec![4]);
+        let reader = io::Cursor::new(b"5\n6\n".to_vec());
+        let Numbers(numbers) = parse(Source::reader("cursor", reader)).unwrap();
+        assert_eq!(numbers, vec![5, 6]);
+        let e = parse::<Numbers>("input/gee/q01_sample.txt").err().unwrap();
+        assert_eq!(ParseError::of(&e).unwrap().file, "input/gee/q01_sample.txt");
+        assert!(parse::<Numbers>("input/nobody/q01_input.txt").is_err());
+        let lines = FileLines::from_reader("cursor", io::Cursor::new("a\nb"));
+        assert_eq!(lines.collect::<Vec<_>>(), vec!["a", "b"]);
+        assert_eq!(FileLines::from_text("x\ny").count(), 2);
+        assert!(matches!(Source::from("-"), Source::Stdin));
+        assert!(matches!(Source::from("q01.txt"), Source::File(_)));
+    }
+
+    #[test]
+    fn parser_bytes_from_text() {
+        let mut input = parse_bytes::<()>(Source::text("rn=1,cm-"), &b',').unwrap();
+        assert_eq!(input.source.next().unwrap(), b"rn=1");
+        assert_eq!(input.source.next().unwrap(), b"cm-");
+        assert!(input.source.next().is_none());
+    }
+
+    #[test]
+    fn parser_error_location() {
+        let mut lines = FileLines::from_text("Game 1: 3 red\nGame 2: x blue\n");
+        assert_eq!(lines.line_number(), 0);
+        lines.next();
+        let line = lines.next().unwrap();
+        assert_eq!(lines.line_number(), 2);
+        let count = &line[8..9];
+        let e = lines
+            .parse_at::<u32>(&line, count, "a cube count")
+            .unwrap_err();
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+        let error = ParseError::of(&e).unwrap();
+        assert_eq!((error.line, error.column), (2, 9));
+        assert_eq!(error.expected, "a cube count");
+        assert_eq!(error.found, "\"x\"");
+        assert_eq!(
+            e.to_string(),
+            "<text>:2:9: expected a cube count, found \"x\""
+        );
+        let e = parse::<Numbers>(Source::reader("numbers.txt", io::Cursor::new("1\n\n")))
+            .err()
+            .unwrap();
+        assert_eq!(
+            e.to_string(),
+            "numbers.txt:2:1: expected a number, found nothing"
+        );
+    }
+
+    #[test]
+    fn parser_error_helpers() {
+        let mut lines = FileLines::from_text("seeds 1 2\n.#?\n");
+        let line = lines.expect_line("seeds").unwrap();
+        let e = lines.strip_prefix(&line, &line, "seeds: ").unwrap_err();
+        assert_eq!(ParseError::of(&e).unwrap().column, 1);
+        let e = lines.split_once(&line, &line[6..], ",").unwrap_err();
+        assert_eq!(ParseError::of(&e).unwrap().column, 7);
+        let line = lines.expect_line("a row").unwrap();
+        let e = lines.chars_of(&line, ".#", "ash or rock").unwrap_err();
+        assert_eq!(ParseError::of(&e).unwrap().column, 3);
+        let e = lines.expect_line("another row").unwrap_err();
+        let error = ParseError::of(&e).unwrap();
+        assert_eq!((error.line, error.found.as_str()), (3, "end of input"));
+    }
+
+    #[test]
+    fn parser_split_position() {
+        let mut split = FileSplit::from_text("rn=1,\ncm-,qp=3", &b',');
+        split.next();
+        assert_eq!(split.position(), (1, 1));
+        split.next();
+        assert_eq!(split.position(), (1, 6));
+        assert_eq!(split.next().unwrap(), b"qp=3");
+        assert_eq!(split.position(), (2, 5));
+        let e = split.error("an operation", "qp=3");
+        assert_eq!(ParseError::of(&e).unwrap().line, 2);
+    }
+
+    #[test]
+    fn parser_sections() {
+        let text = "\n\na\nb\n\n\n\nc\n\nd\n";
+        let mut sections = FileLines::from_text(text).sections();
+        let mut first = sections.next().unwrap();
+        assert_eq!(first.next().unwrap(), "a");
+        assert_eq!(first.line_number(), 3);
+        assert_eq!(first.collect::<Vec<_>>(), vec!["b"]);
+        let mut second = sections.expect("c").unwrap();
+        assert_eq!(
+            (second.next().unwrap(), second.line_number()),
+            (String::from("c"), 8)
+        );
+        assert_eq!(sections.next().unwrap().collect::<Vec<_>>(), vec!["d"]);
+        assert!(sections.next().is_none());
+        let e = sections.expect("more").err().unwrap();
+        assert_eq!(ParseError::of(&e).unwrap().line, 11);
+    }
+
+    #[test]
+    fn parser_headed_sections() {
+        let text = "first:\n1\n2\n\nsecond:\n3\nx\n";
+        let mut sections = FileLines::from_text(text).sections();
+        let Headed { header, body } = sections
+            .parse_next::<Headed<String, Numbers>>("a section")
+            .unwrap();
+        assert_eq!((header.as_str(), body.0), ("first", vec![1, 2]));
+        let e = sections
+            .parse_next::<Headed<String, Numbers>>("a section")
+            .unwrap_err();
+        assert_eq!(ParseError::of(&e).unwrap().line, 7);
+        let e = parse_sections::<Headed<u32, Numbers>>(Source::text(text)).unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "<text>:1:1: expected a section header, found \"first\""
+        );
+    }
+}
@@ -0,0 +1,147 @@
+#![allow(dead_code)]
+
+use crate::utils::geom::{Direction, Point};
+use crate::utils::math::gcd;
+
+/// The lattice points of a simple polygon with integer vertices.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct Lattice {
+    /// Twice the enclosed area, which is always a whole number.
+    pub double_area: u64,
+    /// Points on the edges, vertices included.
+    pub boundary: u64,
+    /// Points strictly inside.
+    pub interior: u64,
+}
+
+impl Lattice {
+    /// Every point on or inside the polygon, e.g. the cubic metres of a lagoon
+    /// whose edges were dug one metre at a time.
+    pub fn total(&self) -> u64 {
+        self.boundary + self.interior
+    }
+}
+
+/// The corners reached by making each move in turn from `start`. The last
+/// move of a closed loop brings it back to `start`, which isn't repeated.
+pub fn vertices(
+    start: Point<i64>,
+    moves: impl IntoIterator<Item = (Direction, i64)>,
+) -> Vec<Point<i64>> {
+    let mut vertices = vec![start];
+    for (direction, distance) in moves {
+        let next = vertices[vertices.len() - 1].moved(direction, distance);
+        vertices.push(next);
+    }
+    if vertices.len() > 1 && vertices.last() == vertices.first() {
+        vertices.pop();
+    }
+    vertices
+}
+
+/// Twice the area enclosed by the vertices, by the shoelace formula.
+pub fn double_area(vertices: &[Point<i64>]) -> u64 {
+    let next = vertices.iter().cycle().skip(1);
+    let sum: i128 = vertices
+        .iter()
+        .zip(next)
+        .map(|(a, b)| a.column as i128 * b.row as i128 - b.column as i128 * a.row as i128)
+        .sum();
+    sum.unsigned_abs() as u64
+}
+
+/// How many lattice points are on the edges between the vertices.
+pub fn boundary(vertices: &[Point<i64>]) -> u64 {
+    let next = vertices.iter().cycle().skip(1);
+    vertices
+        .iter()
+        .zip(next)
+        .map(|(a, b)| {
+            let dr = a.row.abs_diff(b.row) as u128;
+            let dc = a.column.abs_diff(b.column) as u128;
+            gcd(dr, dc) as u64
+        })
+        .sum()
+}
+
+/// The area, boundary and interior of the polygon through the vertices, with
+/// the interior from Pick's theorem: A = i + b/2 - 1.
+pub fn lattice(vertices: &[Point<i64>]) -> Lattice {
+    let double_area = double_area(vertices);
+    let boundary = boundary(vertices);
+    Lattice {
+        double_area,
+        boundary,
+        interior: (double_area + 2).saturating_sub(boundary) / 2,
+    }
+}
+
+/// The lattice of the loop made by a list of moves.
+pub fn lattice_from_moves(moves: impl IntoIterator<Item = (Direction, i64)>) -> Lattice {
+    lattice(&vertices(Point::default(), moves))
+}
+
+/// A move hidden in a colour code like `70c710`: five hex digits of
+/// distance, then 0, 1, 2 or 3 for right, down, left or up.
+pub fn hex_move(code: &str) -> Option<(Direction, i64)> {
+    if code.len() != 6 || !code.chars().all(|c| c.is_ascii_hexdigit()) {
+        return None;
+    }
+    let distance = i64::from_str_radix(&code[..5], 16).ok()?;
+    let direction = match &code[5..] {
+        "0" => Direction::Right,
+        "1" => Direction::Down,
+        "2" => Direction::Left,
+        "3" => Direction::Up,
+        _ => return None,
+    };
+    Some((direction, distance))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{double_area, hex_move, lattice, lattice_from_moves, vertices, Lattice};
+    use crate::utils::geom::{Direction, Point};
+
+    #[test]
+    fn polygon_square() {
+        // A 4x4 square of points has 12 on its edge and 4 inside
+        let square = [
+            (Direction::Right, 3),
+            (Direction::Down, 3),
+            (Direction::Left, 3),
+            (Direction::Up, 3),
+        ];
+        let expected = Lattice {
+            double_area: 18,
+            boundary: 12,
+            interior: 4,
+        };
+        assert_eq!(lattice_from_moves(square), expected);
+        assert_eq!(expected.total(), 16);
+        assert_eq!(vertices(Point::default(), square).len(), 4);
+
+        // Going round the other way doesn't change the area
+        let reversed: Vec<_> = square
+            .iter()
+            .rev()
+            .map(|&(d, n)| (d.opposite(), n))
+            .collect();
+        assert_eq!(lattice_from_moves(reversed), expected);
+    }
+
+    #[test]
+    fn polygon_diagonals_and_hex() {
+        // A triangle with a slanted edge through (1, 1) and (2, 2)
+        let triangle = [Point::new(0, 0), Point::new(0, 3), Point::new(3, 3)];
+        assert_eq!(double_area(&triangle), 9);
+        assert_eq!(lattice(&triangle).boundary, 9);
+        assert_eq!(lattice(&triangle).interior, 1);
+
+        assert_eq!(hex_move("70c710"), Some((Direction::Right, 461937)));
+        assert_eq!(hex_move("0dc571"), Some((Direction::Down, 56407)));
+        assert_eq!(hex_move("7a21e3"), Some((Direction::Up, 500254)));
+        assert_eq!(hex_move("70c714"), None);
+        assert_eq!(hex_move("70c7"), None);
+    }
+}
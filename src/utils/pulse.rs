@@ -0,0 +1,453 @@
+#![allow(dead_code)]
+
+use crate::utils::dot::Dot;
+use crate::utils::math::crt;
+use crate::utils::parser::{error, FileLines};
+use std::cell::RefCell;
+use std::collections::{HashMap, VecDeque};
+
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum Pulse {
+    Low,
+    High,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ModuleKind {
+    Broadcaster,
+    FlipFlop,
+    Conjunction,
+    /// Named as a destination but never defined, like `rx`.
+    Sink,
+}
+
+#[derive(Clone, Debug)]
+struct Module {
+    name: String,
+    kind: ModuleKind,
+    inputs: Vec<usize>,
+    /// Each destination, and which of its inputs this module is.
+    outputs: Vec<(usize, usize)>,
+}
+
+/// The modules of q20 and their wiring, with each module known by its index.
+#[derive(Clone, Debug)]
+pub struct Network {
+    modules: Vec<Module>,
+    ids: HashMap<String, usize>,
+    broadcaster: usize,
+}
+
+impl Network {
+    pub fn len(&self) -> usize {
+        self.modules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.modules.is_empty()
+    }
+
+    pub fn id(&self, name: &str) -> Option<usize> {
+        self.ids.get(name).copied()
+    }
+
+    pub fn name(&self, id: usize) -> &str {
+        &self.modules[id].name
+    }
+
+    pub fn kind(&self, id: usize) -> ModuleKind {
+        self.modules[id].kind
+    }
+
+    pub fn inputs(&self, id: usize) -> &[usize] {
+        &self.modules[id].inputs
+    }
+
+    pub fn outputs(&self, id: usize) -> impl Iterator<Item = usize> + '_ {
+        self.modules[id].outputs.iter().map(|&(to, _)| to)
+    }
+
+    /// The wiring as a GraphViz digraph, with a shape per kind of module: the
+    /// broadcaster where every press starts, flip-flops as boxes,
+    /// conjunctions as inverted houses and sinks like `rx` at the end.
+    pub fn to_dot(&self) -> Dot {
+        let mut dot = Dot::digraph("modules");
+        for (id, module) in self.modules.iter().enumerate() {
+            let style: &[(&str, &str)] = match module.kind {
+                ModuleKind::Broadcaster => &[("shape", "doublecircle"), ("color", "green")],
+                ModuleKind::FlipFlop => &[("shape", "box")],
+                ModuleKind::Conjunction => &[("shape", "invhouse"), ("color", "blue")],
+                ModuleKind::Sink => &[("shape", "doubleoctagon"), ("color", "red")],
+            };
+            dot.node(&module.name, style);
+            for to in self.outputs(id) {
+                dot.edge(&module.name, &self.modules[to].name, &[]);
+            }
+        }
+        dot
+    }
+
+    fn expect_id(&self, name: &str) -> std::io::Result<usize> {
+        match self.id(name) {
+            Some(id) => Ok(id),
+            None => error(&format!("No module named {}", name)),
+        }
+    }
+}
+
+impl TryFrom<FileLines> for Network {
+    type Error = std::io::Error;
+
+    fn try_from(mut lines: FileLines) -> Result<Self, Self::Error> {
+        let mut modules: Vec<Module> = Vec::new();
+        let mut ids = HashMap::new();
+        let mut id = |modules: &mut Vec<Module>, name: &str| -> usize {
+            *ids.entry(String::from(name)).or_insert_with(|| {
+                modules.push(Module {
+                    name: String::from(name),
+                    kind: ModuleKind::Sink,
+                    inputs: Vec::new(),
+                    outputs: Vec::new(),
+                });
+                modules.len() - 1
+            })
+        };
+        while let Some(line) = lines.next() {
+            let (m, d) = lines.split_once(&line, &line, " -> ")?;
+            let (name, kind) = if m == "broadcaster" {
+                (m, ModuleKind::Broadcaster)
+            } else if let Some(name) = m.strip_prefix('%') {
+                (name, ModuleKind::FlipFlop)
+            } else if let Some(name) = m.strip_prefix('&') {
+                (name, ModuleKind::Conjunction)
+            } else {
+                return Err(lines.error_at(&line, m, "broadcaster, %name or &name"));
+            };
+            let from = id(&mut modules, name);
+            if modules[from].kind != ModuleKind::Sink {
+                return Err(lines.error_at(&line, name, "a new module name"));
+            }
+            modules[from].kind = kind;
+            for downstream in d.split(", ") {
+                let to = id(&mut modules, downstream);
+                let input = modules[to].inputs.len();
+                modules[from].outputs.push((to, input));
+                modules[to].inputs.push(from);
+            }
+        }
+        let Some(broadcaster) = modules
+            .iter()
+            .position(|m| m.kind == ModuleKind::Broadcaster)
+        else {
+            return error("No broadcaster module");
+        };
+        let ids = modules
+            .iter()
+            .enumerate()
+            .map(|(i, m)| (m.name.clone(), i))
+            .collect();
+        Ok(Network {
+            modules,
+            ids,
+            broadcaster,
+        })
+    }
+}
+
+/// A pulse on its way to a module. `from` is `None` for the button.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub struct Signal {
+    pub from: Option<usize>,
+    pub to: usize,
+    pub pulse: Pulse,
+    /// Which of `to`'s inputs it arrives on.
+    input: usize,
+}
+
+/// Everything that changes as pulses are sent, so it can be saved and put
+/// back.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct State {
+    on: Vec<bool>,
+    /// The last pulse each module had from each of its inputs.
+    memory: Vec<Vec<Pulse>>,
+    queue: VecDeque<Signal>,
+    pub presses: u64,
+    pub low_pulses: u64,
+    pub high_pulses: u64,
+}
+
+type Observer<'a> = Box<dyn FnMut(&Signal, u64) + 'a>;
+
+pub struct Simulator<'a> {
+    network: &'a Network,
+    state: State,
+    /// Hooks called with each pulse a module receives and the press it's in.
+    observers: Vec<(usize, Observer<'a>)>,
+}
+
+impl<'a> Simulator<'a> {
+    pub fn new(network: &'a Network) -> Self {
+        let state = State {
+            on: vec![false; network.len()],
+            memory: network
+                .modules
+                .iter()
+                .map(|m| vec![Pulse::Low; m.inputs.len()])
+                .collect(),
+            queue: VecDeque::new(),
+            presses: 0,
+            low_pulses: 0,
+            high_pulses: 0,
+        };
+        Simulator {
+            network,
+            state,
+            observers: Vec::new(),
+        }
+    }
+
+    pub fn state(&self) -> &State {
+        &self.state
+    }
+
+    pub fn snapshot(&self) -> State {
+        self.state.clone()
+    }
+
+    pub fn restore(&mut self, state: State) {
+        self.state = state;
+    }
+
+    /// Whether a flip-flop is on.
+    pub fn is_on(&self, id: usize) -> bool {
+        self.state.on[id]
+    }
+
+    /// Calls `hook` with every pulse `module` receives from now on.
+    pub fn observe(
+        &mut self,
+        module: &str,
+        hook: impl FnMut(&Signal, u64) + 'a,
+    ) -> std::io::Result<()> {
+        let id = self.network.expect_id(module)?;
+        self.observers.push((id, Box::new(hook)));
+        Ok(())
+    }
+
+    /// Presses the button, queueing its pulse without sending it on.
+    pub fn press(&mut self) {
+        self.state.presses += 1;
+        self.state.queue.push_back(Signal {
+            from: None,
+            to: self.network.broadcaster,
+            pulse: Pulse::Low,
+            input: 0,
+        });
+    }
+
+    /// Delivers the next queued pulse, if there is one, and returns it.
+    pub fn step(&mut self) -> Option<Signal> {
+        let signal = self.state.queue.pop_front()?;
+        match signal.pulse {
+            Pulse::Low => self.state.low_pulses += 1,
+            Pulse::High => self.state.high_pulses += 1,
+        }
+        for (id, hook) in &mut self.observers {
+            if *id == signal.to {
+                hook(&signal, self.state.presses);
+            }
+        }
+        let module = &self.network.modules[signal.to];
+        let next = match module.kind {
+            ModuleKind::Broadcaster => Some(signal.pulse),
+            ModuleKind::FlipFlop if signal.pulse == Pulse::Low => {
+                let on = &mut self.state.on[signal.to];
+                *on = !*on;
+                Some(if *on { Pulse::High } else { Pulse::Low })
+            }
+            ModuleKind::Conjunction => {
+                let memory = &mut self.state.memory[signal.to];
+                memory[signal.input] = signal.pulse;
+                if memory.iter().all(|&p| p == Pulse::High) {
+                    Some(Pulse::Low)
+                } else {
+                    Some(Pulse::High)
+                }
+            }
+            ModuleKind::FlipFlop | ModuleKind::Sink => None,
+        };
+        if let Some(pulse) = next {
+            for &(to, input) in &module.outputs {
+                self.state.queue.push_back(Signal {
+                    from: Some(signal.to),
+                    to,
+                    pulse,
+                    input,
+                });
+            }
+        }
+        Some(signal)
+    }
+
+    /// Presses the button and sends pulses until they've all arrived.
+    pub fn press_button(&mut self) {
+        self.press();
+        while self.step().is_some() {}
+    }
+}
+
+/// When each input of a conjunction sends it a high pulse, as (first press,
+/// period), from pressing the button up to `limit` times.
+pub fn conjunction_cycles(
+    network: &Network,
+    conjunction: &str,
+    limit: u64,
+) -> std::io::Result<Vec<(u64, u64)>> {
+    let id = network.expect_id(conjunction)?;
+    if network.kind(id) != ModuleKind::Conjunction {
+        return error(&format!("{} isn't a conjunction", conjunction));
+    }
+    let inputs = network.inputs(id).len();
+    // The first two presses each input sent a high pulse in
+    let highs = RefCell::new(vec![Vec::new(); inputs]);
+    let mut simulator = Simulator::new(network);
+    simulator.observe(conjunction, |signal, presses| {
+        let mut highs = highs.borrow_mut();
+        let seen = &mut highs[signal.input];
+        if signal.pulse == Pulse::High && seen.len() < 2 && seen.last() != Some(&presses) {
+            seen.push(presses);
+        }
+    })?;
+    while highs.borrow().iter().any(|seen| seen.len() < 2) {
+        if simulator.state().presses == limit {
+            return error(&format!(
+                "No cycle into {} within {} presses",
+                conjunction, limit
+            ));
+        }
+        simulator.press_button();
+    }
+    drop(simulator);
+    Ok(highs
+        .into_inner()
+        .into_iter()
+        .map(|seen| (seen[0], seen[1] - seen[0]))
+        .collect())
+}
+
+/// The fewest presses until `module` gets a low pulse, when it's fed by a
+/// single conjunction whose inputs each send it high pulses in a cycle.
+pub fn presses_until_low(network: &Network, module: &str, limit: u64) -> std::io::Result<u64> {
+    let id = network.expect_id(module)?;
+    let [feeder] = network.inputs(id) else {
+        return error(&format!("{} isn't fed by a single module", module));
+    };
+    let cycles = conjunction_cycles(network, network.name(*feeder), limit)?;
+    let events = cycles
+        .iter()
+        .map(|&(first, period)| (first as u128, period as u128));
+    match crt(events) {
+        Some((presses, _)) => Ok(presses as u64),
+        None => error(&format!("{} never gets a low pulse", module)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{conjunction_cycles, presses_until_low, Network, Pulse, Simulator};
+    use crate::utils::parser::{parse, Source};
+    use std::cell::RefCell;
+
+    // `inv` sends `all` a high pulse every second press and `inv2` every
+    // fourth, so `rx` first gets a low pulse on the fourth
+    const NETWORK: &str = "broadcaster -> a, b
+%a -> inv
+&inv -> all
+%b -> c
+%c -> inv2
+&inv2 -> all
+&all -> rx
+";
+
+    fn network() -> Network {
+        parse(Source::text(NETWORK)).unwrap()
+    }
+
+    #[test]
+    fn pulse_counts_and_stepping() {
+        let network: Network = parse("input/gee/q20_sample.txt").unwrap();
+        let mut simulator = Simulator::new(&network);
+        for _ in 0..1000 {
+            simulator.press_button();
+        }
+        let state = simulator.state();
+        assert_eq!(state.low_pulses * state.high_pulses, 11687500);
+
+        // One press of the first example, a pulse at a time
+        let network: Network = parse(Source::text(
+            "broadcaster -> a, b, c\n%a -> b\n%b -> c\n%c -> inv\n&inv -> a\n",
+        ))
+        .unwrap();
+        let mut simulator = Simulator::new(&network);
+        simulator.press();
+        let mut pulses = Vec::new();
+        while let Some(signal) = simulator.step() {
+            pulses.push((network.name(signal.to).to_string(), signal.pulse));
+        }
+        assert_eq!(pulses.len(), 12);
+        assert_eq!(pulses[0], ("broadcaster".to_string(), Pulse::Low));
+        assert_eq!(pulses[6], ("inv".to_string(), Pulse::High));
+        assert_eq!(pulses[11], ("a".to_string(), Pulse::High));
+    }
+
+    #[test]
+    fn pulse_observers_and_snapshots() {
+        let network = network();
+        let received = RefCell::new(Vec::new());
+        let mut simulator = Simulator::new(&network);
+        simulator
+            .observe("rx", |signal, presses| {
+                if signal.pulse == Pulse::Low {
+                    received.borrow_mut().push(presses);
+                }
+            })
+            .unwrap();
+        simulator.press_button();
+        let saved = simulator.snapshot();
+        simulator.press_button();
+        let after_two = simulator.snapshot();
+        simulator.restore(saved);
+        simulator.press_button();
+        assert_eq!(simulator.snapshot(), after_two);
+        assert!(simulator.is_on(network.id("c").unwrap()));
+        simulator.press_button();
+        simulator.press_button();
+        assert!(simulator.observe("nowhere", |_, _| ()).is_err());
+        drop(simulator);
+        assert_eq!(received.into_inner(), [4]);
+    }
+
+    #[test]
+    fn pulse_cycles() {
+        let network = network();
+        let cycles = conjunction_cycles(&network, "all", 100).unwrap();
+        assert_eq!(cycles, [(2, 2), (4, 4)]);
+        assert_eq!(presses_until_low(&network, "rx", 100).unwrap(), 4);
+        assert!(conjunction_cycles(&network, "a", 100).is_err());
+        assert!(conjunction_cycles(&network, "all", 3).is_err());
+        assert!(presses_until_low(&network, "all", 100).is_err());
+    }
+
+    #[test]
+    fn pulse_to_dot() {
+        let dot = network().to_dot().to_string();
+        assert!(dot.starts_with("digraph \"modules\" {\n"));
+        assert!(dot.contains("\"broadcaster\" [shape=\"doublecircle\", color=\"green\"];"));
+        assert!(dot.contains("\"a\" [shape=\"box\"];"));
+        assert!(dot.contains("\"all\" [shape=\"invhouse\", color=\"blue\"];"));
+        assert!(dot.contains("\"rx\" [shape=\"doubleoctagon\", color=\"red\"];"));
+        assert!(dot.contains("\"inv2\" -> \"all\";"));
+        assert_eq!(dot.matches(" -> ").count(), 8);
+    }
+}
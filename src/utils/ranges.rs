@@ -0,0 +1,268 @@
+#![allow(dead_code)]
+
+use crate::utils::parser::error;
+use std::ops::Range;
+
+/// The overlap of two half-open ranges, if they have one.
+pub fn intersect(a: &Range<u64>, b: &Range<u64>) -> Option<Range<u64>> {
+    let range = a.start.max(b.start)..a.end.min(b.end);
+    (!range.is_empty()).then_some(range)
+}
+
+/// A set of numbers as sorted, disjoint, non-adjacent half-open ranges.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RangeSet {
+    ranges: Vec<Range<u64>>,
+}
+
+impl RangeSet {
+    pub fn new() -> Self {
+        RangeSet::default()
+    }
+
+    pub fn ranges(&self) -> &[Range<u64>] {
+        &self.ranges
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Range<u64>> {
+        self.ranges.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// How many numbers are in the set.
+    pub fn size(&self) -> u64 {
+        self.ranges.iter().map(|r| r.end - r.start).sum()
+    }
+
+    pub fn min(&self) -> Option<u64> {
+        self.ranges.first().map(|r| r.start)
+    }
+
+    pub fn max(&self) -> Option<u64> {
+        self.ranges.last().map(|r| r.end - 1)
+    }
+
+    pub fn contains(&self, value: u64) -> bool {
+        let i = self.ranges.partition_point(|r| r.end <= value);
+        self.ranges.get(i).is_some_and(|r| r.contains(&value))
+    }
+
+    pub fn insert(&mut self, range: Range<u64>) {
+        self.ranges.push(range);
+        self.normalise();
+    }
+
+    pub fn union(&self, other: &RangeSet) -> RangeSet {
+        self.iter().chain(other.iter()).cloned().collect()
+    }
+
+    pub fn intersection(&self, other: &RangeSet) -> RangeSet {
+        let (mut i, mut j) = (0, 0);
+        let mut ranges = Vec::new();
+        while i < self.ranges.len() && j < other.ranges.len() {
+            let (a, b) = (&self.ranges[i], &other.ranges[j]);
+            ranges.extend(intersect(a, b));
+            // Whichever ends first can't overlap anything else in the other
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        RangeSet { ranges }
+    }
+
+    /// The numbers in `self` that aren't in `other`.
+    pub fn difference(&self, other: &RangeSet) -> RangeSet {
+        let mut ranges = Vec::new();
+        let mut j = 0;
+        for range in &self.ranges {
+            let mut start = range.start;
+            // Skip what ends before this range, but keep anything that could
+            // also cut into the next one
+            while j < other.ranges.len() && other.ranges[j].end <= start {
+                j += 1;
+            }
+            let mut k = j;
+            while k < other.ranges.len() && other.ranges[k].start < range.end {
+                let cut = &other.ranges[k];
+                if start < cut.start {
+                    ranges.push(start..cut.start);
+                }
+                start = start.max(cut.end);
+                k += 1;
+            }
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+        RangeSet { ranges }
+    }
+
+    /// Sorts the ranges, dropping empty ones and merging any that touch.
+    fn normalise(&mut self) {
+        self.ranges.retain(|r| !r.is_empty());
+        self.ranges.sort_by_key(|r| r.start);
+        let mut merged: Vec<Range<u64>> = Vec::with_capacity(self.ranges.len());
+        for range in self.ranges.drain(..) {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range),
+            }
+        }
+        self.ranges = merged;
+    }
+}
+
+impl FromIterator<Range<u64>> for RangeSet {
+    fn from_iter<I: IntoIterator<Item = Range<u64>>>(iter: I) -> Self {
+        let mut set = RangeSet {
+            ranges: iter.into_iter().collect(),
+        };
+        set.normalise();
+        set
+    }
+}
+
+/// A piecewise-linear map, like the almanac's in q05: numbers in a source
+/// range move by the same amount, and everything else maps to itself.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RangeMap {
+    /// Each source range and where its start maps to, sorted by source.
+    entries: Vec<(Range<u64>, u64)>,
+}
+
+impl RangeMap {
+    /// A map from (destination, source, length) triples, which is an error
+    /// if any of the source ranges overlap or either range runs past
+    /// `u64::MAX`.
+    pub fn new(triples: impl IntoIterator<Item = (u64, u64, u64)>) -> std::io::Result<Self> {
+        let mut entries = Vec::new();
+        for (destination, source, length) in triples {
+            if source.max(destination).checked_add(length).is_none() {
+                return error("A range runs past the largest number");
+            }
+            if length > 0 {
+                entries.push((source..source + length, destination));
+            }
+        }
+        entries.sort_by_key(|(source, _)| source.start);
+        if entries.windows(2).any(|w| w[0].0.end > w[1].0.start) {
+            return error("Overlapping source ranges");
+        }
+        Ok(RangeMap { entries })
+    }
+
+    pub fn get(&self, value: u64) -> u64 {
+        let i = self
+            .entries
+            .partition_point(|(source, _)| source.end <= value);
+        match self.entries.get(i) {
+            Some((source, destination)) if source.contains(&value) => {
+                destination + (value - source.start)
+            }
+            _ => value,
+        }
+    }
+
+    /// Where every number in `set` maps to.
+    pub fn apply(&self, set: &RangeSet) -> RangeSet {
+        let mut ranges = Vec::new();
+        for range in set.iter() {
+            let mut start = range.start;
+            let first = self
+                .entries
+                .partition_point(|(source, _)| source.end <= start);
+            for (source, destination) in &self.entries[first..] {
+                if source.start >= range.end {
+                    break;
+                }
+                if start < source.start {
+                    ranges.push(start..source.start);
+                    start = source.start;
+                }
+                let end = range.end.min(source.end);
+                let mapped = destination + (start - source.start);
+                ranges.push(mapped..mapped + (end - start));
+                start = end;
+            }
+            if start < range.end {
+                ranges.push(start..range.end);
+            }
+        }
+        ranges.into_iter().collect()
+    }
+}
+
+/// Where a number ends up after going through each map in turn.
+pub fn chain_get<'a>(maps: impl IntoIterator<Item = &'a RangeMap>, value: u64) -> u64 {
+    maps.into_iter().fold(value, |value, map| map.get(value))
+}
+
+/// Where a whole set ends up after going through each map in turn.
+pub fn chain_apply<'a>(maps: impl IntoIterator<Item = &'a RangeMap>, set: RangeSet) -> RangeSet {
+    maps.into_iter().fold(set, |set, map| map.apply(&set))
+}
+
+#[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::{chain_apply, chain_get, intersect, RangeMap, RangeSet};
+
+    #[test]
+    fn ranges_set_operations() {
+        let a: RangeSet = [5..10, 0..3, 3..4, 20..20].into_iter().collect();
+        assert_eq!(a.ranges(), [0..4, 5..10]);
+        assert_eq!(a.size(), 9);
+        assert_eq!((a.min(), a.max()), (Some(0), Some(9)));
+        assert!(a.contains(3) && !a.contains(4) && !a.contains(10));
+
+        let b: RangeSet = [2..6, 8..12].into_iter().collect();
+        assert_eq!(a.union(&b).ranges(), [0..12]);
+        assert_eq!(a.intersection(&b).ranges(), [2..4, 5..6, 8..10]);
+        assert_eq!(a.difference(&b).ranges(), [0..2, 6..8]);
+        assert_eq!(b.difference(&a).ranges(), [4..5, 10..12]);
+        assert!(a.difference(&a).is_empty());
+        assert_eq!(intersect(&(0..5), &(5..9)), None);
+
+        let mut c = RangeSet::new();
+        c.insert(7..9);
+        c.insert(1..2);
+        c.insert(2..7);
+        assert_eq!(c.ranges(), [1..9]);
+    }
+
+    #[test]
+    fn ranges_map() {
+        // The sample almanac's seed-to-soil and soil-to-fertilizer maps
+        let soil = RangeMap::new([(50, 98, 2), (52, 50, 48)]).unwrap();
+        let fertilizer = RangeMap::new([(0, 15, 37), (37, 52, 2), (39, 0, 15)]).unwrap();
+        assert_eq!(
+            [79, 14, 55, 13, 98, 100].map(|s| soil.get(s)),
+            [81, 14, 57, 13, 50, 100]
+        );
+        assert_eq!(chain_get([&soil, &fertilizer], 79), 81);
+        assert_eq!(chain_get([&soil, &fertilizer], 14), 53);
+
+        let seeds: RangeSet = [79..93, 55..68].into_iter().collect();
+        let soils = soil.apply(&seeds);
+        assert_eq!(soils.ranges(), [57..70, 81..95]);
+        let fertilizers = chain_apply([&soil, &fertilizer], seeds.clone());
+        assert_eq!(fertilizers.size(), seeds.size());
+        for seed in seeds.iter().flat_map(|r| r.clone()) {
+            assert!(fertilizers.contains(chain_get([&soil, &fertilizer], seed)));
+        }
+
+        // A range straddling both ends of a mapped range splits in three
+        let shift = RangeMap::new([(100, 10, 5)]).unwrap();
+        let split = shift.apply(&[8..17].into_iter().collect());
+        assert_eq!(split.ranges(), [8..10, 15..17, 100..105]);
+        assert!(RangeMap::new([(0, 10, 5), (0, 14, 2)]).is_err());
+        assert!(RangeMap::new([(0, u64::MAX, 2)]).is_err());
+        assert!(RangeMap::new([(u64::MAX - 1, 0, 2)]).is_err());
+        assert!(RangeMap::new([(u64::MAX - 1, 0, 1)]).is_ok());
+    }
+}
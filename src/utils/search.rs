@@ -0,0 +1,225 @@
+#![allow(dead_code)]
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::hash::Hash;
+use std::ops::Add;
+
+/// The cheapest way found to a goal: its total cost and every state on the
+/// way, from the start it began at to the goal.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Found<S, C> {
+    pub cost: C,
+    pub path: Vec<S>,
+}
+
+impl<S, C> Found<S, C> {
+    pub fn goal(&self) -> &S {
+        self.path.last().expect("a path always holds its start")
+    }
+}
+
+/// Every state seen so far, with the one it was reached from and its cost.
+struct Visited<S, C> {
+    states: Vec<(S, Option<usize>, C)>,
+    index: HashMap<S, usize>,
+}
+
+impl<S: Clone + Eq + Hash, C: Copy> Visited<S, C> {
+    fn new() -> Self {
+        Visited {
+            states: Vec::new(),
+            index: HashMap::new(),
+        }
+    }
+
+    fn path_to(&self, mut i: usize) -> Vec<S> {
+        let mut path = vec![self.states[i].0.clone()];
+        while let Some(parent) = self.states[i].1 {
+            path.push(self.states[parent].0.clone());
+            i = parent;
+        }
+        path.reverse();
+        path
+    }
+
+    fn found(&self, i: usize) -> Found<S, C> {
+        Found {
+            cost: self.states[i].2,
+            path: self.path_to(i),
+        }
+    }
+}
+
+/// Breadth-first search where every move costs one step.
+pub fn bfs<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, usize>>
+where
+    S: Clone + Eq + Hash,
+    I: IntoIterator<Item = S>,
+{
+    let mut visited = Visited::new();
+    let mut queue = VecDeque::new();
+    for start in starts {
+        if !visited.index.contains_key(&start) {
+            visited.index.insert(start.clone(), visited.states.len());
+            queue.push_back(visited.states.len());
+            visited.states.push((start, None, 0));
+        }
+    }
+    while let Some(i) = queue.pop_front() {
+        if is_goal(&visited.states[i].0) {
+            return Some(visited.found(i));
+        }
+        let steps = visited.states[i].2 + 1;
+        for next in neighbors(&visited.states[i].0) {
+            if !visited.index.contains_key(&next) {
+                visited.index.insert(next.clone(), visited.states.len());
+                queue.push_back(visited.states.len());
+                visited.states.push((next, Some(i), steps));
+            }
+        }
+    }
+    None
+}
+
+/// Dijkstra's algorithm, where `neighbors` gives each next state with the
+/// cost of moving to it.
+pub fn dijkstra<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    neighbors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Default + Ord + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    astar(starts, neighbors, |_| C::default(), is_goal)
+}
+
+/// A* search. `heuristic` must never overestimate the remaining cost to a
+/// goal, or the path found may not be the cheapest.
+pub fn astar<S, C, I>(
+    starts: impl IntoIterator<Item = S>,
+    mut neighbors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> C,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> Option<Found<S, C>>
+where
+    S: Clone + Eq + Hash,
+    C: Copy + Default + Ord + Add<Output = C>,
+    I: IntoIterator<Item = (S, C)>,
+{
+    let mut visited = Visited::new();
+    let mut queue = BinaryHeap::new();
+    for start in starts {
+        if !visited.index.contains_key(&start) {
+            let i = visited.states.len();
+            queue.push(Reverse((heuristic(&start), C::default(), i)));
+            visited.index.insert(start.clone(), i);
+            visited.states.push((start, None, C::default()));
+        }
+    }
+    while let Some(Reverse((_, cost, i))) = queue.pop() {
+        if cost > visited.states[i].2 {
+            // A cheaper way here was found after this one was queued
+            continue;
+        }
+        if is_goal(&visited.states[i].0) {
+            return Some(visited.found(i));
+        }
+        for (next, step) in neighbors(&visited.states[i].0) {
+            let next_cost = cost + step;
+            let j = match visited.index.get(&next) {
+                Some(&j) if visited.states[j].2 <= next_cost => continue,
+                Some(&j) => {
+                    visited.states[j].1 = Some(i);
+                    visited.states[j].2 = next_cost;
+                    j
+                }
+                None => {
+                    let j = visited.states.len();
+                    visited.index.insert(next.clone(), j);
+                    visited.states.push((next, Some(i), next_cost));
+                    j
+                }
+            };
+            let estimate = next_cost + heuristic(&visited.states[j].0);
+            queue.push(Reverse((estimate, next_cost, j)));
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{astar, bfs, dijkstra};
+    use crate::utils::grid::{Grid, Position};
+    use crate::utils::parser::{parse, FileLines, Source};
+
+    fn maze() -> Grid<char> {
+        parse(Source::text("S.#.\n..#.\n#...\n..#E\n")).unwrap()
+    }
+
+    #[test]
+    fn search_bfs() {
+        let grid = maze();
+        let open = |&p: &Position| {
+            let grid = &grid;
+            grid.neighbors4(p).filter(move |&n| grid[n] != '#')
+        };
+        let found = bfs([(0, 0)], open, |&p| p == (3, 3)).unwrap();
+        assert_eq!(found.cost, 6);
+        assert_eq!(found.path.len(), 7);
+        assert_eq!(*found.goal(), (3, 3));
+        assert!(found
+            .path
+            .windows(2)
+            .all(|w| grid.neighbors4(w[0]).any(|n| n == w[1])));
+        assert_eq!(
+            bfs([(0, 0)], open, |&p| p == (0, 3)).map(|f| f.cost),
+            Some(7)
+        );
+        assert_eq!(bfs([(0, 0)], |_| Vec::new(), |&p| p == (3, 3)), None);
+        assert_eq!(
+            bfs([(0, 0)], open, |&p| p == (0, 0)).unwrap().path,
+            [(0, 0)]
+        );
+    }
+
+    #[test]
+    fn search_weighted() {
+        // Digits are the cost of entering each cell
+        let grid: Grid<u32> = Grid::parse(
+            FileLines::from_text("1911\n1919\n1119\n9911"),
+            "a digit",
+            |c| c.to_digit(10),
+        )
+        .unwrap();
+        let target = (3, 3);
+        let moves = |&p: &Position| {
+            let grid = &grid;
+            grid.neighbors4(p).map(move |n| (n, grid[n]))
+        };
+        let slow = dijkstra([(0, 0)], moves, |&p| p == target).unwrap();
+        let fast = astar(
+            [(0, 0)],
+            moves,
+            |&(r, c): &Position| (target.0 - r + target.1 - c) as u32,
+            |&p| p == target,
+        )
+        .unwrap();
+        assert_eq!(slow.cost, 6);
+        assert_eq!(slow, fast);
+        assert_eq!(slow.path.first(), Some(&(0, 0)));
+        let cost: u32 = slow.path[1..].iter().map(|&p| grid[p]).sum();
+        assert_eq!(cost, slow.cost);
+
+        let either = dijkstra([(0, 3), (3, 0)], moves, |&p| p == (0, 2)).unwrap();
+        assert_eq!((either.cost, either.path[0]), (1, (0, 3)));
+    }
+}
@@ -1,6 +1,219 @@
 #![allow(dead_code)]
 
-pub fn transpose_vec_of_strings(vec_of_string: Vec<String>) -> Vec<String> {
+use crate::utils::grid::{Grid, Position};
+use crate::utils::parser::error;
+use std::io;
+
+/// Rotations and flips of a rectangular grid. Rotations are clockwise, a
+/// horizontal flip mirrors each row and a vertical flip reverses the rows.
+/// Empty and ragged grids are errors.
+pub trait Transform: Sized {
+    fn transpose(&self) -> io::Result<Self>;
+    fn rotate_90(&self) -> io::Result<Self>;
+    fn rotate_180(&self) -> io::Result<Self>;
+    fn rotate_270(&self) -> io::Result<Self>;
+    fn flip_horizontal(&self) -> io::Result<Self>;
+    fn flip_vertical(&self) -> io::Result<Self>;
+
+    /// Transposes without a new buffer when the grid is square; other
+    /// shapes have to be rebuilt.
+    fn transpose_in_place(&mut self) -> io::Result<()>;
+    fn flip_horizontal_in_place(&mut self) -> io::Result<()>;
+    fn flip_vertical_in_place(&mut self) -> io::Result<()>;
+
+    fn rotate_90_in_place(&mut self) -> io::Result<()> {
+        self.transpose_in_place()?;
+        self.flip_horizontal_in_place()
+    }
+
+    fn rotate_180_in_place(&mut self) -> io::Result<()> {
+        self.flip_horizontal_in_place()?;
+        self.flip_vertical_in_place()
+    }
+
+    fn rotate_270_in_place(&mut self) -> io::Result<()> {
+        self.transpose_in_place()?;
+        self.flip_vertical_in_place()
+    }
+}
+
+/// Builds a `width` x `height` grid whose cell at each position is copied
+/// from `source` at `from(position)`.
+fn remap<T: Clone>(
+    source: &Grid<T>,
+    width: usize,
+    height: usize,
+    from: impl Fn(Position) -> Position,
+) -> io::Result<Grid<T>> {
+    if source.width() == 0 || source.height() == 0 {
+        return error("Cannot transform an empty grid");
+    }
+    Ok(Grid::from_fn(width, height, |p| source[from(p)].clone()))
+}
+
+/// The width and height of rows that can be transformed, if they can.
+fn shape<T>(rows: &[Vec<T>]) -> io::Result<(usize, usize)> {
+    let width = rows.first().map_or(0, |row| row.len());
+    if width == 0 {
+        return error("Cannot transform an empty grid");
+    }
+    if let Some(r) = rows.iter().position(|row| row.len() != width) {
+        return error(&format!(
+            "Row {} has {} cells, expected {}",
+            r,
+            rows[r].len(),
+            width
+        ));
+    }
+    Ok((width, rows.len()))
+}
+
+impl<T: Clone> Transform for Grid<T> {
+    fn transpose(&self) -> io::Result<Self> {
+        remap(self, self.height(), self.width(), |(r, c)| (c, r))
+    }
+
+    fn rotate_90(&self) -> io::Result<Self> {
+        let last_row = self.height().wrapping_sub(1);
+        remap(self, self.height(), self.width(), |(r, c)| {
+            (last_row - c, r)
+        })
+    }
+
+    fn rotate_180(&self) -> io::Result<Self> {
+        let (last_row, last_col) = (self.height().wrapping_sub(1), self.width().wrapping_sub(1));
+        remap(self, self.width(), self.height(), |(r, c)| {
+            (last_row - r, last_col - c)
+        })
+    }
+
+    fn rotate_270(&self) -> io::Result<Self> {
+        let last_col = self.width().wrapping_sub(1);
+        remap(self, self.height(), self.width(), |(r, c)| {
+            (c, last_col - r)
+        })
+    }
+
+    fn flip_horizontal(&self) -> io::Result<Self> {
+        let last_col = self.width().wrapping_sub(1);
+        remap(self, self.width(), self.height(), |(r, c)| {
+            (r, last_col - c)
+        })
+    }
+
+    fn flip_vertical(&self) -> io::Result<Self> {
+        let last_row = self.height().wrapping_sub(1);
+        remap(self, self.width(), self.height(), |(r, c)| {
+            (last_row - r, c)
+        })
+    }
+
+    fn transpose_in_place(&mut self) -> io::Result<()> {
+        if self.width() != self.height() {
+            *self = self.transpose()?;
+            return Ok(());
+        }
+        if self.width() == 0 {
+            return error("Cannot transform an empty grid");
+        }
+        for r in 0..self.height() {
+            for c in r + 1..self.width() {
+                self.swap((r, c), (c, r));
+            }
+        }
+        Ok(())
+    }
+
+    fn flip_horizontal_in_place(&mut self) -> io::Result<()> {
+        if self.width() == 0 || self.height() == 0 {
+            return error("Cannot transform an empty grid");
+        }
+        for r in 0..self.height() {
+            self.row_mut(r).reverse();
+        }
+        Ok(())
+    }
+
+    fn flip_vertical_in_place(&mut self) -> io::Result<()> {
+        if self.width() == 0 || self.height() == 0 {
+            return error("Cannot transform an empty grid");
+        }
+        let last_row = self.height() - 1;
+        for r in 0..self.height() / 2 {
+            for c in 0..self.width() {
+                self.swap((r, c), (last_row - r, c));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Runs a grid transform over rows, rejecting ragged rows on the way in.
+fn via_grid<T: Clone>(
+    rows: &[Vec<T>],
+    transform: impl FnOnce(&Grid<T>) -> io::Result<Grid<T>>,
+) -> io::Result<Vec<Vec<T>>> {
+    let grid = Grid::from_rows(rows.to_vec())?;
+    Ok(transform(&grid)?.into_rows())
+}
+
+impl<T: Clone> Transform for Vec<Vec<T>> {
+    fn transpose(&self) -> io::Result<Self> {
+        via_grid(self, Grid::transpose)
+    }
+
+    fn rotate_90(&self) -> io::Result<Self> {
+        via_grid(self, Grid::rotate_90)
+    }
+
+    fn rotate_180(&self) -> io::Result<Self> {
+        via_grid(self, Grid::rotate_180)
+    }
+
+    fn rotate_270(&self) -> io::Result<Self> {
+        via_grid(self, Grid::rotate_270)
+    }
+
+    fn flip_horizontal(&self) -> io::Result<Self> {
+        via_grid(self, Grid::flip_horizontal)
+    }
+
+    fn flip_vertical(&self) -> io::Result<Self> {
+        via_grid(self, Grid::flip_vertical)
+    }
+
+    fn transpose_in_place(&mut self) -> io::Result<()> {
+        let (width, height) = shape(self)?;
+        if width != height {
+            *self = self.transpose()?;
+            return Ok(());
+        }
+        for c in 1..width {
+            // rows before `c` on one side, row `c` on the other
+            let (above, below) = self.split_at_mut(c);
+            for (r, row) in above.iter_mut().enumerate() {
+                std::mem::swap(&mut row[c], &mut below[0][r]);
+            }
+        }
+        Ok(())
+    }
+
+    fn flip_horizontal_in_place(&mut self) -> io::Result<()> {
+        shape(self)?;
+        for row in self.iter_mut() {
+            row.reverse();
+        }
+        Ok(())
+    }
+
+    fn flip_vertical_in_place(&mut self) -> io::Result<()> {
+        shape(self)?;
+        self.reverse();
+        Ok(())
+    }
+}
+
+pub fn transpose_vec_of_strings(vec_of_string: Vec<String>) -> io::Result<Vec<String>> {
     /*
        Handy if you want to read a list of string top to bottom,
        but then want to read it left to right.
@@ -23,32 +236,21 @@ pub fn transpose_vec_of_strings(vec_of_string: Vec<String>) -> Vec<String> {
     */
     let matrix: Vec<Vec<char>> = vec_of_string.iter().map(|s| s.chars().collect()).collect();
 
-    let rows = matrix.len();
-    let cols = matrix[0].len();
-
-    // Create a new 2D vector for the transposed matrix
-    let mut transposed_matrix = vec![vec![' '; rows]; cols];
-
-    // Transpose the matrix
-    for (i, row) in matrix.iter().enumerate() {
-        for (j, &value) in row.iter().enumerate() {
-            transposed_matrix[j][i] = value;
-        }
-    }
-
     // Convert the vectors back to strings
-    let transposed_strings: Vec<String> = transposed_matrix
+    let transposed_strings: Vec<String> = matrix
+        .transpose()?
         .into_iter()
         .map(|row| row.into_iter().collect())
         .collect();
 
-    transposed_strings
+    Ok(transposed_strings)
 }
 
 #[cfg(test)]
 mod tests {
 
-    use super::transpose_vec_of_strings;
+    use super::{transpose_vec_of_strings, Transform};
+    use crate::utils::grid::Grid;
 
     #[test]
     fn test_transposing() {
@@ -61,7 +263,7 @@ mod tests {
             .iter()
             .map(|s| s.to_string())
             .collect();
-        let result = transpose_vec_of_strings(input);
+        let result = transpose_vec_of_strings(input).unwrap();
         assert_eq!(result, expected);
     }
 
@@ -72,7 +274,63 @@ mod tests {
             .map(|s| s.to_string())
             .collect();
 
-        let result = transpose_vec_of_strings(transpose_vec_of_strings(input.clone()));
-        assert_eq!(result, input);
+        let result = transpose_vec_of_strings(transpose_vec_of_strings(input.clone()).unwrap());
+        assert_eq!(result.unwrap(), input);
+    }
+
+    #[test]
+    fn test_rotations_and_flips() {
+        let rows = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        assert_eq!(rows.transpose().unwrap(), [[1, 4], [2, 5], [3, 6]]);
+        assert_eq!(rows.rotate_90().unwrap(), [[4, 1], [5, 2], [6, 3]]);
+        assert_eq!(rows.rotate_180().unwrap(), [[6, 5, 4], [3, 2, 1]]);
+        assert_eq!(rows.rotate_270().unwrap(), [[3, 6], [2, 5], [1, 4]]);
+        assert_eq!(rows.flip_horizontal().unwrap(), [[3, 2, 1], [6, 5, 4]]);
+        assert_eq!(rows.flip_vertical().unwrap(), [[4, 5, 6], [1, 2, 3]]);
+
+        let grid = Grid::from_rows(rows).unwrap();
+        let mut turned = grid.clone();
+        turned.rotate_90_in_place().unwrap();
+        assert_eq!((turned.width(), turned.height()), (2, 3));
+        assert_eq!(turned, grid.rotate_270().unwrap().rotate_180().unwrap());
+        turned.rotate_270_in_place().unwrap();
+        assert_eq!(turned, grid);
+        turned.flip_horizontal_in_place().unwrap();
+        turned.flip_vertical_in_place().unwrap();
+        assert_eq!(turned, grid.rotate_180().unwrap());
+        turned.transpose_in_place().unwrap();
+        assert_eq!(turned.row(0), &[6, 3]);
+
+        // Square grids and rows never need rebuilding
+        let square = vec![vec![1, 2, 3], vec![4, 5, 6], vec![7, 8, 9]];
+        let grid = Grid::from_rows(square.clone()).unwrap();
+        let (mut rows, mut turned) = (square.clone(), grid.clone());
+        for _ in 0..4 {
+            rows.rotate_90_in_place().unwrap();
+            turned.rotate_90_in_place().unwrap();
+            assert_eq!(Grid::from_rows(rows.clone()).unwrap(), turned);
+        }
+        assert_eq!(turned, grid);
+        rows.transpose_in_place().unwrap();
+        assert_eq!(rows, [[1, 4, 7], [2, 5, 8], [3, 6, 9]]);
+        rows.rotate_270_in_place().unwrap();
+        assert_eq!(rows, square.flip_vertical().unwrap());
+        rows = vec![vec![1, 2, 3], vec![4, 5, 6]];
+        rows.rotate_180_in_place().unwrap();
+        assert_eq!(rows, [[6, 5, 4], [3, 2, 1]]);
+    }
+
+    #[test]
+    fn test_empty_and_ragged() {
+        assert!(Vec::<Vec<u8>>::new().transpose().is_err());
+        assert!(vec![Vec::<u8>::new()].rotate_90().is_err());
+        assert!(vec![vec![1, 2], vec![3]].flip_vertical().is_err());
+        assert!(Grid::new(0, 3, '.').rotate_180().is_err());
+        assert!(transpose_vec_of_strings(Vec::new()).is_err());
+        assert!(vec![vec![1, 2], vec![3]]
+            .flip_horizontal_in_place()
+            .is_err());
+        assert!(Vec::<Vec<u8>>::new().rotate_90_in_place().is_err());
+        assert!(Grid::new(3, 0, '.').flip_vertical_in_place().is_err());
     }
 }